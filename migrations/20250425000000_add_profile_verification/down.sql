@@ -0,0 +1,6 @@
+-- Remove verification status from profiles
+
+ALTER TABLE profiles
+    DROP COLUMN is_verified,
+    DROP COLUMN verified_at,
+    DROP COLUMN verified_by;
@@ -0,0 +1,7 @@
+-- Add verification status to profiles
+-- Verification is granted by platforms/authorities through on-chain ProfileVerifiedEvent
+
+ALTER TABLE profiles
+    ADD COLUMN is_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN verified_at TIMESTAMP NULL,
+    ADD COLUMN verified_by VARCHAR NULL;
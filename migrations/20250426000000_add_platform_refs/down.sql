@@ -0,0 +1,9 @@
+-- Remove numeric platform references
+
+DROP INDEX IF EXISTS idx_platform_blocked_profiles_platform_ref;
+DROP INDEX IF EXISTS idx_platform_moderators_platform_ref;
+DROP INDEX IF EXISTS idx_platform_memberships_platform_ref;
+
+ALTER TABLE platform_blocked_profiles DROP COLUMN platform_ref;
+ALTER TABLE platform_moderators DROP COLUMN platform_ref;
+ALTER TABLE platform_memberships DROP COLUMN platform_ref;
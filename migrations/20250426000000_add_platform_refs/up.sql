@@ -0,0 +1,29 @@
+-- Add a numeric reference to platforms.id on platform child tables
+-- Joins on the integer key avoid string index scans on platform_id
+
+ALTER TABLE platform_memberships
+    ADD COLUMN platform_ref INTEGER NULL REFERENCES platforms(id) ON DELETE CASCADE;
+ALTER TABLE platform_moderators
+    ADD COLUMN platform_ref INTEGER NULL REFERENCES platforms(id) ON DELETE CASCADE;
+ALTER TABLE platform_blocked_profiles
+    ADD COLUMN platform_ref INTEGER NULL REFERENCES platforms(id) ON DELETE CASCADE;
+
+-- Backfill from the existing string platform_id
+UPDATE platform_memberships AS pm
+SET platform_ref = p.id
+FROM platforms AS p
+WHERE p.platform_id = pm.platform_id;
+
+UPDATE platform_moderators AS pm
+SET platform_ref = p.id
+FROM platforms AS p
+WHERE p.platform_id = pm.platform_id;
+
+UPDATE platform_blocked_profiles AS pbp
+SET platform_ref = p.id
+FROM platforms AS p
+WHERE p.platform_id = pbp.platform_id;
+
+CREATE INDEX idx_platform_memberships_platform_ref ON platform_memberships(platform_ref);
+CREATE INDEX idx_platform_moderators_platform_ref ON platform_moderators(platform_ref);
+CREATE INDEX idx_platform_blocked_profiles_platform_ref ON platform_blocked_profiles(platform_ref);
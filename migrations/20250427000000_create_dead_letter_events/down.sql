@@ -0,0 +1,3 @@
+-- Drop dead letter events
+
+DROP TABLE IF EXISTS dead_letter_events;
@@ -0,0 +1,18 @@
+-- Events that a handler failed to process, kept for inspection and retry
+
+CREATE TABLE dead_letter_events (
+    id SERIAL PRIMARY KEY,
+    event_id VARCHAR NULL,
+    event_type VARCHAR NOT NULL,
+    worker_id VARCHAR NOT NULL,
+    event_data JSONB NOT NULL,
+    error_message TEXT NOT NULL,
+    retry_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- One row per event and worker; repeated failures bump retry_count
+CREATE UNIQUE INDEX idx_dead_letter_events_event_worker ON dead_letter_events(event_id, worker_id);
+CREATE INDEX idx_dead_letter_events_event_type ON dead_letter_events(event_type);
+CREATE INDEX idx_dead_letter_events_created_at ON dead_letter_events(created_at);
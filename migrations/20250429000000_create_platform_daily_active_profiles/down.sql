@@ -0,0 +1,3 @@
+-- Drop platform daily active profiles
+
+DROP TABLE IF EXISTS platform_daily_active_profiles;
@@ -0,0 +1,11 @@
+-- One row per profile active on a platform per day
+-- Daily active counts are read as COUNT(*) per (platform_id, date), so repeated activity counts once
+
+CREATE TABLE platform_daily_active_profiles (
+    platform_id VARCHAR NOT NULL,
+    date DATE NOT NULL,
+    profile_id VARCHAR NOT NULL,
+    PRIMARY KEY (platform_id, date, profile_id)
+);
+
+CREATE INDEX idx_platform_daily_active_profiles_date ON platform_daily_active_profiles(date);
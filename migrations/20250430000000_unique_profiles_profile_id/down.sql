@@ -0,0 +1,3 @@
+-- Remove the unique profile_id constraint (deleted duplicates are not restored)
+
+DROP INDEX IF EXISTS idx_profiles_profile_id_unique;
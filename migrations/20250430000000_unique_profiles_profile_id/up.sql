@@ -0,0 +1,20 @@
+-- Make profile_id the canonical upsert key for profiles
+-- Earlier upserts keyed on username or id could leave several rows for one on-chain profile;
+-- keep the most recently updated row for each profile_id before adding the constraint
+
+DELETE FROM profiles
+WHERE id IN (
+    SELECT id
+    FROM (
+        SELECT id,
+               ROW_NUMBER() OVER (
+                   PARTITION BY profile_id
+                   ORDER BY updated_at DESC, id DESC
+               ) AS row_num
+        FROM profiles
+        WHERE profile_id IS NOT NULL
+    ) ranked
+    WHERE ranked.row_num > 1
+);
+
+CREATE UNIQUE INDEX idx_profiles_profile_id_unique ON profiles(profile_id);
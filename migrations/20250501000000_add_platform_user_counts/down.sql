@@ -0,0 +1,4 @@
+-- Drop platform member counts
+
+ALTER TABLE platforms DROP COLUMN IF EXISTS active_users_count;
+ALTER TABLE platforms DROP COLUMN IF EXISTS total_users_count;
@@ -0,0 +1,14 @@
+-- Member counts on platforms, kept in line with platform_memberships by the reconciliation task
+-- active_users_count counts distinct profiles active on the platform in the last 30 days
+
+ALTER TABLE platforms ADD COLUMN total_users_count BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE platforms ADD COLUMN active_users_count BIGINT NOT NULL DEFAULT 0;
+
+UPDATE platforms p
+SET total_users_count = (
+        SELECT COUNT(*) FROM platform_memberships m WHERE m.platform_id = p.platform_id
+    ),
+    active_users_count = (
+        SELECT COUNT(DISTINCT a.profile_id) FROM platform_daily_active_profiles a
+        WHERE a.platform_id = p.platform_id AND a.date > CURRENT_DATE - 30
+    );
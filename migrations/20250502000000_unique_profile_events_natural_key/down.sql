@@ -0,0 +1,3 @@
+-- Drop the profile history natural key
+
+DROP INDEX IF EXISTS idx_profile_events_natural_key;
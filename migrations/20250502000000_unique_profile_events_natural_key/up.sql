@@ -0,0 +1,12 @@
+-- Make profile history idempotent: one row per (profile_id, event_type, event_id)
+-- Rows without an event_id are left alone since they can't be matched
+
+DELETE FROM profile_events a
+USING profile_events b
+WHERE a.profile_id = b.profile_id
+  AND a.event_type = b.event_type
+  AND a.event_id = b.event_id
+  AND a.id > b.id;
+
+CREATE UNIQUE INDEX idx_profile_events_natural_key
+    ON profile_events (profile_id, event_type, event_id);
@@ -0,0 +1,4 @@
+-- Drop follow-time composite indexes
+
+DROP INDEX IF EXISTS idx_social_graph_relationships_follower_created_at;
+DROP INDEX IF EXISTS idx_social_graph_relationships_following_created_at;
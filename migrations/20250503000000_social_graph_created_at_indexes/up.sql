@@ -0,0 +1,7 @@
+-- Composite indexes for follower/following lists, which filter on one side of the
+-- relationship and page by follow time (newest first, id as tie-breaker)
+
+CREATE INDEX idx_social_graph_relationships_following_created_at
+    ON social_graph_relationships (following_address, created_at DESC, id DESC);
+CREATE INDEX idx_social_graph_relationships_follower_created_at
+    ON social_graph_relationships (follower_address, created_at DESC, id DESC);
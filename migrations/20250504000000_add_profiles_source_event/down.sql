@@ -0,0 +1,3 @@
+-- Drop profile source events
+
+ALTER TABLE profiles DROP COLUMN IF EXISTS source_event;
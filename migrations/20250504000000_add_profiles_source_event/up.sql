@@ -0,0 +1,4 @@
+-- Raw ProfileCreatedEvent/ProfileUpdatedEvent JSON that last wrote each profile
+-- Only populated when STORE_SOURCE_EVENTS is enabled
+
+ALTER TABLE profiles ADD COLUMN source_event JSONB;
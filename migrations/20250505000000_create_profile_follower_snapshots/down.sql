@@ -0,0 +1,3 @@
+-- Drop profile follower snapshots
+
+DROP TABLE IF EXISTS profile_follower_snapshots;
@@ -0,0 +1,12 @@
+-- Follower and following counts per profile per day, for charting growth
+-- The snapshot task rewrites today's row on every pass, so each day keeps its last count
+
+CREATE TABLE profile_follower_snapshots (
+    profile_id VARCHAR NOT NULL,
+    date DATE NOT NULL,
+    followers_count INTEGER NOT NULL,
+    following_count INTEGER NOT NULL,
+    PRIMARY KEY (profile_id, date)
+);
+
+CREATE INDEX idx_profile_follower_snapshots_date ON profile_follower_snapshots(date);
@@ -0,0 +1,4 @@
+-- Drop profile photo flags
+
+ALTER TABLE profiles DROP COLUMN IF EXISTS has_cover_photo;
+ALTER TABLE profiles DROP COLUMN IF EXISTS has_profile_photo;
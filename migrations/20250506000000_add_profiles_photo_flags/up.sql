@@ -0,0 +1,9 @@
+-- Whether the profile has a profile/cover photo, as flagged by the event
+-- Set even when the URL hasn't been indexed yet, so "photo pending" differs from "no photo"
+
+ALTER TABLE profiles ADD COLUMN has_profile_photo BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE profiles ADD COLUMN has_cover_photo BOOLEAN NOT NULL DEFAULT FALSE;
+
+UPDATE profiles
+SET has_profile_photo = COALESCE(profile_photo, '') <> '',
+    has_cover_photo = COALESCE(cover_photo, '') <> '';
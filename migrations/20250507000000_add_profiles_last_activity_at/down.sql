@@ -0,0 +1,4 @@
+-- Drop profile activity tracking
+
+DROP INDEX IF EXISTS idx_profiles_last_activity_at;
+ALTER TABLE profiles DROP COLUMN IF EXISTS last_activity_at;
@@ -0,0 +1,7 @@
+-- Last time the profile did something on chain (profile change, follow/unfollow, platform join)
+
+ALTER TABLE profiles ADD COLUMN last_activity_at TIMESTAMP;
+
+UPDATE profiles SET last_activity_at = updated_at;
+
+CREATE INDEX IF NOT EXISTS idx_profiles_last_activity_at ON profiles(last_activity_at DESC);
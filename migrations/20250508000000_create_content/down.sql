@@ -0,0 +1,3 @@
+-- Drop content
+
+DROP TABLE IF EXISTS content;
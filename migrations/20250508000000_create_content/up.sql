@@ -0,0 +1,19 @@
+-- Content posted on platforms, with counters maintained from interaction events
+-- `id` is the on-chain content id; replies point at their parent through `parent_id`
+
+CREATE TABLE content (
+    id VARCHAR PRIMARY KEY,
+    creator_id VARCHAR NOT NULL,
+    platform_id VARCHAR NOT NULL,
+    parent_id VARCHAR,
+    like_count INTEGER NOT NULL DEFAULT 0,
+    view_count INTEGER NOT NULL DEFAULT 0,
+    share_count INTEGER NOT NULL DEFAULT 0,
+    comment_count INTEGER NOT NULL DEFAULT 0,
+    has_ip_registered BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX idx_content_creator_id ON content(creator_id);
+CREATE INDEX idx_content_platform_id ON content(platform_id);
+CREATE INDEX idx_content_parent_id ON content(parent_id) WHERE parent_id IS NOT NULL;
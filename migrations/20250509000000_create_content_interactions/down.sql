@@ -0,0 +1,3 @@
+-- Drop content_interactions
+
+DROP TABLE IF EXISTS content_interactions;
@@ -0,0 +1,12 @@
+-- One row per profile, content and interaction type; content counters count these rows
+-- Rows may arrive before their content, which then takes its counters from them
+
+CREATE TABLE content_interactions (
+    content_id VARCHAR NOT NULL,
+    profile_id VARCHAR NOT NULL,
+    interaction_type VARCHAR NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (content_id, profile_id, interaction_type)
+);
+
+CREATE INDEX idx_content_interactions_profile_id ON content_interactions(profile_id);
@@ -0,0 +1,3 @@
+-- Drop ip_registrations
+
+DROP TABLE IF EXISTS ip_registrations;
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- IP registrations, kept so content indexed after its IP can still be flagged
+
+CREATE TABLE ip_registrations (
+    ip_id VARCHAR PRIMARY KEY,
+    registered_at TIMESTAMP NOT NULL
+);
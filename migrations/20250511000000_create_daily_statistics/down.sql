@@ -0,0 +1,4 @@
+-- Drop daily statistics
+
+DROP TABLE IF EXISTS platform_daily_statistics;
+DROP TABLE IF EXISTS daily_statistics;
@@ -0,0 +1,26 @@
+-- Daily aggregates, derived from the source tables by POST /admin/recompute-stats
+-- Recomputing a range replaces every row in it, so a bad row can always be rebuilt
+
+CREATE TABLE daily_statistics (
+    date DATE PRIMARY KEY,
+    new_profiles_count BIGINT NOT NULL DEFAULT 0,
+    active_profiles_count BIGINT NOT NULL DEFAULT 0,
+    new_content_count BIGINT NOT NULL DEFAULT 0,
+    total_interactions_count BIGINT NOT NULL DEFAULT 0,
+    new_follows_count BIGINT NOT NULL DEFAULT 0,
+    new_ip_registrations_count BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE platform_daily_statistics (
+    platform_id VARCHAR NOT NULL,
+    date DATE NOT NULL,
+    new_users_count BIGINT NOT NULL DEFAULT 0,
+    active_users_count BIGINT NOT NULL DEFAULT 0,
+    content_created_count BIGINT NOT NULL DEFAULT 0,
+    total_interactions_count BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (platform_id, date)
+);
+
+CREATE INDEX idx_platform_daily_statistics_date ON platform_daily_statistics(date);
@@ -0,0 +1,274 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::str::FromStr;
+
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use base64::Engine;
+use fastcrypto::traits::ToFromBytes;
+use mys_types::base_types::MysAddress;
+use mys_types::crypto::{MysSignature, Signature};
+use shared_crypto::intent::{Intent, IntentMessage, PersonalMessage};
+
+use crate::config::ApiConfig;
+
+/// Header that can carry the admin token instead of `Authorization: Bearer <token>`
+pub const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+/// Base64 signature (flag || signature || public key) over the owner message
+pub const OWNER_SIGNATURE_HEADER: &str = "x-owner-signature";
+/// Unix seconds included in the signed owner message
+pub const OWNER_TIMESTAMP_HEADER: &str = "x-owner-timestamp";
+/// How far an owner signature's timestamp may be from now, in seconds
+pub const OWNER_SIGNATURE_MAX_AGE_SECS: i64 = 300;
+
+/// Check that the request carries the configured admin token
+pub fn require_admin(
+    headers: &HeaderMap,
+    api_config: &ApiConfig,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    // Admin endpoints are disabled entirely when no token is configured
+    let expected = match &api_config.admin_token {
+        Some(token) => token,
+        None => {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(serde_json::json!({
+                    "error": "Admin endpoints are disabled (ADMIN_TOKEN not set)"
+                }))
+            ))
+        }
+    };
+    
+    match provided_admin_token(headers) {
+        Some(token) if tokens_match(token, expected) => Ok(()),
+        _ => Err((
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({
+                "error": "Invalid or missing admin token"
+            }))
+        )),
+    }
+}
+
+/// Whether the request carries the configured admin token
+pub fn is_admin(headers: &HeaderMap, api_config: &ApiConfig) -> bool {
+    match (&api_config.admin_token, provided_admin_token(headers)) {
+        (Some(expected), Some(token)) => tokens_match(token, expected),
+        _ => false,
+    }
+}
+
+/// Whether unapproved platforms should be left out of this request's results
+///
+/// True when `HIDE_UNAPPROVED_PLATFORMS` is set, unless the request carries the admin token.
+pub fn hide_unapproved_platforms(headers: &HeaderMap, api_config: &ApiConfig) -> bool {
+    api_config.hide_unapproved_platforms && !is_admin(headers, api_config)
+}
+
+/// Compare a provided token with the expected one in time independent of where they differ
+///
+/// Only the length can be learned from timing, not how much of a guess was right.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let (provided, expected) = (provided.as_bytes(), expected.as_bytes());
+    provided.len() == expected.len()
+        && provided.iter().zip(expected).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// The admin token sent as a bearer token or in the dedicated header
+fn provided_admin_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or_else(|| headers.get(ADMIN_TOKEN_HEADER).and_then(|v| v.to_str().ok()))
+}
+
+/// Message a profile owner signs (as a personal message) to read the profile's sensitive fields
+pub fn owner_access_message(profile_id: &str, timestamp: i64) -> String {
+    format!("mysocial-sensitive:{}:{}", profile_id, timestamp)
+}
+
+/// Who was let through by `require_owner_or_admin`, for the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accessor {
+    Admin,
+    Owner,
+}
+
+/// Check that the request carries the admin token or a fresh signature from `owner_address`
+///
+/// The owner signs `owner_access_message(profile_id, timestamp)` and sends the signature and
+/// timestamp in `X-Owner-Signature` / `X-Owner-Timestamp`. Signatures older than
+/// `OWNER_SIGNATURE_MAX_AGE_SECS` are rejected so a captured header can't be replayed forever.
+/// `owner_address` is `None` when the profile isn't indexed, which only the admin can get past.
+pub fn require_owner_or_admin(
+    headers: &HeaderMap,
+    api_config: &ApiConfig,
+    profile_id: &str,
+    owner_address: Option<&str>,
+) -> Result<Accessor, (StatusCode, Json<serde_json::Value>)> {
+    if is_admin(headers, api_config) {
+        return Ok(Accessor::Admin);
+    }
+    
+    match owner_address {
+        Some(owner) if owner_signature_valid(headers, profile_id, owner) => Ok(Accessor::Owner),
+        _ => Err((
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({
+                "error": "A valid owner signature or admin token is required"
+            }))
+        )),
+    }
+}
+
+/// Whether the owner signature headers hold a fresh signature by `owner` for `profile_id`
+fn owner_signature_valid(headers: &HeaderMap, profile_id: &str, owner: &str) -> bool {
+    let header_str = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let (Some(encoded), Some(timestamp)) = (header_str(OWNER_SIGNATURE_HEADER), header_str(OWNER_TIMESTAMP_HEADER)) else {
+        return false;
+    };
+    let Ok(timestamp) = timestamp.parse::<i64>() else {
+        return false;
+    };
+    if (chrono::Utc::now().timestamp() - timestamp).abs() > OWNER_SIGNATURE_MAX_AGE_SECS {
+        return false;
+    }
+    
+    let signature = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| Signature::from_bytes(&bytes).ok());
+    let (Some(signature), Ok(owner)) = (signature, MysAddress::from_str(owner)) else {
+        return false;
+    };
+    
+    let message = IntentMessage::new(
+        Intent::personal_message(),
+        PersonalMessage { message: owner_access_message(profile_id, timestamp).into_bytes() },
+    );
+    signature.verify_secure(&message, owner, signature.scheme()).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use mys_types::crypto::{get_key_pair, AccountKeyPair};
+
+    fn api_config(admin_token: Option<&str>) -> ApiConfig {
+        ApiConfig {
+            admin_token: admin_token.map(str::to_string),
+            max_page_size: 100,
+            recent_profiles_default: 10,
+            ipfs_gateway: String::new(),
+            arweave_gateway: String::new(),
+            resolve_media_default: false,
+            max_request_body_bytes: 1024,
+            pretty_json_default: false,
+            hide_unapproved_platforms: false,
+        }
+    }
+
+    fn headers(pairs: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    /// Owner headers signed by `keypair` over `profile_id` at `timestamp`
+    fn owner_headers(keypair: &AccountKeyPair, profile_id: &str, timestamp: i64) -> HeaderMap {
+        let message = IntentMessage::new(
+            Intent::personal_message(),
+            PersonalMessage { message: owner_access_message(profile_id, timestamp).into_bytes() },
+        );
+        let signature = Signature::new_secure(&message, keypair);
+        headers(&[
+            (OWNER_SIGNATURE_HEADER, base64::engine::general_purpose::STANDARD.encode(signature.as_ref())),
+            (OWNER_TIMESTAMP_HEADER, timestamp.to_string()),
+        ])
+    }
+
+    #[test]
+    fn owner_message_names_the_profile_and_time() {
+        assert_eq!(owner_access_message("0xprofile", 1_700_000_000), "mysocial-sensitive:0xprofile:1700000000");
+    }
+
+    #[test]
+    fn fresh_owner_signature_is_accepted() {
+        let (owner, keypair): (MysAddress, AccountKeyPair) = get_key_pair();
+        let headers = owner_headers(&keypair, "0xprofile", chrono::Utc::now().timestamp());
+        
+        assert!(owner_signature_valid(&headers, "0xprofile", &owner.to_string()));
+        assert_eq!(
+            require_owner_or_admin(&headers, &api_config(None), "0xprofile", Some(&owner.to_string())).unwrap(),
+            Accessor::Owner
+        );
+    }
+
+    #[test]
+    fn signature_by_someone_else_is_rejected() {
+        let (owner, _): (MysAddress, AccountKeyPair) = get_key_pair();
+        let (_, other): (MysAddress, AccountKeyPair) = get_key_pair();
+        let headers = owner_headers(&other, "0xprofile", chrono::Utc::now().timestamp());
+        
+        assert!(!owner_signature_valid(&headers, "0xprofile", &owner.to_string()));
+        let (status, _) = require_owner_or_admin(&headers, &api_config(None), "0xprofile", Some(&owner.to_string())).unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn signature_for_another_profile_or_time_is_rejected() {
+        let (owner, keypair): (MysAddress, AccountKeyPair) = get_key_pair();
+        let now = chrono::Utc::now().timestamp();
+        
+        let other_profile = owner_headers(&keypair, "0xother", now);
+        assert!(!owner_signature_valid(&other_profile, "0xprofile", &owner.to_string()));
+        
+        let stale = owner_headers(&keypair, "0xprofile", now - OWNER_SIGNATURE_MAX_AGE_SECS - 60);
+        assert!(!owner_signature_valid(&stale, "0xprofile", &owner.to_string()));
+    }
+
+    #[test]
+    fn unindexed_profile_needs_the_admin_token() {
+        let (_, keypair): (MysAddress, AccountKeyPair) = get_key_pair();
+        let headers = owner_headers(&keypair, "0xprofile", chrono::Utc::now().timestamp());
+        
+        assert!(require_owner_or_admin(&headers, &api_config(Some("secret")), "0xprofile", None).is_err());
+        
+        let admin = headers_with_token("secret");
+        assert_eq!(
+            require_owner_or_admin(&admin, &api_config(Some("secret")), "0xprofile", None).unwrap(),
+            Accessor::Admin
+        );
+    }
+
+    fn headers_with_token(token: &str) -> HeaderMap {
+        headers(&[("authorization", format!("Bearer {}", token))])
+    }
+
+    #[test]
+    fn admin_token_must_match_exactly() {
+        let config = api_config(Some("secret"));
+        
+        assert!(require_admin(&headers_with_token("secret"), &config).is_ok());
+        assert!(require_admin(&headers(&[(ADMIN_TOKEN_HEADER, "secret".to_string())]), &config).is_ok());
+        assert_eq!(require_admin(&headers_with_token("secreT"), &config).unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert_eq!(require_admin(&headers_with_token("secret2"), &config).unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert_eq!(require_admin(&HeaderMap::new(), &config).unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert_eq!(require_admin(&headers_with_token("secret"), &api_config(None)).unwrap_err().0, StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn tokens_match_only_when_equal() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secret", "secreT"));
+        assert!(!tokens_match("secret", "secre"));
+        assert!(!tokens_match("", "secret"));
+    }
+}
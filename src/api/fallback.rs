@@ -0,0 +1,176 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::any::Any;
+
+use axum::{
+    http::{header, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// JSON 404 for paths that match no route
+pub async fn not_found(uri: Uri) -> impl IntoResponse {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "error": format!("No route for {}", uri.path())
+        }))
+    )
+}
+
+/// Replace axum's empty 405 body with a JSON error, keeping the `Allow` header it computed
+pub async fn method_not_allowed_json(response: Response) -> Response {
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+        return response;
+    }
+    
+    let allow = response.headers().get(header::ALLOW).cloned();
+    let allowed: Vec<String> = allow
+        .as_ref()
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').map(|m| m.trim().to_string()).filter(|m| !m.is_empty()).collect())
+        .unwrap_or_default();
+    
+    let mut json_response = (
+        StatusCode::METHOD_NOT_ALLOWED,
+        Json(serde_json::json!({
+            "error": "Method not allowed",
+            "allowed_methods": allowed
+        }))
+    ).into_response();
+    
+    if let Some(allow) = allow {
+        json_response.headers_mut().insert(header::ALLOW, allow);
+    }
+    
+    json_response
+}
+
+/// Replace the plain-text 413 from the body limit layer with a JSON error
+pub async fn payload_too_large_json(response: Response) -> Response {
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE {
+        return response;
+    }
+    
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(serde_json::json!({
+            "error": "Request body too large"
+        }))
+    ).into_response()
+}
+
+/// JSON 500 for a handler that panicked, logged inside the request's span so it carries the request id
+///
+/// The panic message is only logged; clients get a generic error.
+pub fn panic_response(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let message = panic
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| panic.downcast_ref::<&str>().copied())
+        .unwrap_or("unknown panic payload");
+    tracing::error!("Handler panicked: {}", message);
+    
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(serde_json::json!({
+            "error": "Internal server error"
+        }))
+    ).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    /// One GET route behind the same fallback and 405 layers as the real router
+    fn app() -> Router {
+        Router::new()
+            .route("/profiles", get(|| async { "ok" }))
+            .fallback(not_found)
+            .layer(middleware::map_response(method_not_allowed_json))
+    }
+
+    async fn call(app: Router, method: &str, path: &str) -> Response {
+        let request = Request::builder().method(method).uri(path).body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap()
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn wrong_method_gets_a_json_405_that_keeps_the_allow_header() {
+        let response = call(app(), "POST", "/profiles").await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        
+        let allow = response.headers().get(header::ALLOW).unwrap().to_str().unwrap().to_string();
+        assert!(allow.contains("GET"), "Allow header was {allow}");
+        
+        let body = json_body(response).await;
+        assert_eq!(body["error"], "Method not allowed");
+        let allowed: Vec<&str> = body["allowed_methods"].as_array().unwrap().iter().map(|m| m.as_str().unwrap()).collect();
+        assert!(allowed.contains(&"GET"));
+        assert_eq!(allowed.len(), allow.split(',').count());
+    }
+
+    #[tokio::test]
+    async fn unknown_path_gets_a_json_404() {
+        let response = call(app(), "GET", "/nope").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(json_body(response).await["error"], "No route for /nope");
+    }
+
+    #[tokio::test]
+    async fn a_panicking_handler_gets_a_json_500() {
+        let app = Router::new()
+            .route("/panic", get(|| async { panic!("unexpected row shape") }))
+            .layer(tower_http::catch_panic::CatchPanicLayer::custom(panic_response));
+        
+        let response = call(app, "GET", "/panic").await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        // The panic message is only logged
+        assert_eq!(json_body(response).await, serde_json::json!({ "error": "Internal server error" }));
+    }
+
+    #[test]
+    fn panic_response_accepts_string_and_str_payloads() {
+        for payload in [Box::new("static message") as Box<dyn Any + Send>, Box::new(String::from("formatted")), Box::new(42)] {
+            assert_eq!(panic_response(payload).status(), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    #[tokio::test]
+    async fn an_oversized_body_gets_a_json_413() {
+        let app = || Router::new()
+            .route("/admin/batch", axum::routing::post(|body: String| async move { body.len().to_string() }))
+            .layer(axum::extract::DefaultBodyLimit::disable())
+            .layer(tower_http::limit::RequestBodyLimitLayer::new(16))
+            .layer(middleware::map_response(payload_too_large_json));
+        let post = |body: &str| Request::builder()
+            .method("POST")
+            .uri("/admin/batch")
+            .header(header::CONTENT_LENGTH, body.len())
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        
+        let response = app().oneshot(post(&"x".repeat(64))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(json_body(response).await, serde_json::json!({ "error": "Request body too large" }));
+        
+        let response = app().oneshot(post("small")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn other_responses_pass_through_untouched() {
+        let response = call(app(), "GET", "/profiles").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::ALLOW).is_none());
+    }
+}
@@ -0,0 +1,505 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Extension,
+    Json,
+};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+use crate::api::auth::require_admin;
+use crate::blockchain::recompute_daily_statistics;
+use crate::config::ApiConfig;
+use crate::db::DbPool;
+use crate::models::DeadLetterEvent;
+use crate::schema::dead_letter_events;
+use crate::api::params::page_offset;
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterQuery {
+    /// Only return failures for event types containing this value (e.g. `FollowEvent`)
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub page: Option<i64>,
+}
+
+/// List events that failed processing, newest first
+pub async fn list_dead_letters(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    Query(query): Query<DeadLetterQuery>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&headers, &api_config) {
+        return rejection;
+    }
+    
+    let limit = api_config.page_size(query.limit, 50);
+    let offset = query.offset.unwrap_or(0);
+    let page = query.page.unwrap_or(1);
+    
+    // If page is provided, calculate the offset
+    let offset = page_offset(page, limit, offset);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let type_pattern = query.event_type
+        .filter(|event_type| !event_type.is_empty())
+        .map(|event_type| format!("%{}%", event_type));
+    
+    let mut count_query = dead_letter_events::table.into_boxed();
+    let mut list_query = dead_letter_events::table.into_boxed();
+    if let Some(pattern) = &type_pattern {
+        count_query = count_query.filter(dead_letter_events::event_type.like(pattern.clone()));
+        list_query = list_query.filter(dead_letter_events::event_type.like(pattern.clone()));
+    }
+    
+    let total = match count_query.count().get_result::<i64>(&mut conn).await {
+        Ok(count) => count,
+        Err(e) => {
+            error!("Failed to count dead letters: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to count dead letters: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let result = list_query
+        .order_by((dead_letter_events::created_at.desc(), dead_letter_events::id.desc()))
+        .limit(limit)
+        .offset(offset)
+        .load::<DeadLetterEvent>(&mut conn)
+        .await;
+        
+    match result {
+        Ok(dead_letters) => {
+            let total_pages = (total as f64 / limit as f64).ceil() as i64;
+            (StatusCode::OK, Json(serde_json::json!({
+                "dead_letters": dead_letters,
+                "pagination": {
+                    "total": total,
+                    "limit": limit,
+                    "offset": offset,
+                    "page": page,
+                    "total_pages": total_pages
+                }
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch dead letters: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch dead letters: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Discard a dead letter
+pub async fn delete_dead_letter(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    Path(id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&headers, &api_config) {
+        return rejection;
+    }
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    match diesel::delete(dead_letter_events::table.find(id))
+        .execute(&mut conn)
+        .await
+    {
+        Ok(0) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Dead letter not found"
+            }))
+        ),
+        Ok(_) => {
+            info!("Discarded dead letter {}", id);
+            (StatusCode::OK, Json(serde_json::json!({
+                "deleted": id
+            })))
+        },
+        Err(e) => {
+            error!("Failed to delete dead letter {}: {}", id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to delete dead letter: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConsistencyQuery {
+    /// Number of profiles to sample (default 100, capped by MAX_PAGE_SIZE)
+    pub limit: Option<i64>,
+    /// Check only this profile instead of a random sample, e.g. to follow up on an alert
+    pub profile_id: Option<String>,
+}
+
+/// Stored and live follow counts for one sampled profile
+#[derive(Debug, QueryableByName, Serialize)]
+struct FollowerCountCheck {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    profile_id: String,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    stored_followers: i32,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    actual_followers: i64,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    stored_following: i32,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    actual_following: i64,
+}
+
+impl FollowerCountCheck {
+    fn is_consistent(&self) -> bool {
+        self.stored_followers as i64 == self.actual_followers
+            && self.stored_following as i64 == self.actual_following
+    }
+}
+
+/// Compare stored follower/following counts with the relationships table for a random sample
+///
+/// Returns the sampled profiles whose counts disagree, for alerting on drift. With `profile_id`
+/// only that profile is checked.
+pub async fn check_follower_counts(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    Query(query): Query<ConsistencyQuery>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&headers, &api_config) {
+        return rejection;
+    }
+    
+    let limit = api_config.page_size(query.limit, 100);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let result = diesel::sql_query(
+        "SELECT p.profile_id,
+                p.followers_count AS stored_followers,
+                (SELECT COUNT(*) FROM social_graph_relationships r WHERE r.following_address = p.profile_id) AS actual_followers,
+                p.following_count AS stored_following,
+                (SELECT COUNT(*) FROM social_graph_relationships r WHERE r.follower_address = p.profile_id) AS actual_following
+         FROM (
+             SELECT profile_id, followers_count, following_count FROM profiles
+             WHERE profile_id IS NOT NULL AND ($2::text IS NULL OR profile_id = $2)
+             ORDER BY random()
+             LIMIT $1
+         ) p"
+    )
+    .bind::<diesel::sql_types::BigInt, _>(limit)
+    .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(query.profile_id.as_deref())
+    .load::<FollowerCountCheck>(&mut conn)
+    .await;
+    
+    match result {
+        Ok(checks) => {
+            let sampled = checks.len();
+            let mismatches: Vec<FollowerCountCheck> = checks
+                .into_iter()
+                .filter(|check| !check.is_consistent())
+                .collect();
+            if !mismatches.is_empty() {
+                warn!("Follower count drift in {} of {} sampled profiles", mismatches.len(), sampled);
+            }
+            (StatusCode::OK, Json(serde_json::json!({
+                "sampled": sampled,
+                "mismatch_count": mismatches.len(),
+                "mismatches": mismatches
+            })))
+        },
+        Err(e) => {
+            error!("Failed to check follower counts: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to check follower counts: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Day range for `recompute_stats`, inclusive at both ends
+#[derive(Debug, Deserialize)]
+pub struct RecomputeStatsQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Rebuild the daily statistics for `from..=to` from the source tables
+///
+/// For recovering from a counting bug without a full reindex; the rows in the range are
+/// replaced in one transaction.
+pub async fn recompute_stats(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    Query(query): Query<RecomputeStatsQuery>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&headers, &api_config) {
+        return rejection;
+    }
+    
+    if query.to < query.from {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "to must not be before from"
+            }))
+        )
+    }
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    match recompute_daily_statistics(&mut conn, query.from, query.to).await {
+        Ok(written) => {
+            info!(
+                "Recomputed daily statistics for {}..={}: {} daily rows, {} platform rows",
+                query.from, query.to, written.daily_rows, written.platform_rows
+            );
+            (StatusCode::OK, Json(serde_json::json!({
+                "from": query.from,
+                "to": query.to,
+                "daily_rows": written.daily_rows,
+                "platform_rows": written.platform_rows
+            })))
+        },
+        Err(e) => {
+            error!("Failed to recompute daily statistics: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to recompute daily statistics: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{header, HeaderValue};
+    use crate::db::test_support::{insert_profile, test_database, unique_id};
+    use crate::schema::social_graph_relationships;
+
+    fn api_config() -> ApiConfig {
+        ApiConfig {
+            admin_token: Some("secret".to_string()),
+            max_page_size: 100,
+            recent_profiles_default: 10,
+            ipfs_gateway: String::new(),
+            arweave_gateway: String::new(),
+            resolve_media_default: false,
+            max_request_body_bytes: 1024,
+            pretty_json_default: false,
+            hide_unapproved_platforms: false,
+        }
+    }
+
+    async fn check(pool: DbPool, profile_id: &str) -> serde_json::Value {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        let query = ConsistencyQuery { limit: None, profile_id: Some(profile_id.to_string()) };
+        let response = check_follower_counts(State(pool), Extension(api_config()), headers, Query(query))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn seeded_mismatch_is_reported() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (profile, follower) = (unique_id("profile"), unique_id("profile"));
+        {
+            // The relationship is written without touching the stored counters, which stay at 0
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &profile, &unique_id("user")).await;
+            diesel::insert_into(social_graph_relationships::table)
+                .values((
+                    social_graph_relationships::follower_address.eq(&follower),
+                    social_graph_relationships::following_address.eq(&profile),
+                    social_graph_relationships::created_at.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        
+        let body = check(pool, &profile).await;
+        
+        assert_eq!(body["sampled"], 1);
+        assert_eq!(body["mismatch_count"], 1);
+        assert_eq!(body["mismatches"][0]["profile_id"], profile);
+        assert_eq!(body["mismatches"][0]["stored_followers"], 0);
+        assert_eq!(body["mismatches"][0]["actual_followers"], 1);
+    }
+
+    #[tokio::test]
+    async fn recompute_rejects_a_backwards_range() {
+        let Some(db) = test_database().await else { return };
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        let query = RecomputeStatsQuery {
+            from: NaiveDate::from_ymd_opt(2024, 5, 2).unwrap(),
+            to: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+        };
+        
+        let response = recompute_stats(State(db.pool.as_ref().clone()), Extension(api_config()), headers, Query(query))
+            .await
+            .into_response();
+        
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn consistent_profile_is_not_reported() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let profile = unique_id("profile");
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &profile, &unique_id("user")).await;
+        }
+        
+        let body = check(pool, &profile).await;
+        
+        assert_eq!(body["sampled"], 1);
+        assert_eq!(body["mismatch_count"], 0);
+    }
+
+    fn admin_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        headers
+    }
+
+    async fn dead_letters_of_type(pool: DbPool, headers: HeaderMap, event_type: &str) -> (StatusCode, serde_json::Value) {
+        let query = DeadLetterQuery { event_type: Some(event_type.to_string()), limit: None, offset: None, page: None };
+        let response = list_dead_letters(State(pool), Extension(api_config()), headers, Query(query))
+            .await
+            .into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn dead_letters_are_listed_and_discarded() {
+        use crate::models::dead_letter::NewDeadLetterEvent;
+        
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let event_type = format!("0x1::social_graph::{}", unique_id("FailedEvent"));
+        let ids: Vec<i32> = {
+            let mut conn = pool.get().await.unwrap();
+            let mut ids = Vec::new();
+            for n in 0..2 {
+                let now = chrono::Utc::now().naive_utc();
+                let id = diesel::insert_into(dead_letter_events::table)
+                    .values(&NewDeadLetterEvent {
+                        event_id: Some(unique_id("tx")),
+                        event_type: event_type.clone(),
+                        worker_id: "social-graph".to_string(),
+                        event_data: serde_json::json!({ "n": n }),
+                        error_message: format!("failure {}", n),
+                        created_at: now,
+                        updated_at: now,
+                    })
+                    .returning(dead_letter_events::id)
+                    .get_result(&mut conn)
+                    .await
+                    .unwrap();
+                ids.push(id);
+            }
+            ids
+        };
+        
+        let (status, _) = dead_letters_of_type(pool.clone(), HeaderMap::new(), &event_type).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        
+        let (status, body) = dead_letters_of_type(pool.clone(), admin_headers(), &event_type).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["pagination"]["total"], 2);
+        // Newest first, with the error and retry count
+        assert_eq!(body["dead_letters"][0]["id"], ids[1]);
+        assert_eq!(body["dead_letters"][0]["error_message"], "failure 1");
+        assert_eq!(body["dead_letters"][0]["retry_count"], 0);
+        
+        let delete = |id: i32| delete_dead_letter(State(pool.clone()), Extension(api_config()), admin_headers(), Path(id));
+        assert_eq!(delete(ids[0]).await.into_response().status(), StatusCode::OK);
+        assert_eq!(delete(ids[0]).await.into_response().status(), StatusCode::NOT_FOUND);
+        
+        let (_, body) = dead_letters_of_type(pool, admin_headers(), &event_type).await;
+        assert_eq!(body["pagination"]["total"], 1);
+        assert_eq!(body["dead_letters"][0]["id"], ids[1]);
+    }
+}
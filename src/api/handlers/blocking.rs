@@ -4,16 +4,31 @@
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::Json;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
 use serde::Serialize;
-use tracing::debug;
+use tracing::{debug, error};
 
+use crate::api::params::{ListParams, SortOrder};
 use crate::db::DbPool;
+use crate::schema::{profiles, profiles_blocked};
+
+/// Pagination details for list responses
+#[derive(Debug, Serialize)]
+pub struct Pagination {
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub page: i64,
+    pub total_pages: i64,
+}
 
 /// Response type for blocked profiles list
 #[derive(Debug, Serialize)]
 pub struct BlockedProfilesResponse {
     pub blocked_profiles: Vec<ProfileBlockInfo>,
     pub total: i64,
+    pub pagination: Pagination,
 }
 
 /// Profile block information
@@ -44,19 +59,86 @@ pub struct BlockCheckResponse {
 }
 
 /// Get profiles blocked by a user
+///
+/// Sorts by `created_at` (default) or `blocked_address`.
 pub async fn get_blocked_profiles(
     Path(profile_id): Path<String>,
-    State(_pool): State<DbPool>,
+    State(pool): State<DbPool>,
+    params: ListParams,
 ) -> Result<Json<BlockedProfilesResponse>, StatusCode> {
-    debug!("Getting profiles blocked by profile_id: {}", profile_id);
+    let (limit, offset, page) = (params.limit, params.offset, params.page);
+    let order = params.order;
+    let sort = params.sort.as_deref().unwrap_or("created_at");
     
-    // In a real implementation, this would query the database using profile_events
-    // to find currently blocked profiles
+    debug!("Getting profiles blocked by profile_id: {}, sort: {} {}", profile_id, sort, order.to_str());
+    
+    let mut conn = pool.get()
+        .await
+        .map_err(|e| {
+            error!("Failed to get database connection: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    
+    // Blocks are recorded with the blocker's on-chain address, which may be the profile ID or the owner wallet
+    let mut blocker_ids = vec![profile_id.clone()];
+    if let Ok(owner_address) = profiles::table
+        .filter(profiles::profile_id.eq(&profile_id))
+        .select(profiles::owner_address)
+        .first::<String>(&mut conn)
+        .await
+    {
+        blocker_ids.push(owner_address);
+    }
+    
+    let total = profiles_blocked::table
+        .filter(profiles_blocked::blocker_wallet_address.eq_any(&blocker_ids))
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await
+        .map_err(|e| {
+            error!("Failed to count blocked profiles: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    
+    let mut list_query = profiles_blocked::table
+        .filter(profiles_blocked::blocker_wallet_address.eq_any(&blocker_ids))
+        .into_boxed();
+    
+    // Always tie-break on id so pages are stable
+    list_query = match (sort, order) {
+        ("created_at", SortOrder::Desc) => list_query.order_by((profiles_blocked::created_at.desc(), profiles_blocked::id.desc())),
+        ("created_at", SortOrder::Asc) => list_query.order_by((profiles_blocked::created_at.asc(), profiles_blocked::id.asc())),
+        ("blocked_address", SortOrder::Desc) => list_query.order_by((profiles_blocked::blocked_address.desc(), profiles_blocked::id.desc())),
+        ("blocked_address", SortOrder::Asc) => list_query.order_by((profiles_blocked::blocked_address.asc(), profiles_blocked::id.asc())),
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+    
+    let blocked = list_query
+        .select((profiles_blocked::blocked_address, profiles_blocked::created_at))
+        .limit(limit)
+        .offset(offset)
+        .load::<(String, chrono::NaiveDateTime)>(&mut conn)
+        .await
+        .map_err(|e| {
+            error!("Failed to fetch blocked profiles: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    
+    let total_pages = (total as f64 / limit as f64).ceil() as i64;
     
-    // Placeholder empty response
     Ok(Json(BlockedProfilesResponse {
-        blocked_profiles: Vec::new(),
-        total: 0,
+        blocked_profiles: blocked
+            .into_iter()
+            .map(|(profile_id, blocked_at)| ProfileBlockInfo { profile_id, blocked_at })
+            .collect(),
+        total,
+        pagination: Pagination {
+            total,
+            limit,
+            offset,
+            page,
+            total_pages,
+        },
     }))
 }
 
@@ -107,4 +189,60 @@ pub async fn check_platform_blocked(
     Ok(Json(BlockCheckResponse {
         is_blocked: false,
     }))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{test_database, unique_id};
+
+    fn params(sort: &str, order: &str) -> ListParams {
+        ListParams::parse(None, None, None, Some(sort.to_string()), Some(order), 100).unwrap()
+    }
+
+    async fn blocked_ids(pool: DbPool, blocker: &str, sort: &str, order: &str) -> Vec<String> {
+        let Json(response) = get_blocked_profiles(Path(blocker.to_string()), State(pool), params(sort, order))
+            .await
+            .unwrap();
+        assert_eq!(response.total, 3);
+        assert_eq!(response.pagination.total, 3);
+        response.blocked_profiles.into_iter().map(|block| block.profile_id).collect()
+    }
+
+    #[tokio::test]
+    async fn blocked_profiles_follow_the_requested_order() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let blocker = unique_id("blocker");
+        let (first, tied_a, tied_b) = (unique_id("b1"), unique_id("b2"), unique_id("b3"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            let earlier = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap().naive_utc();
+            let later = chrono::DateTime::from_timestamp(1_700_000_100, 0).unwrap().naive_utc();
+            // The last two share a timestamp, so only the id tie-break orders them
+            for (blocked, created_at) in [(&first, earlier), (&tied_a, later), (&tied_b, later)] {
+                diesel::insert_into(profiles_blocked::table)
+                    .values((
+                        profiles_blocked::blocker_wallet_address.eq(&blocker),
+                        profiles_blocked::blocked_address.eq(blocked),
+                        profiles_blocked::created_at.eq(created_at),
+                    ))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        
+        assert_eq!(
+            blocked_ids(pool.clone(), &blocker, "created_at", "desc").await,
+            vec![tied_b.clone(), tied_a.clone(), first.clone()]
+        );
+        assert_eq!(
+            blocked_ids(pool.clone(), &blocker, "created_at", "asc").await,
+            vec![first.clone(), tied_a.clone(), tied_b.clone()]
+        );
+        
+        let unknown_sort = get_blocked_profiles(Path(blocker), State(pool), params("rowid", "asc")).await;
+        assert_eq!(unknown_sort.unwrap_err(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,481 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error};
+
+use crate::api::params::ListParams;
+use crate::db::DbPool;
+use crate::models::content::Content;
+use crate::schema::{content, profiles};
+
+/// A content row with the names of the profile that created it
+#[derive(Debug, Serialize)]
+pub struct ContentWithCreator {
+    #[serde(flatten)]
+    pub content: Content,
+    /// Null when the creator's profile hasn't been indexed
+    pub creator_username: Option<String>,
+    pub creator_display_name: Option<String>,
+}
+
+/// A piece of content with its place in a thread
+///
+/// The number of direct replies is the content's `comment_count`.
+#[derive(Debug, Serialize)]
+pub struct ContentDetail {
+    #[serde(flatten)]
+    pub content: ContentWithCreator,
+    /// The content this replies to; null for a root post or a parent not indexed yet
+    pub parent: Option<ContentWithCreator>,
+    /// A page of direct replies, oldest first, when asked for with `include_replies=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replies: Option<Vec<ContentWithCreator>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ContentQuery {
+    #[serde(default)]
+    pub include_replies: bool,
+}
+
+/// Content joined with its creator's names; a left join, so content whose creator isn't
+/// indexed yet is still found
+macro_rules! content_with_creator {
+    () => {
+        content::table
+            .left_join(profiles::table.on(profiles::profile_id.eq(content::creator_id.nullable())))
+            .select((
+                Content::as_select(),
+                profiles::username.nullable(),
+                profiles::display_name.nullable(),
+            ))
+    };
+}
+
+fn with_creator((content, creator_username, creator_display_name): (Content, Option<String>, Option<String>)) -> ContentWithCreator {
+    ContentWithCreator {
+        content,
+        creator_username,
+        creator_display_name,
+    }
+}
+
+/// Find one piece of content with its creator's names
+async fn find_content(conn: &mut AsyncPgConnection, content_id: &str) -> QueryResult<Option<ContentWithCreator>> {
+    content_with_creator!()
+        .filter(content::id.eq(content_id))
+        .first::<(Content, Option<String>, Option<String>)>(conn)
+        .await
+        .optional()
+        .map(|row| row.map(with_creator))
+}
+
+/// Get a piece of content with its interaction counts, its creator's names and its parent
+///
+/// `include_replies=true` adds a page of direct replies, paged with `limit`/`offset`/`page`.
+pub async fn get_content_by_id(
+    State(db_pool): State<DbPool>,
+    Path(content_id): Path<String>,
+    Query(query): Query<ContentQuery>,
+    params: ListParams,
+) -> impl IntoResponse {
+    debug!("Getting content with ID: {}", content_id);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let result = async {
+        let Some(found) = find_content(&mut conn, &content_id).await? else {
+            return Ok(None);
+        };
+        
+        let parent = match &found.content.parent_id {
+            Some(parent_id) => find_content(&mut conn, parent_id).await?,
+            None => None,
+        };
+        
+        let replies = if query.include_replies {
+            let rows = content_with_creator!()
+                .filter(content::parent_id.eq(&content_id))
+                .order((content::created_at.asc(), content::id.asc()))
+                .limit(params.limit)
+                .offset(params.offset)
+                .load::<(Content, Option<String>, Option<String>)>(&mut conn)
+                .await?;
+            Some(rows.into_iter().map(with_creator).collect())
+        } else {
+            None
+        };
+        
+        Result::<_, diesel::result::Error>::Ok(Some(ContentDetail { content: found, parent, replies }))
+    }
+    .await;
+    
+    match result {
+        Ok(Some(detail)) => (
+            StatusCode::OK,
+            Json(serde_json::json!(detail))
+        ),
+        Ok(None) => {
+            debug!("Content not found with ID: {}", content_id);
+            (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Content not found"
+                }))
+            )
+        },
+        Err(e) => {
+            error!("Failed to fetch content: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch content: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Width of the buckets in a content interaction timeline
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineBucket {
+    Hour,
+    #[default]
+    Day,
+}
+
+impl TimelineBucket {
+    /// The `date_trunc` field name for this bucket width
+    fn unit(self) -> &'static str {
+        match self {
+            TimelineBucket::Hour => "hour",
+            TimelineBucket::Day => "day",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct InteractionTimelineQuery {
+    #[serde(default)]
+    pub bucket: TimelineBucket,
+}
+
+/// Interactions of one type within one bucket
+#[derive(Debug, QueryableByName)]
+struct InteractionBucketRow {
+    #[diesel(sql_type = diesel::sql_types::Timestamp)]
+    start: chrono::NaiveDateTime,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    interaction_type: String,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    count: i64,
+}
+
+/// Interactions within one bucket, counted by type
+#[derive(Debug, PartialEq, Serialize)]
+pub struct InteractionBucket {
+    pub start: chrono::NaiveDateTime,
+    pub counts: std::collections::BTreeMap<String, i64>,
+    pub total: i64,
+}
+
+/// Fold rows ordered by bucket start into one entry per bucket
+fn group_buckets(rows: Vec<InteractionBucketRow>) -> Vec<InteractionBucket> {
+    let mut buckets: Vec<InteractionBucket> = Vec::new();
+    for row in rows {
+        match buckets.last_mut() {
+            Some(bucket) if bucket.start == row.start => {
+                bucket.total += row.count;
+                bucket.counts.insert(row.interaction_type, row.count);
+            }
+            _ => buckets.push(InteractionBucket {
+                start: row.start,
+                counts: [(row.interaction_type, row.count)].into(),
+                total: row.count,
+            }),
+        }
+    }
+    buckets
+}
+
+/// Get a piece of content's interactions over time, counted by type per hour or day
+///
+/// Oldest bucket first; buckets without interactions are left out.
+pub async fn get_content_interaction_timeline(
+    State(db_pool): State<DbPool>,
+    Path(content_id): Path<String>,
+    Query(query): Query<InteractionTimelineQuery>,
+) -> impl IntoResponse {
+    debug!("Getting {:?} interaction timeline for content: {}", query.bucket, content_id);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let result = async {
+        let exists = diesel::dsl::select(diesel::dsl::exists(content::table.filter(content::id.eq(&content_id))))
+            .get_result::<bool>(&mut conn)
+            .await?;
+        if !exists {
+            return Ok(None);
+        }
+        
+        let rows = diesel::sql_query(
+            "SELECT date_trunc($1, created_at) AS start, interaction_type, COUNT(*) AS count
+             FROM content_interactions
+             WHERE content_id = $2
+             GROUP BY start, interaction_type
+             ORDER BY start, interaction_type"
+        )
+        .bind::<diesel::sql_types::Text, _>(query.bucket.unit())
+        .bind::<diesel::sql_types::Text, _>(&content_id)
+        .load::<InteractionBucketRow>(&mut conn)
+        .await?;
+        
+        Result::<_, diesel::result::Error>::Ok(Some(group_buckets(rows)))
+    }
+    .await;
+    
+    match result {
+        Ok(Some(buckets)) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "content_id": content_id,
+                "bucket": query.bucket,
+                "buckets": buckets
+            }))
+        ),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Content not found"
+            }))
+        ),
+        Err(e) => {
+            error!("Failed to fetch interaction timeline: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch interaction timeline: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::Response;
+    use diesel_async::AsyncPgConnection;
+    use crate::db::test_support::{insert_profile, test_database, unique_id};
+
+    async fn insert_content(conn: &mut AsyncPgConnection, content_id: &str, creator_id: &str, parent_id: Option<&str>) {
+        diesel::insert_into(content::table)
+            .values((
+                content::id.eq(content_id),
+                content::creator_id.eq(creator_id),
+                content::platform_id.eq("0xplatform"),
+                content::parent_id.eq(parent_id),
+                content::like_count.eq(3),
+                content::created_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(conn)
+            .await
+            .unwrap();
+    }
+
+    async fn get(pool: DbPool, content_id: &str, include_replies: bool) -> Response {
+        let params = ListParams::parse(None, None, None, None, None, 100).unwrap();
+        get_content_by_id(State(pool), Path(content_id.to_string()), Query(ContentQuery { include_replies }), params)
+            .await
+            .into_response()
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn content_comes_with_counts_and_creator_names() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let creator = unique_id("profile");
+        let username = unique_id("user");
+        let content_id = unique_id("content");
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &creator, &username).await;
+            insert_content(&mut conn, &content_id, &creator, None).await;
+        }
+        
+        let response = get(pool, &content_id, false).await;
+        
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["id"], content_id.as_str());
+        assert_eq!(body["like_count"], 3);
+        assert_eq!(body["creator_username"], username.as_str());
+    }
+
+    #[tokio::test]
+    async fn content_from_an_unindexed_creator_has_no_names() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let content_id = unique_id("content");
+        insert_content(&mut pool.get().await.unwrap(), &content_id, &unique_id("profile"), None).await;
+        
+        let response = get(pool, &content_id, false).await;
+        
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(body_json(response).await["creator_username"].is_null());
+    }
+
+    #[tokio::test]
+    async fn unknown_content_is_not_found() {
+        let Some(db) = test_database().await else { return };
+        
+        let response = get(db.pool.as_ref().clone(), &unique_id("content"), false).await;
+        
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn root_post_has_no_parent() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let root = unique_id("content");
+        insert_content(&mut pool.get().await.unwrap(), &root, &unique_id("profile"), None).await;
+        
+        let body = body_json(get(pool, &root, false).await).await;
+        
+        assert!(body["parent"].is_null());
+        assert!(body.get("replies").is_none());
+    }
+
+    #[tokio::test]
+    async fn reply_resolves_its_parent_and_the_parent_lists_its_replies() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let creator = unique_id("profile");
+        let username = unique_id("user");
+        let root = unique_id("content");
+        let reply = unique_id("content");
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &creator, &username).await;
+            insert_content(&mut conn, &root, &creator, None).await;
+            insert_content(&mut conn, &reply, &unique_id("profile"), Some(&root)).await;
+        }
+        
+        let body = body_json(get(pool.clone(), &reply, false).await).await;
+        assert_eq!(body["parent"]["id"], root.as_str());
+        assert_eq!(body["parent"]["creator_username"], username.as_str());
+        
+        let body = body_json(get(pool, &root, true).await).await;
+        let replies = body["replies"].as_array().unwrap();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0]["id"], reply.as_str());
+    }
+
+    async fn insert_interaction(conn: &mut AsyncPgConnection, content_id: &str, interaction_type: &str, created_at: &str) {
+        use crate::schema::content_interactions;
+        
+        diesel::insert_into(content_interactions::table)
+            .values((
+                content_interactions::content_id.eq(content_id),
+                content_interactions::profile_id.eq(unique_id("profile")),
+                content_interactions::interaction_type.eq(interaction_type),
+                content_interactions::created_at.eq(created_at.parse::<chrono::NaiveDateTime>().unwrap()),
+            ))
+            .execute(conn)
+            .await
+            .unwrap();
+    }
+
+    async fn timeline(pool: DbPool, content_id: &str, bucket: TimelineBucket) -> Response {
+        get_content_interaction_timeline(State(pool), Path(content_id.to_string()), Query(InteractionTimelineQuery { bucket }))
+            .await
+            .into_response()
+    }
+
+    #[tokio::test]
+    async fn interactions_are_counted_per_bucket_and_type() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let content_id = unique_id("content");
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_content(&mut conn, &content_id, &unique_id("profile"), None).await;
+            insert_interaction(&mut conn, &content_id, "like", "2025-03-01T10:05:00").await;
+            insert_interaction(&mut conn, &content_id, "like", "2025-03-01T10:55:00").await;
+            insert_interaction(&mut conn, &content_id, "view", "2025-03-01T10:30:00").await;
+            insert_interaction(&mut conn, &content_id, "share", "2025-03-01T11:15:00").await;
+        }
+        
+        let response = timeline(pool.clone(), &content_id, TimelineBucket::Hour).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["bucket"], "hour");
+        assert_eq!(body["buckets"], serde_json::json!([
+            { "start": "2025-03-01T10:00:00", "counts": { "like": 2, "view": 1 }, "total": 3 },
+            { "start": "2025-03-01T11:00:00", "counts": { "share": 1 }, "total": 1 },
+        ]));
+        
+        // The same interactions fall into a single day
+        let body = body_json(timeline(pool, &content_id, TimelineBucket::Day).await).await;
+        assert_eq!(body["buckets"], serde_json::json!([
+            { "start": "2025-03-01T00:00:00", "counts": { "like": 2, "share": 1, "view": 1 }, "total": 4 },
+        ]));
+    }
+
+    #[tokio::test]
+    async fn timeline_of_unknown_content_is_not_found() {
+        let Some(db) = test_database().await else { return };
+        
+        let response = timeline(db.pool.as_ref().clone(), &unique_id("content"), TimelineBucket::Day).await;
+        
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn bucket_defaults_to_day_and_rejects_other_widths() {
+        let parse = |uri: &'static str| {
+            Query::<InteractionTimelineQuery>::try_from_uri(&axum::http::Uri::from_static(uri)).map(|Query(query)| query.bucket)
+        };
+        assert_eq!(parse("/timeline").unwrap(), TimelineBucket::Day);
+        assert_eq!(parse("/timeline?bucket=hour").unwrap(), TimelineBucket::Hour);
+        assert!(parse("/timeline?bucket=week").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+    Json,
+};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use futures::StreamExt;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tracing::{error, info};
+
+use crate::api::auth::require_admin;
+use crate::config::ApiConfig;
+use crate::db::DbPool;
+use crate::schema::social_graph_relationships;
+
+/// Rows written per body chunk
+const EXPORT_CHUNK_ROWS: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// Output format; only `csv` is supported
+    pub format: Option<String>,
+}
+
+/// Stream every follow relationship as CSV (admin only)
+///
+/// Rows are read with a database cursor and sent in chunks as they arrive, so the table is
+/// never held in memory. A database error mid-export aborts the response, leaving a
+/// truncated file rather than a silently incomplete one.
+pub async fn export_social_graph(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    Query(query): Query<ExportQuery>,
+) -> Response {
+    if let Err(rejection) = require_admin(&headers, &api_config) {
+        return rejection.into_response();
+    }
+
+    let format = query.format.as_deref().unwrap_or("csv");
+    if format != "csv" {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Unsupported format '{}': expected csv", format)
+            }))
+        ).into_response();
+    }
+
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            ).into_response()
+        }
+    };
+
+    info!("Exporting social graph as CSV");
+
+    // The task owns the connection for the length of the export and feeds the body through a
+    // small channel, so a slow client holds back the cursor instead of growing a buffer
+    let (tx, rx) = mpsc::channel::<Result<String, std::io::Error>>(4);
+    crate::tasks::spawn_named("social-graph-export", async move {
+        let rows = social_graph_relationships::table
+            .select((
+                social_graph_relationships::follower_address,
+                social_graph_relationships::following_address,
+                social_graph_relationships::created_at,
+            ))
+            .order_by(social_graph_relationships::id.asc())
+            .load_stream::<(String, String, NaiveDateTime)>(&mut conn)
+            .await;
+        let mut rows = match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                error!("Failed to start social graph export: {}", e);
+                let _ = tx.send(Err(std::io::Error::other(e))).await;
+                return;
+            }
+        };
+
+        let mut chunk = String::from("follower_id,following_id,created_at\n");
+        let mut chunk_rows = 0;
+        let mut exported = 0u64;
+        while let Some(row) = rows.next().await {
+            match row {
+                // Ids are hex addresses, so they never need CSV quoting
+                Ok((follower, following, created_at)) => {
+                    chunk.push_str(&format!("{},{},{}\n", follower, following, created_at.format("%Y-%m-%dT%H:%M:%S%.f")));
+                    chunk_rows += 1;
+                    exported += 1;
+                }
+                Err(e) => {
+                    error!("Social graph export failed after {} rows: {}", exported, e);
+                    let _ = tx.send(Err(std::io::Error::other(e))).await;
+                    return;
+                }
+            }
+
+            if chunk_rows >= EXPORT_CHUNK_ROWS {
+                if tx.send(Ok(std::mem::take(&mut chunk))).await.is_err() {
+                    info!("Social graph export cancelled by the client after {} rows", exported);
+                    return;
+                }
+                chunk_rows = 0;
+            }
+        }
+
+        if !chunk.is_empty() {
+            let _ = tx.send(Ok(chunk)).await;
+        }
+        info!("Exported {} social graph relationships", exported);
+    });
+
+    let body = Body::from_stream(futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    }));
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"social-graph.csv\""),
+        ],
+        body,
+    ).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use crate::db::test_support::{test_database, unique_id};
+
+    fn api_config() -> ApiConfig {
+        ApiConfig {
+            admin_token: Some("secret".to_string()),
+            max_page_size: 100,
+            recent_profiles_default: 10,
+            ipfs_gateway: String::new(),
+            arweave_gateway: String::new(),
+            resolve_media_default: false,
+            max_request_body_bytes: 1024,
+            pretty_json_default: false,
+            hide_unapproved_platforms: false,
+        }
+    }
+
+    fn admin_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        headers
+    }
+
+    async fn export(pool: DbPool, headers: HeaderMap, format: Option<&str>) -> Response {
+        let query = ExportQuery { format: format.map(str::to_string) };
+        export_social_graph(State(pool), Extension(api_config()), headers, Query(query)).await
+    }
+
+    #[tokio::test]
+    async fn csv_contains_the_seeded_relationships() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let follower = unique_id("follower");
+        let (first, second) = (unique_id("following"), unique_id("following"));
+        let created_at = chrono::DateTime::from_timestamp(1_700_000_000, 500_000_000).unwrap().naive_utc();
+        {
+            let mut conn = pool.get().await.unwrap();
+            for following in [&first, &second] {
+                diesel::insert_into(social_graph_relationships::table)
+                    .values((
+                        social_graph_relationships::follower_address.eq(&follower),
+                        social_graph_relationships::following_address.eq(following),
+                        social_graph_relationships::created_at.eq(created_at),
+                    ))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        
+        let response = export(pool, admin_headers(), Some("csv")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/csv; charset=utf-8");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let csv = String::from_utf8(bytes.to_vec()).unwrap();
+        
+        assert!(csv.starts_with("follower_id,following_id,created_at\n"));
+        let seeded: Vec<&str> = csv.lines().filter(|line| line.starts_with(&format!("{},", follower))).collect();
+        assert_eq!(seeded, vec![
+            format!("{},{},2023-11-14T22:13:20.500", follower, first),
+            format!("{},{},2023-11-14T22:13:20.500", follower, second),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn export_requires_the_admin_token() {
+        let Some(db) = test_database().await else { return };
+        let response = export(db.pool.as_ref().clone(), HeaderMap::new(), None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn unsupported_format_is_rejected() {
+        let Some(db) = test_database().await else { return };
+        let response = export(db.pool.as_ref().clone(), admin_headers(), Some("parquet")).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -5,11 +5,33 @@ use axum::{
     extract::State,
     http::StatusCode,
     response::IntoResponse,
+    Extension,
     Json,
 };
 use serde_json::json;
+use crate::api::readiness::Readiness;
 use crate::db::DbPool;
 
+/// Readiness probe: 503 until migrations have run and the database pool is verified
+pub async fn ready(Extension(readiness): Extension<Readiness>) -> impl IntoResponse {
+    if readiness.is_ready() {
+        (
+            StatusCode::OK,
+            Json(json!({
+                "status": "ready"
+            }))
+        )
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({
+                "status": "starting",
+                "message": "Waiting for migrations and database verification"
+            }))
+        )
+    }
+}
+
 /// Health check endpoint
 pub async fn health_check(State(db_pool): State<DbPool>) -> impl IntoResponse {
     // Check database connection
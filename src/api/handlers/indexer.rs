@@ -0,0 +1,307 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension,
+    Json,
+};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::sql_types::{Text, Timestamp};
+use diesel::QueryableByName;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, error};
+
+use crate::blockchain::CatchUpStatus;
+use crate::config::BlockchainConfig;
+use crate::db::DbPool;
+use crate::models::indexer::IndexerProgress;
+use crate::schema::indexer_progress;
+
+/// What the indexer is watching, for debugging missing events
+///
+/// Only non-secret settings are returned; the database URL and tokens never appear here.
+pub async fn get_indexer_config(
+    Extension(blockchain_config): Extension<BlockchainConfig>,
+) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(json!({
+            "package_addresses": crate::get_monitored_package_addresses(),
+            "event_modules": crate::MONITORED_MODULE_NAMES,
+            "event_types": crate::INDEXED_EVENT_TYPES,
+            "start_checkpoint": blockchain_config.start_checkpoint,
+            "startup_replay_checkpoints": blockchain_config.startup_replay_checkpoints,
+            "poll_interval_ms": blockchain_config.poll_interval_ms,
+            "batch_size": blockchain_config.batch_size,
+            "ignored_addresses_count": blockchain_config.ignored_addresses.len()
+        }))
+    )
+}
+
+/// Whether the indexer has reached the chain tip, and where each worker has got to
+///
+/// `caught_up` is false until the listener has indexed everything up to the tip (less
+/// `INDEX_LAG_CHECKPOINTS`) and goes back to false if it falls behind. Workers record the
+/// timestamp of the last event they handled as `last_checkpoint_processed`.
+pub async fn get_indexer_status(
+    State(db_pool): State<DbPool>,
+    Extension(catch_up): Extension<CatchUpStatus>,
+) -> impl IntoResponse {
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let progress = indexer_progress::table
+        .select(IndexerProgress::as_select())
+        .order_by(indexer_progress::id.asc())
+        .load::<IndexerProgress>(&mut conn)
+        .await;
+    
+    match progress {
+        Ok(workers) => (StatusCode::OK, Json(json!({
+            "caught_up": catch_up.is_caught_up(),
+            "workers": workers
+        }))),
+        Err(e) => {
+            error!("Failed to fetch indexer progress: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch indexer progress: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventStatusQuery {
+    /// Digest of the transaction that emitted the events
+    pub tx_digest: Option<String>,
+}
+
+/// An event from the transaction found in one of the event history tables
+#[derive(Debug, QueryableByName, Serialize)]
+struct RecordedEvent {
+    /// `profile`, `social_graph`, `platform` or `dead_letter`
+    #[diesel(sql_type = Text)]
+    source: String,
+    #[diesel(sql_type = Text)]
+    event_id: String,
+    #[diesel(sql_type = Text)]
+    event_type: String,
+    #[diesel(sql_type = Timestamp)]
+    recorded_at: NaiveDateTime,
+}
+
+/// Whether the events of a transaction have been indexed
+///
+/// Event ids are `<tx_digest>:<event_seq>`, so the history tables and the dead letters are
+/// searched by digest prefix. `status` is `processed` if any event was stored, `failed` if
+/// events only reached the dead letters, and `unknown` otherwise. Checkpoint numbers are not
+/// stored with events, so they can't be reported.
+pub async fn get_event_status(
+    State(db_pool): State<DbPool>,
+    Query(query): Query<EventStatusQuery>,
+) -> impl IntoResponse {
+    // Digests are base58, so anything else can't match and must not reach the LIKE pattern
+    let tx_digest = match query.tx_digest.as_deref().map(str::trim) {
+        Some(digest) if !digest.is_empty() && digest.len() <= 64 && digest.chars().all(|c| c.is_ascii_alphanumeric()) => digest.to_string(),
+        Some(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "tx_digest must be a base58 transaction digest"
+                }))
+            )
+        }
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "tx_digest is required"
+                }))
+            )
+        }
+    };
+    
+    debug!("Getting event status for tx_digest: {}", tx_digest);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let events_result = diesel::sql_query(
+        "SELECT 'profile' AS source, event_id, event_type, created_at AS recorded_at
+         FROM profile_events WHERE event_id LIKE $1
+         UNION ALL
+         SELECT 'social_graph', event_id, event_type, created_at
+         FROM social_graph_events WHERE event_id LIKE $1
+         UNION ALL
+         SELECT 'platform', event_id, event_type, created_at
+         FROM platform_events WHERE event_id LIKE $1
+         UNION ALL
+         SELECT 'dead_letter', event_id, event_type, created_at
+         FROM dead_letter_events WHERE event_id LIKE $1
+         ORDER BY event_id, source"
+    )
+    .bind::<Text, _>(format!("{}:%", tx_digest))
+    .load::<RecordedEvent>(&mut conn)
+    .await;
+    
+    match events_result {
+        Ok(events) => {
+            let processed = events.iter().any(|event| event.source != "dead_letter");
+            let status = match (processed, events.is_empty()) {
+                (true, _) => "processed",
+                (false, false) => "failed",
+                (false, true) => "unknown",
+            };
+            
+            (StatusCode::OK, Json(json!({
+                "tx_digest": tx_digest,
+                "status": status,
+                "events": events
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch event status: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch event status: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProfileFieldLimits;
+
+    #[tokio::test]
+    async fn config_reports_the_default_package_when_not_overridden() {
+        let blockchain_config = BlockchainConfig {
+            rpc_url: "http://localhost:9000".to_string(),
+            ws_url: "ws://localhost:9000".to_string(),
+            poll_interval_ms: 5000,
+            batch_size: 50,
+            startup_replay_checkpoints: 0,
+            start_checkpoint: 1200,
+            checkpoint_fetch_retries: 3,
+            checkpoint_fetch_timeout_secs: 30,
+            index_lag_checkpoints: 0,
+            store_source_events: false,
+            ignored_addresses: vec!["0xdead".to_string()],
+            profile_field_limits: ProfileFieldLimits::default(),
+            platform_handler_concurrency: 1,
+            max_event_data_bytes: 256 * 1024,
+            handler_send_timeout_ms: 5000,
+        };
+
+        let resp = get_indexer_config(Extension(blockchain_config)).await.into_response();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let config: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(config["package_addresses"], json!([crate::DEFAULT_MYSOCIAL_PACKAGE_ADDRESS]));
+        assert_eq!(config["start_checkpoint"], 1200);
+        assert_eq!(config["ignored_addresses_count"], 1);
+        let event_types = config["event_types"].as_array().unwrap();
+        assert!(event_types.contains(&json!("ProfileCreatedEvent")));
+        assert!(event_types.contains(&json!("FollowEvent")));
+        assert!(!body.windows(b"0xdead".len()).any(|window| window == b"0xdead"));
+    }
+
+    async fn event_status(pool: DbPool, tx_digest: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let query = EventStatusQuery { tx_digest: tx_digest.map(str::to_string) };
+        let resp = get_event_status(State(pool), Query(query)).await.into_response();
+        let status = resp.status();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn event_status_reports_processed_failed_and_unknown_digests() {
+        use crate::db::test_support::{test_database, unique_id};
+        use crate::schema::{dead_letter_events, platform_events};
+        
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        // Digests are alphanumeric, so drop the 0x of the test ids
+        let digest = || unique_id("digest")[2..].to_string();
+        let (processed, failed, unknown) = (digest(), digest(), digest());
+        {
+            let mut conn = pool.get().await.unwrap();
+            let now = chrono::Utc::now().naive_utc();
+            diesel::insert_into(platform_events::table)
+                .values((
+                    platform_events::event_type.eq("PlatformCreated"),
+                    platform_events::platform_id.eq("0xplatform"),
+                    platform_events::event_data.eq(json!({})),
+                    platform_events::event_id.eq(format!("{}:0", processed)),
+                    platform_events::created_at.eq(now),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            diesel::insert_into(dead_letter_events::table)
+                .values((
+                    dead_letter_events::event_id.eq(format!("{}:1", failed)),
+                    dead_letter_events::event_type.eq("FollowEvent"),
+                    dead_letter_events::worker_id.eq("test-worker"),
+                    dead_letter_events::event_data.eq(json!({})),
+                    dead_letter_events::error_message.eq("boom"),
+                    dead_letter_events::retry_count.eq(0),
+                    dead_letter_events::created_at.eq(now),
+                    dead_letter_events::updated_at.eq(now),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        
+        let (status, body) = event_status(pool.clone(), Some(&processed)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "processed");
+        assert_eq!(body["events"][0]["source"], "platform");
+        assert_eq!(body["events"][0]["event_id"], format!("{}:0", processed));
+        
+        let (_, body) = event_status(pool.clone(), Some(&failed)).await;
+        assert_eq!(body["status"], "failed");
+        
+        let (status, body) = event_status(pool.clone(), Some(&unknown)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "unknown");
+        assert_eq!(body["events"], json!([]));
+        
+        assert_eq!(event_status(pool.clone(), Some("abc%")).await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(event_status(pool, None).await.0, StatusCode::BAD_REQUEST);
+    }
+}
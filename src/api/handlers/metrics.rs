@@ -0,0 +1,16 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+
+/// Prometheus metrics endpoint
+pub async fn metrics() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::render(),
+    )
+}
@@ -1,9 +1,15 @@
 // Copyright (c) MySocial Team
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod admin;
+pub mod content;
+pub mod export;
 pub mod health;
+pub mod indexer;
+pub mod metrics;
 pub mod platforms;
 pub mod profiles;
 pub mod profile_events;
+pub mod search;
 pub mod social_graph;
 pub mod blocking;
\ No newline at end of file
@@ -3,9 +3,9 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
-    Json,
+    Extension,
 };
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
@@ -13,34 +13,75 @@ use tracing::{debug, error};
 use serde::{Deserialize};
 use chrono::NaiveDateTime;
 
+use crate::api::auth::hide_unapproved_platforms;
+use crate::api::media::{resolve_media_field, MediaQuery};
+use crate::api::params::{ListParams, SortOrder};
+use crate::config::ApiConfig;
+use crate::api::response::{db_error, JsonBody};
 use crate::db::DbPool;
-use crate::models::platform::{Platform, PlatformModerator, PlatformBlockedProfile, PlatformWithDetails};
-use crate::schema::{platforms, platform_moderators, platform_blocked_profiles};
+use crate::models::Profile;
+use crate::models::platform::{Platform, PlatformModerator, PlatformBlockedProfile, PlatformStatus, PlatformWithDetails, PLATFORM_STATUS_DEVELOPMENT, PLATFORM_STATUS_SHUTDOWN};
+use crate::schema::{platforms, platform_moderators, platform_blocked_profiles, platform_memberships, profiles};
 
+/// Platform list filters; pagination and sorting come from `ListParams`
 #[derive(Debug, Deserialize)]
 pub struct PlatformQuery {
-    pub limit: Option<i64>,
-    pub offset: Option<i64>,
-    pub page: Option<i64>,
+    /// Comma separated statuses to include (e.g. `live,beta`)
+    pub status: Option<String>,
+    /// Rewrite ipfs:// and ar:// logo URLs to gateway URLs
+    pub resolve_media: Option<bool>,
+}
+
+/// Only used to tell whether `order` was given explicitly
+#[derive(Debug, Deserialize)]
+pub struct TimelineQuery {
+    pub order: Option<String>,
+}
+
+/// Parse the `status` filter into status codes
+fn parse_status_filter(status: Option<&str>) -> Result<Option<Vec<i16>>, String> {
+    let status = match status {
+        Some(s) if !s.trim().is_empty() => s,
+        _ => return Ok(None),
+    };
+    
+    status
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| s.parse::<PlatformStatus>().map(|status| status.code()))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
 }
 
 /// Get a list of all platforms with pagination
+///
+/// Only approved platforms are listed when `HIDE_UNAPPROVED_PLATFORMS` is set, unless the
+/// request carries the admin token.
 pub async fn get_platforms(
     State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    params: ListParams,
     Query(query): Query<PlatformQuery>,
 ) -> impl IntoResponse {
-    let limit = query.limit.unwrap_or(50);
-    let offset = query.offset.unwrap_or(0);
-    let page = query.page.unwrap_or(1);
+    let (limit, offset) = (params.limit, params.offset);
+    let resolve_media = api_config.resolve_media(query.resolve_media);
+    let order = params.order;
+    let sort = params.sort.as_deref().unwrap_or("created_at");
     
-    // If page is provided, calculate the offset
-    let offset = if page > 1 {
-        (page - 1) * limit
-    } else {
-        offset
+    let status_filter = match parse_status_filter(query.status.as_deref()) {
+        Ok(filter) => filter,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                JsonBody(serde_json::json!({
+                    "error": e
+                }))
+            )
+        }
     };
     
-    debug!("Getting platforms list with limit: {}, offset: {}", limit, offset);
+    debug!("Getting platforms list with limit: {}, offset: {}, status: {:?}, sort: {} {}", limit, offset, status_filter, sort, order.to_str());
     
     let mut conn = match db_pool.get().await {
         Ok(conn) => conn,
@@ -48,27 +89,55 @@ pub async fn get_platforms(
             error!("Database connection error: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Database error: {}", e)
                 }))
             )
         }
     };
     
+    let mut count_query = platforms::table.into_boxed();
+    let mut list_query = platforms::table.into_boxed();
+    if hide_unapproved_platforms(&headers, &api_config) {
+        count_query = count_query.filter(platforms::is_approved.eq(true));
+        list_query = list_query.filter(platforms::is_approved.eq(true));
+    }
+    if let Some(statuses) = &status_filter {
+        count_query = count_query.filter(platforms::status.eq_any(statuses.clone()));
+        list_query = list_query.filter(platforms::status.eq_any(statuses.clone()));
+    }
+    
     // Get the total count for pagination info
-    let total_count = match platforms::table
+    let total_count = match count_query
         .count()
         .get_result::<i64>(&mut conn)
         .await {
         Ok(count) => count,
-        Err(_) => 0,
+        Err(e) => return db_error(e, "Not found", "Failed to count platforms"),
     };
     
-    let total_pages = (total_count as f64 / limit as f64).ceil() as i64;
+    // Nullable columns sort NULLS LAST in both directions so unset values always sink to the bottom
+    let list_query = match (sort, order) {
+        ("created_at", SortOrder::Desc) => list_query.order_by((platforms::created_at.desc(), platforms::id.desc())),
+        ("created_at", SortOrder::Asc) => list_query.order_by((platforms::created_at.asc(), platforms::id.asc())),
+        ("name", SortOrder::Desc) => list_query.order_by((platforms::name.desc(), platforms::id.desc())),
+        ("name", SortOrder::Asc) => list_query.order_by((platforms::name.asc(), platforms::id.asc())),
+        ("approval_changed_at", SortOrder::Desc) => list_query.order_by((platforms::approval_changed_at.desc().nulls_last(), platforms::id.desc())),
+        ("approval_changed_at", SortOrder::Asc) => list_query.order_by((platforms::approval_changed_at.asc().nulls_last(), platforms::id.asc())),
+        ("shutdown_date", SortOrder::Desc) => list_query.order_by((platforms::shutdown_date.desc().nulls_last(), platforms::id.desc())),
+        ("shutdown_date", SortOrder::Asc) => list_query.order_by((platforms::shutdown_date.asc().nulls_last(), platforms::id.asc())),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                JsonBody(serde_json::json!({
+                    "error": format!("Unsupported sort: {}", sort)
+                }))
+            )
+        }
+    };
     
     // Query platforms with pagination
-    let platforms_result = platforms::table
-        .order_by(platforms::created_at.desc())
+    let platforms_result = list_query
         .limit(limit)
         .offset(offset)
         .load::<Platform>(&mut conn)
@@ -82,7 +151,7 @@ pub async fn get_platforms(
             for platform in platforms {
                 // Get moderator count
                 let moderator_count = platform_moderators::table
-                    .filter(platform_moderators::platform_id.eq(&platform.platform_id))
+                    .filter(platform_moderators::platform_ref.eq(platform.id))
                     .count()
                     .get_result::<i64>(&mut conn)
                     .await
@@ -90,7 +159,7 @@ pub async fn get_platforms(
                 
                 // Get blocked profiles count
                 let blocked_count = platform_blocked_profiles::table
-                    .filter(platform_blocked_profiles::platform_id.eq(&platform.platform_id))
+                    .filter(platform_blocked_profiles::platform_ref.eq(platform.id))
                     .count()
                     .get_result::<i64>(&mut conn)
                     .await
@@ -113,7 +182,7 @@ pub async fn get_platforms(
                     name: platform.name,
                     tagline: platform.tagline,
                     description: platform.description,
-                    logo: platform.logo,
+                    logo: if resolve_media { resolve_media_field(platform.logo, &api_config) } else { platform.logo },
                     developer_address: platform.developer_address,
                     terms_of_service: platform.terms_of_service,
                     privacy_policy: platform.privacy_policy,
@@ -128,27 +197,23 @@ pub async fn get_platforms(
                     is_approved: platform.is_approved,
                     approval_changed_at: platform.approval_changed_at,
                     approved_by: platform.approved_by.clone(),
+                    total_users_count: platform.total_users_count,
+                    active_users_count: platform.active_users_count,
                     moderator_count,
                     blocked_profiles_count: blocked_count,
                 });
             }
             
-            (StatusCode::OK, Json(serde_json::json!({
+            (StatusCode::OK, JsonBody(serde_json::json!({
                 "platforms": platform_details,
-                "pagination": {
-                    "total": total_count,
-                    "limit": limit,
-                    "offset": offset,
-                    "page": page,
-                    "total_pages": total_pages
-                }
+                "pagination": params.pagination(total_count)
             })))
         },
         Err(e) => {
             error!("Failed to fetch platforms: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Failed to fetch platforms: {}", e)
                 }))
             )
@@ -156,11 +221,71 @@ pub async fn get_platforms(
     }
 }
 
+/// Count platforms per status, keyed by status name; statuses with no platforms are reported as 0
+///
+/// Respects `HIDE_UNAPPROVED_PLATFORMS` like the platform list.
+pub async fn get_platform_status_breakdown(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let mut query = platforms::table
+        .group_by(platforms::status)
+        .select((platforms::status, diesel::dsl::count_star()))
+        .into_boxed();
+    if hide_unapproved_platforms(&headers, &api_config) {
+        query = query.filter(platforms::is_approved.eq(true));
+    }
+    
+    let counts = match query.load::<(i16, i64)>(&mut conn).await {
+        Ok(counts) => counts,
+        Err(e) => return db_error(e, "Not found", "Failed to count platforms by status"),
+    };
+    
+    let mut breakdown = serde_json::Map::new();
+    for code in PLATFORM_STATUS_DEVELOPMENT..=PLATFORM_STATUS_SHUTDOWN {
+        breakdown.insert(PlatformWithDetails::status_to_text(code).to_lowercase(), 0.into());
+    }
+    let mut total = 0;
+    for (code, count) in counts {
+        // Codes outside the known range are grouped under "unknown"
+        let name = PlatformWithDetails::status_to_text(code).to_lowercase();
+        let entry = breakdown.entry(name).or_insert(0.into());
+        *entry = (entry.as_i64().unwrap_or(0) + count).into();
+        total += count;
+    }
+    
+    (StatusCode::OK, JsonBody(serde_json::json!({
+        "statuses": breakdown,
+        "total": total
+    })))
+}
+
 /// Get a platform by its ID
+///
+/// An unapproved platform is reported as not found when `HIDE_UNAPPROVED_PLATFORMS` is set,
+/// unless the request carries the admin token.
 pub async fn get_platform_by_id(
     State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
     Path(platform_id): Path<String>,
+    Query(media): Query<MediaQuery>,
 ) -> impl IntoResponse {
+    let resolve_media = api_config.resolve_media(media.resolve_media);
     debug!("Getting platform with ID: {}", platform_id);
     
     let mut conn = match db_pool.get().await {
@@ -169,7 +294,7 @@ pub async fn get_platform_by_id(
             error!("Database connection error: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Database error: {}", e)
                 }))
             )
@@ -177,8 +302,13 @@ pub async fn get_platform_by_id(
     };
     
     // Get the platform
-    let platform_result = platforms::table
+    let mut platform_query = platforms::table
         .filter(platforms::platform_id.eq(&platform_id))
+        .into_boxed();
+    if hide_unapproved_platforms(&headers, &api_config) {
+        platform_query = platform_query.filter(platforms::is_approved.eq(true));
+    }
+    let platform_result = platform_query
         .first::<Platform>(&mut conn)
         .await;
     
@@ -186,7 +316,7 @@ pub async fn get_platform_by_id(
         Ok(platform) => {
             // Get moderator count
             let moderator_count = platform_moderators::table
-                .filter(platform_moderators::platform_id.eq(&platform.platform_id))
+                .filter(platform_moderators::platform_ref.eq(platform.id))
                 .count()
                 .get_result::<i64>(&mut conn)
                 .await
@@ -194,7 +324,7 @@ pub async fn get_platform_by_id(
             
             // Get blocked profiles count
             let blocked_count = platform_blocked_profiles::table
-                .filter(platform_blocked_profiles::platform_id.eq(&platform.platform_id))
+                .filter(platform_blocked_profiles::platform_ref.eq(platform.id))
                 .count()
                 .get_result::<i64>(&mut conn)
                 .await
@@ -202,7 +332,7 @@ pub async fn get_platform_by_id(
             
             // Get moderators
             let moderators = platform_moderators::table
-                .filter(platform_moderators::platform_id.eq(&platform.platform_id))
+                .filter(platform_moderators::platform_ref.eq(platform.id))
                 .load::<PlatformModerator>(&mut conn)
                 .await
                 .unwrap_or_default();
@@ -224,7 +354,7 @@ pub async fn get_platform_by_id(
                 name: platform.name,
                 tagline: platform.tagline,
                 description: platform.description,
-                logo: platform.logo,
+                logo: if resolve_media { resolve_media_field(platform.logo, &api_config) } else { platform.logo },
                 developer_address: platform.developer_address,
                 terms_of_service: platform.terms_of_service,
                 privacy_policy: platform.privacy_policy,
@@ -239,33 +369,18 @@ pub async fn get_platform_by_id(
                 is_approved: platform.is_approved,
                 approval_changed_at: platform.approval_changed_at,
                 approved_by: platform.approved_by.clone(),
+                total_users_count: platform.total_users_count,
+                active_users_count: platform.active_users_count,
                 moderator_count,
                 blocked_profiles_count: blocked_count,
             };
             
-            (StatusCode::OK, Json(serde_json::json!({
+            (StatusCode::OK, JsonBody(serde_json::json!({
                 "platform": platform_details,
                 "moderators": moderators
             })))
         },
-        Err(diesel::result::Error::NotFound) => {
-            debug!("Platform not found: {}", platform_id);
-            (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "error": "Platform not found"
-                }))
-            )
-        },
-        Err(e) => {
-            error!("Failed to fetch platform: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to fetch platform: {}", e)
-                }))
-            )
-        }
+        Err(e) => db_error(e, "Platform not found", "Failed to fetch platform")
     }
 }
 
@@ -273,18 +388,9 @@ pub async fn get_platform_by_id(
 pub async fn get_platform_moderators(
     State(db_pool): State<DbPool>,
     Path(platform_id): Path<String>,
-    Query(query): Query<PlatformQuery>,
+    params: ListParams,
 ) -> impl IntoResponse {
-    let limit = query.limit.unwrap_or(50);
-    let offset = query.offset.unwrap_or(0);
-    let page = query.page.unwrap_or(1);
-    
-    // If page is provided, calculate the offset
-    let offset = if page > 1 {
-        (page - 1) * limit
-    } else {
-        offset
-    };
+    let (limit, offset) = (params.limit, params.offset);
     
     debug!("Getting moderators for platform: {}", platform_id);
     
@@ -294,56 +400,54 @@ pub async fn get_platform_moderators(
             error!("Database connection error: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Database error: {}", e)
                 }))
             )
         }
     };
     
-    // Check if platform exists
-    let platform_exists = match platforms::table
+    // Check if platform exists, resolving the numeric id its child rows reference
+    let platform_ref = match platforms::table
         .filter(platforms::platform_id.eq(&platform_id))
-        .count()
-        .get_result::<i64>(&mut conn)
-        .await {
-        Ok(count) => count > 0,
+        .select(platforms::id)
+        .first::<i32>(&mut conn)
+        .await
+        .optional() {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            debug!("Platform not found: {}", platform_id);
+            return (
+                StatusCode::NOT_FOUND,
+                JsonBody(serde_json::json!({
+                    "error": "Platform not found"
+                }))
+            )
+        }
         Err(e) => {
             error!("Failed to check platform: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Failed to check platform: {}", e)
                 }))
             )
         }
     };
     
-    if !platform_exists {
-        debug!("Platform not found: {}", platform_id);
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
-                "error": "Platform not found"
-            }))
-        )
-    }
-    
     // Get the total count for pagination info
     let total_count = match platform_moderators::table
-        .filter(platform_moderators::platform_id.eq(&platform_id))
+        .filter(platform_moderators::platform_ref.eq(platform_ref))
         .count()
         .get_result::<i64>(&mut conn)
         .await {
         Ok(count) => count,
-        Err(_) => 0,
+        Err(e) => return db_error(e, "Not found", "Failed to count moderators"),
     };
     
-    let total_pages = (total_count as f64 / limit as f64).ceil() as i64;
-    
     // Get moderators with pagination
     let moderators_result = platform_moderators::table
-        .filter(platform_moderators::platform_id.eq(&platform_id))
+        .filter(platform_moderators::platform_ref.eq(platform_ref))
         .order_by(platform_moderators::created_at.desc())
         .limit(limit)
         .offset(offset)
@@ -352,22 +456,16 @@ pub async fn get_platform_moderators(
     
     match moderators_result {
         Ok(moderators) => {
-            (StatusCode::OK, Json(serde_json::json!({
+            (StatusCode::OK, JsonBody(serde_json::json!({
                 "moderators": moderators,
-                "pagination": {
-                    "total": total_count,
-                    "limit": limit,
-                    "offset": offset,
-                    "page": page,
-                    "total_pages": total_pages
-                }
+                "pagination": params.pagination(total_count)
             })))
         },
         Err(e) => {
             error!("Failed to fetch moderators: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Failed to fetch moderators: {}", e)
                 }))
             )
@@ -378,20 +476,26 @@ pub async fn get_platform_moderators(
 /// Get a list of approved platforms with pagination
 pub async fn get_approved_platforms(
     State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    params: ListParams,
     Query(query): Query<PlatformQuery>,
 ) -> impl IntoResponse {
-    let limit = query.limit.unwrap_or(50);
-    let offset = query.offset.unwrap_or(0);
-    let page = query.page.unwrap_or(1);
+    let (limit, offset) = (params.limit, params.offset);
+    let resolve_media = api_config.resolve_media(query.resolve_media);
     
-    // If page is provided, calculate the offset
-    let offset = if page > 1 {
-        (page - 1) * limit
-    } else {
-        offset
+    let status_filter = match parse_status_filter(query.status.as_deref()) {
+        Ok(filter) => filter,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                JsonBody(serde_json::json!({
+                    "error": e
+                }))
+            )
+        }
     };
     
-    debug!("Getting approved platforms list with limit: {}, offset: {}", limit, offset);
+    debug!("Getting approved platforms list with limit: {}, offset: {}, status: {:?}", limit, offset, status_filter);
     
     let mut conn = match db_pool.get().await {
         Ok(conn) => conn,
@@ -399,28 +503,35 @@ pub async fn get_approved_platforms(
             error!("Database connection error: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Database error: {}", e)
                 }))
             )
         }
     };
     
-    // Get the total count for pagination info (only approved platforms)
-    let total_count = match platforms::table
+    let mut count_query = platforms::table
+        .filter(platforms::is_approved.eq(true))
+        .into_boxed();
+    let mut list_query = platforms::table
         .filter(platforms::is_approved.eq(true))
+        .into_boxed();
+    if let Some(statuses) = &status_filter {
+        count_query = count_query.filter(platforms::status.eq_any(statuses.clone()));
+        list_query = list_query.filter(platforms::status.eq_any(statuses.clone()));
+    }
+    
+    // Get the total count for pagination info (only approved platforms)
+    let total_count = match count_query
         .count()
         .get_result::<i64>(&mut conn)
         .await {
         Ok(count) => count,
-        Err(_) => 0,
+        Err(e) => return db_error(e, "Not found", "Failed to count platforms"),
     };
     
-    let total_pages = (total_count as f64 / limit as f64).ceil() as i64;
-    
     // Query platforms with pagination, filtered by approval status
-    let platforms_result = platforms::table
-        .filter(platforms::is_approved.eq(true))
+    let platforms_result = list_query
         .order_by(platforms::created_at.desc())
         .limit(limit)
         .offset(offset)
@@ -435,7 +546,7 @@ pub async fn get_approved_platforms(
             for platform in platforms {
                 // Get moderator count
                 let moderator_count = platform_moderators::table
-                    .filter(platform_moderators::platform_id.eq(&platform.platform_id))
+                    .filter(platform_moderators::platform_ref.eq(platform.id))
                     .count()
                     .get_result::<i64>(&mut conn)
                     .await
@@ -443,7 +554,7 @@ pub async fn get_approved_platforms(
                 
                 // Get blocked profiles count
                 let blocked_count = platform_blocked_profiles::table
-                    .filter(platform_blocked_profiles::platform_id.eq(&platform.platform_id))
+                    .filter(platform_blocked_profiles::platform_ref.eq(platform.id))
                     .count()
                     .get_result::<i64>(&mut conn)
                     .await
@@ -466,7 +577,7 @@ pub async fn get_approved_platforms(
                     name: platform.name,
                     tagline: platform.tagline,
                     description: platform.description,
-                    logo: platform.logo,
+                    logo: if resolve_media { resolve_media_field(platform.logo, &api_config) } else { platform.logo },
                     developer_address: platform.developer_address,
                     terms_of_service: platform.terms_of_service,
                     privacy_policy: platform.privacy_policy,
@@ -481,27 +592,23 @@ pub async fn get_approved_platforms(
                     is_approved: platform.is_approved,
                     approval_changed_at: platform.approval_changed_at,
                     approved_by: platform.approved_by.clone(),
+                    total_users_count: platform.total_users_count,
+                    active_users_count: platform.active_users_count,
                     moderator_count,
                     blocked_profiles_count: blocked_count,
                 });
             }
             
-            (StatusCode::OK, Json(serde_json::json!({
+            (StatusCode::OK, JsonBody(serde_json::json!({
                 "platforms": platform_details,
-                "pagination": {
-                    "total": total_count,
-                    "limit": limit,
-                    "offset": offset,
-                    "page": page,
-                    "total_pages": total_pages
-                }
+                "pagination": params.pagination(total_count)
             })))
         },
         Err(e) => {
             error!("Failed to fetch approved platforms: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Failed to fetch approved platforms: {}", e)
                 }))
             )
@@ -509,12 +616,41 @@ pub async fn get_approved_platforms(
     }
 }
 
-/// Get the approval status of a specific platform
-pub async fn get_platform_approval_status(
+/// Get platforms awaiting approval, oldest first, as a moderation queue
+///
+/// Admin only when `HIDE_UNAPPROVED_PLATFORMS` is set, since every result is unapproved.
+pub async fn get_pending_platforms(
     State(db_pool): State<DbPool>,
-    Path(platform_id): Path<String>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    params: ListParams,
+    Query(query): Query<PlatformQuery>,
 ) -> impl IntoResponse {
-    debug!("Getting approval status for platform: {}", platform_id);
+    if hide_unapproved_platforms(&headers, &api_config) {
+        return (
+            StatusCode::FORBIDDEN,
+            JsonBody(serde_json::json!({
+                "error": "Unapproved platforms are hidden (HIDE_UNAPPROVED_PLATFORMS)"
+            }))
+        )
+    }
+    
+    let (limit, offset) = (params.limit, params.offset);
+    let resolve_media = api_config.resolve_media(query.resolve_media);
+    
+    let status_filter = match parse_status_filter(query.status.as_deref()) {
+        Ok(filter) => filter,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                JsonBody(serde_json::json!({
+                    "error": e
+                }))
+            )
+        }
+    };
+    
+    debug!("Getting pending platforms list with limit: {}, offset: {}, status: {:?}", limit, offset, status_filter);
     
     let mut conn = match db_pool.get().await {
         Ok(conn) => conn,
@@ -522,153 +658,1133 @@ pub async fn get_platform_approval_status(
             error!("Database connection error: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
+                JsonBody(serde_json::json!({
                     "error": format!("Database error: {}", e)
                 }))
             )
         }
     };
     
-    // Get the platform
-    let platform_result = platforms::table
-        .filter(platforms::platform_id.eq(&platform_id))
-        .select((
-            platforms::is_approved,
-            platforms::approval_changed_at,
-            platforms::approved_by
-        ))
-        .first::<(bool, Option<NaiveDateTime>, Option<String>)>(&mut conn)
+    let mut count_query = platforms::table
+        .filter(platforms::is_approved.eq(false))
+        .into_boxed();
+    let mut list_query = platforms::table
+        .filter(platforms::is_approved.eq(false))
+        .into_boxed();
+    if let Some(statuses) = &status_filter {
+        count_query = count_query.filter(platforms::status.eq_any(statuses.clone()));
+        list_query = list_query.filter(platforms::status.eq_any(statuses.clone()));
+    }
+    
+    // Get the total count for pagination info (only unapproved platforms)
+    let total_count = match count_query
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await {
+        Ok(count) => count,
+        Err(e) => return db_error(e, "Not found", "Failed to count platforms"),
+    };
+    
+    // Oldest submissions first so the queue is worked in arrival order
+    let platforms_result = list_query
+        .order_by((platforms::created_at.asc(), platforms::id.asc()))
+        .limit(limit)
+        .offset(offset)
+        .load::<Platform>(&mut conn)
         .await;
     
-    match platform_result {
-        Ok((is_approved, approval_changed_at, approved_by)) => {
-            (StatusCode::OK, Json(serde_json::json!({
-                "platform_id": platform_id,
-                "is_approved": is_approved,
-                "approval_changed_at": approval_changed_at,
-                "approved_by": approved_by
+    match platforms_result {
+        Ok(platforms) => {
+            // For each platform, get additional information like moderator count
+            let mut platform_details = Vec::with_capacity(platforms.len());
+            
+            for platform in platforms {
+                // Get moderator count
+                let moderator_count = platform_moderators::table
+                    .filter(platform_moderators::platform_ref.eq(platform.id))
+                    .count()
+                    .get_result::<i64>(&mut conn)
+                    .await
+                    .unwrap_or(0);
+                
+                // Get blocked profiles count
+                let blocked_count = platform_blocked_profiles::table
+                    .filter(platform_blocked_profiles::platform_ref.eq(platform.id))
+                    .count()
+                    .get_result::<i64>(&mut conn)
+                    .await
+                    .unwrap_or(0);
+                
+                // Convert platform_names from JSON to Vec<String>
+                let platform_names: Option<Vec<String>> = platform.platform_names
+                    .as_ref()
+                    .and_then(|json| serde_json::from_value(json.clone()).ok());
+                
+                // Convert links from JSON to Vec<String>
+                let links: Option<Vec<String>> = platform.links
+                    .as_ref()
+                    .and_then(|json| serde_json::from_value(json.clone()).ok());
+                
+                // Build response with details
+                platform_details.push(PlatformWithDetails {
+                    id: platform.id,
+                    platform_id: platform.platform_id,
+                    name: platform.name,
+                    tagline: platform.tagline,
+                    description: platform.description,
+                    logo: if resolve_media { resolve_media_field(platform.logo, &api_config) } else { platform.logo },
+                    developer_address: platform.developer_address,
+                    terms_of_service: platform.terms_of_service,
+                    privacy_policy: platform.privacy_policy,
+                    platform_names,
+                    links,
+                    status: platform.status,
+                    status_text: PlatformWithDetails::status_to_text(platform.status),
+                    release_date: platform.release_date,
+                    shutdown_date: platform.shutdown_date,
+                    created_at: platform.created_at,
+                    updated_at: platform.updated_at,
+                    is_approved: platform.is_approved,
+                    approval_changed_at: platform.approval_changed_at,
+                    approved_by: platform.approved_by.clone(),
+                    total_users_count: platform.total_users_count,
+                    active_users_count: platform.active_users_count,
+                    moderator_count,
+                    blocked_profiles_count: blocked_count,
+                });
+            }
+            
+            (StatusCode::OK, JsonBody(serde_json::json!({
+                "platforms": platform_details,
+                "pagination": params.pagination(total_count)
             })))
         },
-        Err(diesel::result::Error::NotFound) => {
-            debug!("Platform not found: {}", platform_id);
-            (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "error": "Platform not found"
-                }))
-            )
-        },
         Err(e) => {
-            error!("Failed to fetch platform approval status: {}", e);
+            error!("Failed to fetch pending platforms: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to fetch platform approval status: {}", e)
+                JsonBody(serde_json::json!({
+                    "error": format!("Failed to fetch pending platforms: {}", e)
                 }))
             )
         }
     }
 }
 
-pub async fn get_platform_blocked_profiles(
+/// Get the platforms created by a developer address, newest first
+pub async fn get_developer_platforms(
     State(db_pool): State<DbPool>,
-    Path(platform_id): Path<String>,
+    Path(address): Path<String>,
+    Extension(api_config): Extension<ApiConfig>,
+    params: ListParams,
     Query(query): Query<PlatformQuery>,
 ) -> impl IntoResponse {
-    let limit = query.limit.unwrap_or(50);
-    let offset = query.offset.unwrap_or(0);
-    let page = query.page.unwrap_or(1);
+    let (limit, offset) = (params.limit, params.offset);
+    let resolve_media = api_config.resolve_media(query.resolve_media);
     
-    // If page is provided, calculate the offset
-    let offset = if page > 1 {
-        (page - 1) * limit
-    } else {
-        offset
-    };
-    
-    debug!("Getting blocked profiles for platform: {}", platform_id);
-    
-    let mut conn = match db_pool.get().await {
-        Ok(conn) => conn,
+    let status_filter = match parse_status_filter(query.status.as_deref()) {
+        Ok(filter) => filter,
         Err(e) => {
-            error!("Database connection error: {}", e);
             return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Database error: {}", e)
+                StatusCode::BAD_REQUEST,
+                JsonBody(serde_json::json!({
+                    "error": e
                 }))
             )
         }
     };
     
-    // Check if platform exists
-    let platform_exists = match platforms::table
-        .filter(platforms::platform_id.eq(&platform_id))
-        .count()
-        .get_result::<i64>(&mut conn)
-        .await {
-        Ok(count) => count > 0,
+    debug!("Getting platforms for developer {} with limit: {}, offset: {}, status: {:?}", address, limit, offset, status_filter);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
         Err(e) => {
-            error!("Failed to check platform: {}", e);
+            error!("Database connection error: {}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to check platform: {}", e)
+                JsonBody(serde_json::json!({
+                    "error": format!("Database error: {}", e)
                 }))
             )
         }
     };
     
-    if !platform_exists {
-        debug!("Platform not found: {}", platform_id);
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
-                "error": "Platform not found"
-            }))
-        )
+    let mut count_query = platforms::table
+        .filter(platforms::developer_address.eq(&address))
+        .into_boxed();
+    let mut list_query = platforms::table
+        .filter(platforms::developer_address.eq(&address))
+        .into_boxed();
+    if let Some(statuses) = &status_filter {
+        count_query = count_query.filter(platforms::status.eq_any(statuses.clone()));
+        list_query = list_query.filter(platforms::status.eq_any(statuses.clone()));
     }
     
     // Get the total count for pagination info
-    let total_count = platform_blocked_profiles::table
-        .filter(platform_blocked_profiles::platform_id.eq(&platform_id))
+    let total_count = match count_query
         .count()
         .get_result::<i64>(&mut conn)
-        .await
-        .unwrap_or(0);
-    
-    let total_pages = (total_count as f64 / limit as f64).ceil() as i64;
+        .await {
+        Ok(count) => count,
+        Err(e) => return db_error(e, "Not found", "Failed to count platforms"),
+    };
     
-    // Get blocked profiles with pagination
-    let blocked_profiles_result = platform_blocked_profiles::table
-        .filter(platform_blocked_profiles::platform_id.eq(&platform_id))
-        .order_by(platform_blocked_profiles::created_at.desc())
+    let platforms_result = list_query
+        .order_by((platforms::created_at.desc(), platforms::id.desc()))
         .limit(limit)
         .offset(offset)
-        .load::<PlatformBlockedProfile>(&mut conn)
+        .load::<Platform>(&mut conn)
         .await;
     
-    match blocked_profiles_result {
-        Ok(blocked) => {
-            (StatusCode::OK, Json(serde_json::json!({
-                "blocked_profiles": blocked,
-                "pagination": {
-                    "total": total_count,
-                    "limit": limit,
-                    "offset": offset,
-                    "page": page,
-                    "total_pages": total_pages
-                }
-            })))
-        },
-        Err(e) => {
-            error!("Failed to fetch blocked profiles: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to fetch blocked profiles: {}", e)
-                }))
-            )
-        }
-    }
-}
\ No newline at end of file
+    match platforms_result {
+        Ok(platforms) => {
+            // For each platform, get additional information like moderator count
+            let mut platform_details = Vec::with_capacity(platforms.len());
+            
+            for platform in platforms {
+                // Get moderator count
+                let moderator_count = platform_moderators::table
+                    .filter(platform_moderators::platform_ref.eq(platform.id))
+                    .count()
+                    .get_result::<i64>(&mut conn)
+                    .await
+                    .unwrap_or(0);
+                
+                // Get blocked profiles count
+                let blocked_count = platform_blocked_profiles::table
+                    .filter(platform_blocked_profiles::platform_ref.eq(platform.id))
+                    .count()
+                    .get_result::<i64>(&mut conn)
+                    .await
+                    .unwrap_or(0);
+                
+                // Convert platform_names from JSON to Vec<String>
+                let platform_names: Option<Vec<String>> = platform.platform_names
+                    .as_ref()
+                    .and_then(|json| serde_json::from_value(json.clone()).ok());
+                
+                // Convert links from JSON to Vec<String>
+                let links: Option<Vec<String>> = platform.links
+                    .as_ref()
+                    .and_then(|json| serde_json::from_value(json.clone()).ok());
+                
+                // Build response with details
+                platform_details.push(PlatformWithDetails {
+                    id: platform.id,
+                    platform_id: platform.platform_id,
+                    name: platform.name,
+                    tagline: platform.tagline,
+                    description: platform.description,
+                    logo: if resolve_media { resolve_media_field(platform.logo, &api_config) } else { platform.logo },
+                    developer_address: platform.developer_address,
+                    terms_of_service: platform.terms_of_service,
+                    privacy_policy: platform.privacy_policy,
+                    platform_names,
+                    links,
+                    status: platform.status,
+                    status_text: PlatformWithDetails::status_to_text(platform.status),
+                    release_date: platform.release_date,
+                    shutdown_date: platform.shutdown_date,
+                    created_at: platform.created_at,
+                    updated_at: platform.updated_at,
+                    is_approved: platform.is_approved,
+                    approval_changed_at: platform.approval_changed_at,
+                    approved_by: platform.approved_by.clone(),
+                    total_users_count: platform.total_users_count,
+                    active_users_count: platform.active_users_count,
+                    moderator_count,
+                    blocked_profiles_count: blocked_count,
+                });
+            }
+            
+            (StatusCode::OK, JsonBody(serde_json::json!({
+                "developer_address": address,
+                "platforms": platform_details,
+                "pagination": params.pagination(total_count)
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch developer platforms: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Failed to fetch developer platforms: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Get the approval status of a specific platform
+pub async fn get_platform_approval_status(
+    State(db_pool): State<DbPool>,
+    Path(platform_id): Path<String>,
+) -> impl IntoResponse {
+    debug!("Getting approval status for platform: {}", platform_id);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Get the platform
+    let platform_result = platforms::table
+        .filter(platforms::platform_id.eq(&platform_id))
+        .select((
+            platforms::is_approved,
+            platforms::approval_changed_at,
+            platforms::approved_by
+        ))
+        .first::<(bool, Option<NaiveDateTime>, Option<String>)>(&mut conn)
+        .await;
+    
+    match platform_result {
+        Ok((is_approved, approval_changed_at, approved_by)) => {
+            (StatusCode::OK, JsonBody(serde_json::json!({
+                "platform_id": platform_id,
+                "is_approved": is_approved,
+                "approval_changed_at": approval_changed_at,
+                "approved_by": approved_by
+            })))
+        },
+        Err(e) => db_error(e, "Platform not found", "Failed to fetch platform approval status")
+    }
+}
+
+pub async fn get_platform_blocked_profiles(
+    State(db_pool): State<DbPool>,
+    Path(platform_id): Path<String>,
+    params: ListParams,
+) -> impl IntoResponse {
+    let (limit, offset) = (params.limit, params.offset);
+    
+    let order = params.order;
+    let sort = params.sort.as_deref().unwrap_or("created_at");
+    
+    debug!("Getting blocked profiles for platform: {}, sort: {} {}", platform_id, sort, order.to_str());
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Check if platform exists, resolving the numeric id its child rows reference
+    let platform_ref = match platforms::table
+        .filter(platforms::platform_id.eq(&platform_id))
+        .select(platforms::id)
+        .first::<i32>(&mut conn)
+        .await
+        .optional() {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            debug!("Platform not found: {}", platform_id);
+            return (
+                StatusCode::NOT_FOUND,
+                JsonBody(serde_json::json!({
+                    "error": "Platform not found"
+                }))
+            )
+        }
+        Err(e) => {
+            error!("Failed to check platform: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Failed to check platform: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Get the total count for pagination info
+    let total_count = match platform_blocked_profiles::table
+        .filter(platform_blocked_profiles::platform_ref.eq(platform_ref))
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await {
+        Ok(count) => count,
+        Err(e) => return db_error(e, "Not found", "Failed to count blocked profiles"),
+    };
+    
+    // Get blocked profiles with pagination, tie-breaking on id so pages are stable
+    let blocked_query = platform_blocked_profiles::table
+        .filter(platform_blocked_profiles::platform_ref.eq(platform_ref))
+        .into_boxed();
+    
+    let blocked_query = match (sort, order) {
+        ("created_at", SortOrder::Desc) => blocked_query.order_by((platform_blocked_profiles::created_at.desc(), platform_blocked_profiles::id.desc())),
+        ("created_at", SortOrder::Asc) => blocked_query.order_by((platform_blocked_profiles::created_at.asc(), platform_blocked_profiles::id.asc())),
+        ("profile_id", SortOrder::Desc) => blocked_query.order_by((platform_blocked_profiles::profile_id.desc(), platform_blocked_profiles::id.desc())),
+        ("profile_id", SortOrder::Asc) => blocked_query.order_by((platform_blocked_profiles::profile_id.asc(), platform_blocked_profiles::id.asc())),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                JsonBody(serde_json::json!({
+                    "error": format!("Unsupported sort: {}", sort)
+                }))
+            )
+        }
+    };
+    
+    let blocked_profiles_result = blocked_query
+        .limit(limit)
+        .offset(offset)
+        .load::<PlatformBlockedProfile>(&mut conn)
+        .await;
+    
+    match blocked_profiles_result {
+        Ok(blocked) => {
+            (StatusCode::OK, JsonBody(serde_json::json!({
+                "blocked_profiles": blocked,
+                "pagination": params.pagination(total_count)
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch blocked profiles: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Failed to fetch blocked profiles: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Join-date window for `get_platform_members_joined`; pagination comes from `ListParams`
+#[derive(Debug, Deserialize)]
+pub struct JoinedRangeQuery {
+    /// First day, inclusive (`YYYY-MM-DD`); defaults to 29 days before `to`
+    pub from: Option<chrono::NaiveDate>,
+    /// Last day, inclusive (`YYYY-MM-DD`); defaults to today (UTC)
+    pub to: Option<chrono::NaiveDate>,
+    /// Only used to tell whether `order` was given explicitly
+    pub order: Option<String>,
+}
+
+/// Get the profiles that joined a platform within a date range, earliest join first
+///
+/// `order=desc` lists the most recent joins first. Members whose profile isn't indexed are
+/// left out.
+pub async fn get_platform_members_joined(
+    State(db_pool): State<DbPool>,
+    Path(platform_id): Path<String>,
+    params: ListParams,
+    Query(range): Query<JoinedRangeQuery>,
+) -> impl IntoResponse {
+    let (limit, offset) = (params.limit, params.offset);
+    let to = range.to.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let from = range.from.unwrap_or(to - chrono::Duration::days(29));
+    
+    if from > to {
+        return (
+            StatusCode::BAD_REQUEST,
+            JsonBody(serde_json::json!({
+                "error": "from must not be after to"
+            }))
+        )
+    }
+    
+    // Whole days: from midnight on `from` up to (not including) midnight after `to`
+    let start = from.and_hms_opt(0, 0, 0).unwrap_or_default();
+    let end = (to + chrono::Duration::days(1)).and_hms_opt(0, 0, 0).unwrap_or_default();
+    
+    debug!("Getting members of platform {} who joined between {} and {}", platform_id, from, to);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let joined_in_range = || {
+        platform_memberships::table
+            .inner_join(platforms::table.on(platforms::id.nullable().eq(platform_memberships::platform_ref)))
+            .inner_join(profiles::table.on(profiles::profile_id.eq(platform_memberships::profile_id.nullable())))
+            .filter(platforms::platform_id.eq(platform_id.clone()))
+            .filter(platform_memberships::joined_at.ge(start))
+            .filter(platform_memberships::joined_at.lt(end))
+            .into_boxed()
+    };
+    
+    let total_count = match joined_in_range()
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await {
+        Ok(count) => count,
+        Err(e) => return db_error(e, "Not found", "Failed to count platform members"),
+    };
+    
+    // Cohorts read oldest first unless an order is given explicitly
+    let order = if range.order.is_none() { SortOrder::Asc } else { params.order };
+    let members_query = match order {
+        SortOrder::Asc => joined_in_range().order_by((platform_memberships::joined_at.asc(), platform_memberships::id.asc())),
+        SortOrder::Desc => joined_in_range().order_by((platform_memberships::joined_at.desc(), platform_memberships::id.desc())),
+    };
+    
+    let members_result = members_query
+        .select((Profile::as_select(), platform_memberships::joined_at))
+        .limit(limit)
+        .offset(offset)
+        .load::<(Profile, NaiveDateTime)>(&mut conn)
+        .await;
+    
+    match members_result {
+        Ok(members) => {
+            let members: Vec<serde_json::Value> = members
+                .into_iter()
+                .map(|(profile, joined_at)| serde_json::json!({
+                    "profile": profile,
+                    "joined_at": joined_at
+                }))
+                .collect();
+            (StatusCode::OK, JsonBody(serde_json::json!({
+                "platform_id": platform_id,
+                "from": from,
+                "to": to,
+                "members": members,
+                "pagination": params.pagination(total_count)
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch platform members: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Failed to fetch platform members: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Activity on a platform for one day, used in the platform timeline
+#[derive(Debug, QueryableByName)]
+struct DailySnapshot {
+    #[diesel(sql_type = diesel::sql_types::Date)]
+    date: chrono::NaiveDate,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    active_profiles: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    new_members: i64,
+}
+
+/// Get a platform's history as one feed: its events interleaved with daily activity snapshots
+///
+/// Oldest first by default (`order=desc` for newest first). Daily snapshots are placed at the
+/// end of their day so they follow that day's events.
+pub async fn get_platform_timeline(
+    State(db_pool): State<DbPool>,
+    Path(platform_id): Path<String>,
+    params: ListParams,
+    Query(timeline): Query<TimelineQuery>,
+) -> impl IntoResponse {
+    // Timelines read naturally oldest first, unlike the other lists
+    let order = if timeline.order.is_none() { SortOrder::Asc } else { params.order };
+    let (limit, offset) = (params.limit, params.offset);
+    
+    debug!("Getting timeline for platform: {}, order: {}", platform_id, order.to_str());
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    if let Err(e) = platforms::table
+        .filter(platforms::platform_id.eq(&platform_id))
+        .select(platforms::id)
+        .first::<i32>(&mut conn)
+        .await
+    {
+        return db_error(e, "Platform not found", "Failed to check platform");
+    }
+    
+    // Each source can contribute at most offset + limit entries to the requested page
+    let window = offset.saturating_add(limit);
+    
+    let events_query = crate::schema::platform_events::table
+        .filter(crate::schema::platform_events::platform_id.eq(&platform_id))
+        .into_boxed();
+    let events_query = match order {
+        SortOrder::Asc => events_query.order_by((crate::schema::platform_events::created_at.asc(), crate::schema::platform_events::id.asc())),
+        SortOrder::Desc => events_query.order_by((crate::schema::platform_events::created_at.desc(), crate::schema::platform_events::id.desc())),
+    };
+    let events = match events_query
+        .limit(window)
+        .load::<crate::models::platform::PlatformEvent>(&mut conn)
+        .await {
+        Ok(events) => events,
+        Err(e) => return db_error(e, "Not found", "Failed to fetch platform events"),
+    };
+    
+    // Direction comes from the SortOrder enum, never from raw input
+    let snapshots = match diesel::sql_query(format!(
+        "SELECT date,
+                COALESCE(a.active_profiles, 0) AS active_profiles,
+                COALESCE(m.new_members, 0) AS new_members
+         FROM (
+             SELECT date, COUNT(*) AS active_profiles
+             FROM platform_daily_active_profiles
+             WHERE platform_id = $1
+             GROUP BY date
+         ) a
+         FULL OUTER JOIN (
+             SELECT DATE(joined_at) AS date, COUNT(*) AS new_members
+             FROM platform_memberships
+             WHERE platform_id = $1
+             GROUP BY DATE(joined_at)
+         ) m USING (date)
+         ORDER BY date {}
+         LIMIT $2",
+        order.to_str()
+    ))
+    .bind::<diesel::sql_types::Text, _>(&platform_id)
+    .bind::<diesel::sql_types::BigInt, _>(window)
+    .load::<DailySnapshot>(&mut conn)
+    .await {
+        Ok(snapshots) => snapshots,
+        Err(e) => return db_error(e, "Not found", "Failed to fetch platform activity"),
+    };
+    
+    let mut entries: Vec<(NaiveDateTime, serde_json::Value)> = events
+        .into_iter()
+        .map(|event| {
+            (event.created_at, serde_json::json!({
+                "type": "event",
+                "timestamp": event.created_at,
+                "event_type": event.event_type,
+                "event_id": event.event_id,
+                "data": event.event_data
+            }))
+        })
+        .collect();
+    
+    entries.extend(snapshots.into_iter().map(|snapshot| {
+        let timestamp = snapshot.date
+            .and_hms_opt(23, 59, 59)
+            .unwrap_or_default();
+        (timestamp, serde_json::json!({
+            "type": "daily_stats",
+            "timestamp": timestamp,
+            "date": snapshot.date,
+            "active_profiles": snapshot.active_profiles,
+            "new_members": snapshot.new_members
+        }))
+    }));
+    
+    // Stable sort keeps events ahead of a snapshot with the same timestamp
+    match order {
+        SortOrder::Asc => entries.sort_by(|a, b| a.0.cmp(&b.0)),
+        SortOrder::Desc => entries.sort_by(|a, b| b.0.cmp(&a.0)),
+    }
+    
+    let timeline: Vec<serde_json::Value> = entries
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .map(|(_, entry)| entry)
+        .collect();
+    
+    (StatusCode::OK, JsonBody(serde_json::json!({
+        "platform_id": platform_id,
+        "order": order.to_str(),
+        "limit": limit,
+        "offset": offset,
+        "timeline": timeline
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{header, HeaderValue};
+    use crate::db::test_support::{insert_platform, test_database, unique_id};
+    use crate::models::platform::{PLATFORM_STATUS_ALPHA, PLATFORM_STATUS_BETA, PLATFORM_STATUS_LIVE};
+
+    fn api_config(hide_unapproved_platforms: bool) -> ApiConfig {
+        ApiConfig {
+            admin_token: Some("secret".to_string()),
+            max_page_size: 100,
+            recent_profiles_default: 10,
+            ipfs_gateway: String::new(),
+            arweave_gateway: String::new(),
+            resolve_media_default: false,
+            max_request_body_bytes: 1024,
+            pretty_json_default: false,
+            hide_unapproved_platforms,
+        }
+    }
+
+    fn admin_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        headers
+    }
+
+    async fn status_of(pool: DbPool, config: ApiConfig, headers: HeaderMap, platform_id: &str) -> StatusCode {
+        let media = MediaQuery { resolve_media: None };
+        get_platform_by_id(State(pool), Extension(config), headers, Path(platform_id.to_string()), Query(media))
+            .await
+            .into_response()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn unapproved_platform_is_hidden_only_when_the_flag_is_on() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (unapproved, approved) = (unique_id("platform"), unique_id("platform"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_platform(&mut conn, &unapproved).await;
+            let id = insert_platform(&mut conn, &approved).await;
+            diesel::update(platforms::table.find(id))
+                .set(platforms::is_approved.eq(true))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(status_of(pool.clone(), api_config(true), HeaderMap::new(), &unapproved).await, StatusCode::NOT_FOUND);
+        assert_eq!(status_of(pool.clone(), api_config(true), HeaderMap::new(), &approved).await, StatusCode::OK);
+        assert_eq!(status_of(pool.clone(), api_config(false), HeaderMap::new(), &unapproved).await, StatusCode::OK);
+
+        // The admin token overrides the flag
+        assert_eq!(status_of(pool, api_config(true), admin_headers(), &unapproved).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn platform_lookups_send_json_with_a_charset() {
+        use crate::api::response::JSON_CONTENT_TYPE;
+        
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let platform_id = unique_id("platform");
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_platform(&mut conn, &platform_id).await;
+        }
+        
+        for (platform_id, status) in [(platform_id.as_str(), StatusCode::OK), ("0xmissing", StatusCode::NOT_FOUND)] {
+            let media = MediaQuery { resolve_media: None };
+            let response = get_platform_by_id(State(pool.clone()), Extension(api_config(false)), HeaderMap::new(), Path(platform_id.to_string()), Query(media))
+                .await
+                .into_response();
+            assert_eq!(response.status(), status);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], JSON_CONTENT_TYPE);
+        }
+    }
+
+    #[tokio::test]
+    async fn platform_blocked_profiles_follow_the_requested_order() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let platform_id = unique_id("platform");
+        let (first, second) = (unique_id("p1"), unique_id("p2"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            let platform_ref = insert_platform(&mut conn, &platform_id).await;
+            for (profile_id, seconds) in [(&first, 1_700_000_000), (&second, 1_700_000_100)] {
+                diesel::insert_into(platform_blocked_profiles::table)
+                    .values((
+                        platform_blocked_profiles::platform_id.eq(&platform_id),
+                        platform_blocked_profiles::profile_id.eq(profile_id),
+                        platform_blocked_profiles::blocked_by.eq("0xmoderator"),
+                        platform_blocked_profiles::created_at.eq(chrono::DateTime::from_timestamp(seconds, 0).unwrap().naive_utc()),
+                        platform_blocked_profiles::platform_ref.eq(platform_ref),
+                    ))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        
+        for (order, expected) in [("desc", [&second, &first]), ("asc", [&first, &second])] {
+            let params = ListParams::parse(None, None, None, Some("created_at".to_string()), Some(order), 100).unwrap();
+            let response = get_platform_blocked_profiles(State(pool.clone()), Path(platform_id.clone()), params)
+                .await
+                .into_response();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value =
+                serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+            let ids: Vec<&str> = body["blocked_profiles"].as_array().unwrap().iter().map(|b| b["profile_id"].as_str().unwrap()).collect();
+            assert_eq!(ids, expected.map(String::as_str));
+            assert_eq!(body["pagination"]["total"], 2);
+        }
+    }
+
+    #[test]
+    fn status_filter_accepts_several_names_and_codes() {
+        assert_eq!(parse_status_filter(None), Ok(None));
+        assert_eq!(parse_status_filter(Some(" ")), Ok(None));
+        assert_eq!(
+            parse_status_filter(Some("live, Beta,6")),
+            Ok(Some(vec![PLATFORM_STATUS_LIVE, PLATFORM_STATUS_BETA, PLATFORM_STATUS_SHUTDOWN]))
+        );
+        assert!(parse_status_filter(Some("live,retired")).is_err());
+        assert!(parse_status_filter(Some("7")).is_err());
+    }
+
+    #[tokio::test]
+    async fn platforms_are_filtered_by_several_statuses() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (live, beta, alpha) = (unique_id("platform"), unique_id("platform"), unique_id("platform"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            for (platform_id, status) in [(&live, PLATFORM_STATUS_LIVE), (&beta, PLATFORM_STATUS_BETA), (&alpha, PLATFORM_STATUS_ALPHA)] {
+                let id = insert_platform(&mut conn, platform_id).await;
+                diesel::update(platforms::table.find(id))
+                    .set(platforms::status.eq(status))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let list = |status: &str| {
+            let pool = pool.clone();
+            let query = PlatformQuery { status: Some(status.to_string()), resolve_media: None };
+            async move {
+                let params = ListParams::parse(Some("100"), None, None, None, None, 100).unwrap();
+                get_platforms(State(pool), Extension(api_config(false)), HeaderMap::new(), params, Query(query))
+                    .await
+                    .into_response()
+            }
+        };
+
+        let response = list("live,beta").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        let listed = body["platforms"].as_array().unwrap();
+        assert!(listed.iter().all(|p| [PLATFORM_STATUS_LIVE, PLATFORM_STATUS_BETA].contains(&(p["status"].as_i64().unwrap() as i16))));
+        let ids: Vec<&str> = listed.iter().map(|p| p["platform_id"].as_str().unwrap()).collect();
+        assert!(ids.contains(&live.as_str()));
+        assert!(ids.contains(&beta.as_str()));
+        assert!(!ids.contains(&alpha.as_str()));
+
+        assert_eq!(list("live,retired").await.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn platforms_without_an_approval_date_sort_last_in_both_orders() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (dated, undated) = (unique_id("platform"), unique_id("platform"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            let id = insert_platform(&mut conn, &dated).await;
+            let far_future = chrono::NaiveDate::from_ymd_opt(2999, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+            diesel::update(platforms::table.find(id))
+                .set(platforms::approval_changed_at.eq(far_future))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            insert_platform(&mut conn, &undated).await;
+        }
+
+        let list = |order: &'static str, offset: Option<String>| {
+            let pool = pool.clone();
+            async move {
+                let params = ListParams::parse(Some("100"), offset.as_deref(), None, Some("approval_changed_at".to_string()), Some(order), 100).unwrap();
+                let query = PlatformQuery { status: None, resolve_media: None };
+                let response = get_platforms(State(pool), Extension(api_config(false)), HeaderMap::new(), params, Query(query))
+                    .await
+                    .into_response();
+                assert_eq!(response.status(), StatusCode::OK);
+                serde_json::from_slice::<serde_json::Value>(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+            }
+        };
+
+        // Nothing is dated later than the far-future row, so it leads the descending list
+        let first_page = list("desc", None).await;
+        assert_eq!(first_page["platforms"][0]["approval_changed_at"], "2999-01-01T00:00:00");
+        
+        // Start a little before the end, as other tests may remove platforms meanwhile
+        for order in ["desc", "asc"] {
+            let total = list(order, None).await["pagination"]["total"].as_i64().unwrap();
+            let last_page = list(order, Some((total - 10).max(0).to_string())).await;
+            let last = last_page["platforms"].as_array().unwrap().last().unwrap();
+            assert!(last["approval_changed_at"].is_null(), "{} order ends with a dated platform", order);
+        }
+    }
+
+    #[tokio::test]
+    async fn timeline_interleaves_events_and_daily_stats_by_time() {
+        use crate::schema::platform_events;
+        
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let platform_id = unique_id("platform");
+        let day = |day: u32, hour: u32| chrono::NaiveDate::from_ymd_opt(2024, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_platform(&mut conn, &platform_id).await;
+            for (event_type, created_at) in [("PlatformCreatedEvent", day(1, 10)), ("PlatformApprovalChangedEvent", day(2, 10))] {
+                diesel::insert_into(platform_events::table)
+                    .values((
+                        platform_events::event_type.eq(event_type),
+                        platform_events::platform_id.eq(&platform_id),
+                        platform_events::event_data.eq(serde_json::json!({})),
+                        platform_events::created_at.eq(created_at),
+                    ))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+            diesel::insert_into(platform_memberships::table)
+                .values((
+                    platform_memberships::platform_id.eq(&platform_id),
+                    platform_memberships::profile_id.eq(unique_id("profile")),
+                    platform_memberships::joined_at.eq(day(1, 12)),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        
+        let timeline = |order: Option<&str>| {
+            let params = ListParams::parse(None, None, None, None, order, 100).unwrap();
+            let query = TimelineQuery { order: order.map(str::to_string) };
+            let response = get_platform_timeline(State(pool.clone()), Path(platform_id.clone()), params, Query(query));
+            async move {
+                let response = response.await.into_response();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body: serde_json::Value =
+                    serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+                body["timeline"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|entry| entry["event_type"].as_str().unwrap_or(entry["type"].as_str().unwrap()).to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+        
+        // The day's snapshot closes the day, after its events and before the next day's
+        assert_eq!(timeline(None).await, ["PlatformCreatedEvent", "daily_stats", "PlatformApprovalChangedEvent"]);
+        assert_eq!(timeline(Some("desc")).await, ["PlatformApprovalChangedEvent", "daily_stats", "PlatformCreatedEvent"]);
+    }
+
+    #[tokio::test]
+    async fn pending_queue_lists_only_unapproved_platforms_oldest_first() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (older, newer, approved) = (unique_id("platform"), unique_id("platform"), unique_id("platform"));
+        let mut conn = pool.get().await.unwrap();
+        // Dated before anything else in the table so they head the queue
+        for (platform_id, seconds, is_approved) in [(&newer, 631_152_100, false), (&older, 631_152_000, false), (&approved, 631_151_900, true)] {
+            let id = insert_platform(&mut conn, platform_id).await;
+            diesel::update(platforms::table.find(id))
+                .set((
+                    platforms::is_approved.eq(is_approved),
+                    platforms::created_at.eq(chrono::DateTime::from_timestamp(seconds, 0).unwrap().naive_utc()),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        
+        let params = ListParams::parse(Some("2"), None, None, None, None, 100).unwrap();
+        let query = PlatformQuery { status: None, resolve_media: None };
+        let response = get_pending_platforms(State(pool.clone()), Extension(api_config(false)), HeaderMap::new(), params, Query(query))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        let ids: Vec<&str> = body["platforms"].as_array().unwrap().iter().map(|p| p["platform_id"].as_str().unwrap()).collect();
+        
+        // Don't leave rows that would head the queue in later runs
+        diesel::delete(platforms::table.filter(platforms::platform_id.eq_any([&older, &newer, &approved])))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(ids, [older.as_str(), newer.as_str()]);
+        
+        // Every result is unapproved, so the queue is hidden along with them
+        let params = ListParams::parse(None, None, None, None, None, 100).unwrap();
+        let query = PlatformQuery { status: None, resolve_media: None };
+        let response = get_pending_platforms(State(pool), Extension(api_config(true)), HeaderMap::new(), params, Query(query))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn developer_platforms_lists_only_that_developers_platforms() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let developer = unique_id("developer");
+        let (first, second, other) = (unique_id("platform"), unique_id("platform"), unique_id("platform"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            for platform_id in [&first, &second, &other] {
+                let id = insert_platform(&mut conn, platform_id).await;
+                if platform_id != &other {
+                    diesel::update(platforms::table.find(id))
+                        .set(platforms::developer_address.eq(&developer))
+                        .execute(&mut conn)
+                        .await
+                        .unwrap();
+                }
+                if platform_id == &second {
+                    diesel::insert_into(platform_moderators::table)
+                        .values((
+                            platform_moderators::platform_id.eq(platform_id),
+                            platform_moderators::moderator_address.eq("0xmoderator"),
+                            platform_moderators::added_by.eq(&developer),
+                            platform_moderators::created_at.eq(chrono::Utc::now().naive_utc()),
+                            platform_moderators::platform_ref.eq(id),
+                        ))
+                        .execute(&mut conn)
+                        .await
+                        .unwrap();
+                }
+            }
+        }
+        
+        let params = ListParams::parse(None, None, None, None, None, 100).unwrap();
+        let query = PlatformQuery { status: None, resolve_media: None };
+        let response = get_developer_platforms(State(pool), Path(developer), Extension(api_config(false)), params, Query(query))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        
+        // Newest first, each with its counts
+        let listed: Vec<(&str, i64)> = body["platforms"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| (p["platform_id"].as_str().unwrap(), p["moderator_count"].as_i64().unwrap()))
+            .collect();
+        assert_eq!(listed, [(second.as_str(), 1), (first.as_str(), 0)]);
+        assert_eq!(body["pagination"]["total"], 2);
+    }
+
+    #[tokio::test]
+    async fn members_joined_are_filtered_to_the_date_window() {
+        use crate::db::test_support::insert_profile;
+        
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let platform_id = unique_id("platform");
+        let (before, first, last) = (unique_id("profile"), unique_id("profile"), unique_id("profile"));
+        let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        {
+            let mut conn = pool.get().await.unwrap();
+            let platform_ref = insert_platform(&mut conn, &platform_id).await;
+            for (profile_id, joined) in [(&before, day(1)), (&first, day(5)), (&last, day(9))] {
+                insert_profile(&mut conn, profile_id, &unique_id("user")).await;
+                diesel::insert_into(platform_memberships::table)
+                    .values((
+                        platform_memberships::platform_id.eq(&platform_id),
+                        platform_memberships::profile_id.eq(profile_id),
+                        platform_memberships::joined_at.eq(joined.and_hms_opt(23, 30, 0).unwrap()),
+                        platform_memberships::platform_ref.eq(platform_ref),
+                    ))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        
+        // The last day of the window counts in full
+        let members = |order: Option<&'static str>| {
+            let (pool, platform_id) = (pool.clone(), platform_id.clone());
+            async move {
+                let params = ListParams::parse(None, None, None, None, order, 100).unwrap();
+                let range = JoinedRangeQuery { from: Some(day(2)), to: Some(day(9)), order: order.map(str::to_string) };
+                let response = get_platform_members_joined(State(pool), Path(platform_id), params, Query(range))
+                    .await
+                    .into_response();
+                assert_eq!(response.status(), StatusCode::OK);
+                serde_json::from_slice::<serde_json::Value>(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+            }
+        };
+        let ids = |body: &serde_json::Value| -> Vec<String> {
+            body["members"].as_array().unwrap().iter().map(|m| m["profile"]["profile_id"].as_str().unwrap().to_string()).collect()
+        };
+        
+        let body = members(None).await;
+        assert_eq!(ids(&body), [first.clone(), last.clone()]);
+        assert_eq!(body["pagination"]["total"], 2);
+        assert_eq!(ids(&members(Some("desc")).await), [last, first]);
+    }
+
+    #[tokio::test]
+    async fn status_breakdown_counts_seeded_platforms_per_status() {
+        use crate::models::platform::{PLATFORM_STATUS_MAINTENANCE, PLATFORM_STATUS_SUNSET};
+        
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let breakdown = || {
+            let pool = pool.clone();
+            async move {
+                let response = get_platform_status_breakdown(State(pool), Extension(api_config(false)), HeaderMap::new())
+                    .await
+                    .into_response();
+                assert_eq!(response.status(), StatusCode::OK);
+                serde_json::from_slice::<serde_json::Value>(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap()
+            }
+        };
+        
+        // Other tests add platforms in statuses these don't use, so compare the change
+        let before = breakdown().await;
+        {
+            let mut conn = pool.get().await.unwrap();
+            for status in [PLATFORM_STATUS_MAINTENANCE, PLATFORM_STATUS_MAINTENANCE, PLATFORM_STATUS_SUNSET, 9] {
+                let id = insert_platform(&mut conn, &unique_id("platform")).await;
+                diesel::update(platforms::table.find(id))
+                    .set(platforms::status.eq(status))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        let after = breakdown().await;
+        
+        let added = |name: &str| after["statuses"][name].as_i64().unwrap() - before["statuses"][name].as_i64().unwrap_or(0);
+        assert_eq!(added("maintenance"), 2);
+        assert_eq!(added("sunset"), 1);
+        assert_eq!(added("unknown"), 1);
+        assert_eq!(after["statuses"].as_object().unwrap().len(), 8);
+        let summed: i64 = after["statuses"].as_object().unwrap().values().map(|count| count.as_i64().unwrap()).sum();
+        assert_eq!(after["total"], summed);
+    }
+}
+
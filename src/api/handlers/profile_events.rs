@@ -2,16 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use axum::extract::{Path, Query, State};
-use axum::http::StatusCode;
-use axum::Json;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error};
 
+use crate::config::ApiConfig;
 use crate::db::DbPool;
 use crate::models::profile_events::ProfileEvent;
 use crate::schema;
+use crate::api::params::MAX_OFFSET;
 
 /// Query parameters for fetching profile events
 #[derive(Debug, Deserialize)]
@@ -83,7 +86,7 @@ pub async fn get_profile_events(
     let events = query_builder
         .order_by(schema::profile_events::created_at.desc())
         .limit(query.limit)
-        .offset(query.offset)
+        .offset(query.offset.clamp(0, MAX_OFFSET))
         .load::<ProfileEvent>(&mut conn)
         .await
         .map_err(|e| {
@@ -198,4 +201,194 @@ pub async fn get_blocking_history(
     debug!("Found {} blocking events for profile_id: {}", events.len(), profile_id);
     
     Ok(Json(ProfileEventsResponse { events, total }))
-}
\ No newline at end of file
+}
+
+/// Query parameters for the profile timeline feed
+#[derive(Debug, Deserialize)]
+pub struct TimelineFeedQuery {
+    /// Number of recent events to include (default 50, capped at `MAX_PAGE_SIZE`)
+    pub limit: Option<i64>,
+}
+
+/// Get a profile's recent events as an RSS 2.0 feed, newest first
+///
+/// One item per event, titled with the event type and dated with the event time. Feed links
+/// are built from the request's `Host` header, since the API doesn't know its public URL.
+pub async fn get_profile_timeline_rss(
+    Path(profile_id): Path<String>,
+    Query(query): Query<TimelineFeedQuery>,
+    State(pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    debug!("Getting timeline feed for profile_id: {}", profile_id);
+    
+    let mut conn = pool.get()
+        .await
+        .map_err(|e| {
+            error!("Failed to get database connection: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    
+    let username = schema::profiles::table
+        .filter(schema::profiles::profile_id.eq(&profile_id))
+        .select(schema::profiles::username)
+        .first::<String>(&mut conn)
+        .await
+        .optional()
+        .map_err(|e| {
+            error!("Failed to get profile for timeline feed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    
+    let limit = query.limit.unwrap_or(50).clamp(1, api_config.max_page_size.max(1));
+    let events = schema::profile_events::table
+        .filter(schema::profile_events::profile_id.eq(&profile_id))
+        .order_by(schema::profile_events::created_at.desc())
+        .limit(limit)
+        .load::<ProfileEvent>(&mut conn)
+        .await
+        .map_err(|e| {
+            error!("Failed to get profile events for timeline feed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    
+    debug!("Found {} timeline feed events for profile_id: {}", events.len(), profile_id);
+    
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or("localhost");
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|proto| proto.to_str().ok())
+        .filter(|proto| *proto == "https")
+        .unwrap_or("http");
+    let base_url = format!("{}://{}", scheme, host);
+    
+    Ok((
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        timeline_rss(&base_url, &profile_id, &username, &events),
+    ).into_response())
+}
+
+/// Serialize profile events as an RSS 2.0 document
+fn timeline_rss(base_url: &str, profile_id: &str, username: &str, events: &[ProfileEvent]) -> String {
+    let mut rss = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n");
+    rss.push_str(&format!("<title>Activity of {}</title>\n", xml_escape(username)));
+    rss.push_str(&format!(
+        "<link>{}</link>\n",
+        xml_escape(&format!("{}/profile/{}", base_url, profile_id))
+    ));
+    rss.push_str(&format!("<description>Recent events for profile {}</description>\n", xml_escape(profile_id)));
+    if let Some(latest) = events.first() {
+        rss.push_str(&format!("<lastBuildDate>{}</lastBuildDate>\n", latest.created_at.and_utc().to_rfc2822()));
+    }
+    
+    for event in events {
+        // The on-chain event id is stable across reindexing; the row id is the fallback
+        let guid = event.event_id.clone().unwrap_or_else(|| format!("profile-event-{}", event.id));
+        rss.push_str("<item>\n");
+        rss.push_str(&format!("<title>{}</title>\n", xml_escape(&event.event_type)));
+        rss.push_str(&format!(
+            "<link>{}</link>\n",
+            xml_escape(&format!("{}/profile-events/{}", base_url, profile_id))
+        ));
+        rss.push_str(&format!("<description>{}</description>\n", xml_escape(&event.event_data.to_string())));
+        rss.push_str(&format!("<pubDate>{}</pubDate>\n", event.created_at.and_utc().to_rfc2822()));
+        rss.push_str(&format!("<guid isPermaLink=\"false\">{}</guid>\n", xml_escape(&guid)));
+        rss.push_str("</item>\n");
+    }
+    
+    rss.push_str("</channel>\n</rss>\n");
+    rss
+}
+
+/// Escape text for an XML element or attribute, dropping characters XML 1.0 can't hold
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: i32, event_type: &str, event_data: serde_json::Value, event_id: Option<&str>) -> ProfileEvent {
+        let created_at = chrono::DateTime::from_timestamp(1_700_000_000 + id as i64, 0).unwrap().naive_utc();
+        ProfileEvent {
+            id,
+            event_type: event_type.to_string(),
+            profile_id: "0xprofile".to_string(),
+            event_data,
+            event_id: event_id.map(str::to_string),
+            created_at,
+            updated_at: created_at,
+        }
+    }
+
+    /// Every `&` in `xml` starts one of the predefined entities
+    fn entities_are_valid(xml: &str) -> bool {
+        xml.match_indices('&').all(|(i, _)| {
+            ["&amp;", "&lt;", "&gt;", "&quot;", "&apos;"].iter().any(|entity| xml[i..].starts_with(entity))
+        })
+    }
+
+    #[test]
+    fn escape_covers_markup_and_drops_invalid_characters() {
+        assert_eq!(xml_escape(r#"<a href="x">Tom & 'Jerry'</a>"#), "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;");
+        assert_eq!(xml_escape("tab\there\u{0}\u{1b}\u{FFFF}"), "tab\there");
+        assert_eq!(xml_escape("line\nbreak"), "line\nbreak");
+    }
+
+    #[test]
+    fn timeline_is_a_valid_rss_document() {
+        let events = vec![
+            event(2, "ProfileUpdated", serde_json::json!({ "bio": "<b>me</b> & you" }), Some("0xtx:1")),
+            event(1, "ProfileCreated", serde_json::json!({ "username": "alice" }), None),
+        ];
+        let rss = timeline_rss("https://indexer.example", "0xprofile", "al<ice>", &events);
+        
+        assert!(rss.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n"));
+        assert!(rss.ends_with("</channel>\n</rss>\n"));
+        assert_eq!(rss.matches("<item>").count(), 2);
+        assert_eq!(rss.matches("</item>").count(), 2);
+        assert!(entities_are_valid(&rss));
+        
+        assert!(rss.contains("<title>Activity of al&lt;ice&gt;</title>"));
+        assert!(rss.contains("<link>https://indexer.example/profile/0xprofile</link>"));
+        assert!(rss.contains("&lt;b&gt;me&lt;/b&gt; &amp; you"));
+        assert!(rss.contains("<guid isPermaLink=\"false\">0xtx:1</guid>"));
+        assert!(rss.contains("<guid isPermaLink=\"false\">profile-event-1</guid>"));
+        
+        // Newest event first, and the channel is dated by it
+        let updated = rss.find("<title>ProfileUpdated</title>").unwrap();
+        let created = rss.find("<title>ProfileCreated</title>").unwrap();
+        assert!(updated < created);
+        let latest = events[0].created_at.and_utc().to_rfc2822();
+        assert!(rss.contains(&format!("<lastBuildDate>{}</lastBuildDate>", latest)));
+        assert!(rss.contains(&format!("<pubDate>{}</pubDate>", latest)));
+    }
+
+    #[test]
+    fn empty_timeline_has_no_items_or_build_date() {
+        let rss = timeline_rss("http://localhost", "0xprofile", "alice", &[]);
+        
+        assert!(!rss.contains("<item>"));
+        assert!(!rss.contains("<lastBuildDate>"));
+        assert!(rss.ends_with("</channel>\n</rss>\n"));
+    }
+}
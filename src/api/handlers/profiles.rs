@@ -3,41 +3,196 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
+    Extension,
     Json,
 };
+use chrono::{NaiveDate, NaiveDateTime, Utc};
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
-use serde::{Deserialize};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
 
+use crate::api::auth::{require_admin, require_owner_or_admin};
+use crate::api::media::{resolve_profile_media, MediaQuery};
+use crate::config::ApiConfig;
 use crate::db::DbPool;
-use crate::models::Profile;
-use crate::schema::profiles;
+use crate::models::{Profile, ProfileBlock, ProfileEvent, SocialGraphRelationship, PlatformBlockedProfile};
+use crate::schema::{profiles, profiles_blocked, profile_events, social_graph_relationships, platform_memberships, platform_blocked_profiles};
+use crate::api::params::{page_offset, ListParams};
+use crate::models::social_graph::parse_window;
 
+/// `/recent-profiles` filters; pagination comes from `ListParams`
 #[derive(Debug, Deserialize)]
 pub struct ProfileQuery {
-    pub limit: Option<i64>,
-    pub offset: Option<i64>,
-    pub page: Option<i64>,
+    /// Rewrite ipfs:// and ar:// media URLs to gateway URLs
+    pub resolve_media: Option<bool>,
+    /// Only profiles with (`true`) or without (`false`) a username
+    pub has_username: Option<bool>,
+    /// Only profiles with (`true`) or without (`false`) a profile photo
+    pub has_photo: Option<bool>,
+    /// Only profiles with an `id` below this one; pass the previous page's `next_cursor`
+    pub before_id: Option<i32>,
+}
+
+/// SQL condition for a profile having chosen a username
+///
+/// Profiles created without one get the placeholder `user_` + the first 8 characters of the
+/// owner address (see `ProfileCreatedEvent::into_model`), which doesn't count.
+const HAS_USERNAME_SQL: &str = "(username <> '' AND username <> 'user_' || LEFT(owner_address, 8))";
+
+/// Apply the `has_username`/`has_photo` filters; empty strings count as missing
+fn filter_profile_completeness<'a>(
+    mut query: profiles::BoxedQuery<'a, diesel::pg::Pg>,
+    has_username: Option<bool>,
+    has_photo: Option<bool>,
+) -> profiles::BoxedQuery<'a, diesel::pg::Pg> {
+    use diesel::dsl::sql;
+    use diesel::sql_types::Bool;
+    
+    match has_username {
+        Some(true) => query = query.filter(sql::<Bool>(HAS_USERNAME_SQL)),
+        Some(false) => query = query.filter(sql::<Bool>(&format!("NOT {}", HAS_USERNAME_SQL))),
+        None => {}
+    }
+    match has_photo {
+        Some(true) => query = query.filter(profiles::profile_photo.is_not_null().and(profiles::profile_photo.ne(""))),
+        Some(false) => query = query.filter(profiles::profile_photo.is_null().or(profiles::profile_photo.eq(""))),
+        None => {}
+    }
+    query
 }
 
 /// Get a list of latest profiles with pagination in descending order by id
 pub async fn latest_profiles(
     State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    params: ListParams,
     Query(query): Query<ProfileQuery>,
 ) -> impl IntoResponse {
-    let limit = query.limit.unwrap_or(50);
-    let offset = query.offset.unwrap_or(0);
-    let page = query.page.unwrap_or(1);
+    // Default and cap come from RECENT_PROFILES_DEFAULT and MAX_PAGE_SIZE
+    let mut params = params.default_limit(api_config.recent_profiles_default, api_config.max_page_size);
+    // A cursor replaces offset paging
+    if query.before_id.is_some() {
+        params.offset = 0;
+    }
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Start below the cursor so profiles created between requests can't shift rows across pages
+    let filtered = || {
+        let query_filter = filter_profile_completeness(profiles::table.into_boxed(), query.has_username, query.has_photo);
+        match query.before_id {
+            Some(before_id) => query_filter.filter(profiles::id.lt(before_id)),
+            None => query_filter,
+        }
+    };
     
-    // If page is provided, calculate the offset
-    let offset = if page > 1 {
-        (page - 1) * limit
-    } else {
-        offset
+    // Get total count for pagination info
+    let total_count = match filtered().count().get_result::<i64>(&mut conn).await {
+        Ok(count) => count,
+        Err(e) => {
+            error!("Failed to count profiles: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to count profiles: {}", e)
+                }))
+            )
+        }
     };
     
+    // One extra row tells whether there is another page
+    let profiles_result = filtered()
+        .order_by(profiles::id.desc())
+        .limit(params.limit + 1)
+        .offset(params.offset)
+        .load::<Profile>(&mut conn)
+        .await;
+    
+    match profiles_result {
+        Ok(profiles) => {
+            let (mut profiles, next_cursor) = cursor_page(profiles, params.limit);
+            if api_config.resolve_media(query.resolve_media) {
+                profiles.iter_mut().for_each(|profile| resolve_profile_media(profile, &api_config));
+            }
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "profiles": profiles,
+                    "next_cursor": next_cursor,
+                    "pagination": params.pagination(total_count)
+                }))
+            )
+        },
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": format!("Failed to fetch profiles: {}", e)
+            }))
+        )
+    }
+}
+
+/// Cut a page fetched with `limit + 1` rows back to `limit`
+///
+/// The cursor is the smallest id on the page, and is only set when the extra row showed
+/// there is more to fetch.
+fn cursor_page(mut profiles: Vec<Profile>, limit: i64) -> (Vec<Profile>, Option<i32>) {
+    if profiles.len() as i64 <= limit {
+        return (profiles, None);
+    }
+    profiles.truncate(limit as usize);
+    let next_cursor = profiles.last().map(|profile| profile.id);
+    (profiles, next_cursor)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActiveProfilesQuery {
+    /// How far back to look, e.g. `24h` or `7d` (default 24h)
+    pub window: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub page: Option<i64>,
+    /// Rewrite ipfs:// and ar:// media URLs to gateway URLs
+    pub resolve_media: Option<bool>,
+}
+
+/// Get profiles active within a window, most recently active first
+pub async fn get_active_profiles(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    Query(query): Query<ActiveProfilesQuery>,
+) -> impl IntoResponse {
+    let window = query.window.unwrap_or_else(|| "24h".to_string());
+    let duration = match parse_window(&window) {
+        Some(duration) => duration,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Invalid window '{}': expected a number followed by h, d or w (e.g. 24h)", window)
+                }))
+            )
+        }
+    };
+    let since = Utc::now().naive_utc() - duration;
+    
+    let limit = api_config.page_size(query.limit, api_config.recent_profiles_default);
+    let page = query.page.unwrap_or(1);
+    let offset = page_offset(page, limit, query.offset.unwrap_or(0));
+    
     let mut conn = match db_pool.get().await {
         Ok(conn) => conn,
         Err(e) => {
@@ -50,52 +205,72 @@ pub async fn latest_profiles(
         }
     };
     
-    // Get total count for pagination info
     let total_count = match profiles::table
+        .filter(profiles::last_activity_at.ge(since))
         .count()
         .get_result::<i64>(&mut conn)
         .await {
         Ok(count) => count,
-        Err(_) => 0,
+        Err(e) => {
+            error!("Failed to count active profiles: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch active profiles: {}", e)
+                }))
+            )
+        }
     };
     
-    let total_pages = (total_count as f64 / limit as f64).ceil() as i64;
-    
-    // Get profiles in descending order by id
+    // id breaks ties so pages stay stable when several profiles share a timestamp
     let profiles_result = profiles::table
-        .order_by(profiles::id.desc())
+        .filter(profiles::last_activity_at.ge(since))
+        .order_by((profiles::last_activity_at.desc(), profiles::id.desc()))
         .limit(limit)
         .offset(offset)
         .load::<Profile>(&mut conn)
         .await;
     
     match profiles_result {
-        Ok(profiles) => (
-            StatusCode::OK, 
-            Json(serde_json::json!({
-                "profiles": profiles,
-                "pagination": {
-                    "total": total_count,
-                    "limit": limit,
-                    "offset": offset,
-                    "page": page,
-                    "total_pages": total_pages
-                }
-            }))
-        ),
+        Ok(mut profiles) => {
+            if api_config.resolve_media(query.resolve_media) {
+                profiles.iter_mut().for_each(|profile| resolve_profile_media(profile, &api_config));
+            }
+            let total_pages = (total_count as f64 / limit as f64).ceil() as i64;
+            (
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "profiles": profiles,
+                    "window": window,
+                    "since": since,
+                    "pagination": {
+                        "total": total_count,
+                        "limit": limit,
+                        "offset": offset,
+                        "page": page,
+                        "total_pages": total_pages
+                    }
+                }))
+            )
+        },
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({
-                "error": format!("Failed to fetch profiles: {}", e)
+                "error": format!("Failed to fetch active profiles: {}", e)
             }))
         )
     }
 }
 
-/// Get a profile by address
-pub async fn get_profile_by_address(
+/// Get a profile by its on-chain profile id
+///
+/// An owner address is still accepted when no profile has that id, since this route looked
+/// profiles up by owner address before it shared `:profile_id` with the nested routes.
+pub async fn get_profile_by_id(
     State(db_pool): State<DbPool>,
-    Path(address): Path<String>,
+    Extension(api_config): Extension<ApiConfig>,
+    Path(profile_id): Path<String>,
+    Query(media): Query<MediaQuery>,
 ) -> impl IntoResponse {
     let mut conn = match db_pool.get().await {
         Ok(conn) => conn,
@@ -109,13 +284,25 @@ pub async fn get_profile_by_address(
         }
     };
     
-    let profile_result = profiles::table
-        .filter(profiles::owner_address.eq(address))
+    let profile_result = match profiles::table
+        .filter(profiles::profile_id.eq(&profile_id))
         .first::<Profile>(&mut conn)
-        .await;
+        .await
+    {
+        Err(diesel::result::Error::NotFound) => profiles::table
+            .filter(profiles::owner_address.eq(&profile_id))
+            .first::<Profile>(&mut conn)
+            .await,
+        result => result,
+    };
     
     match profile_result {
-        Ok(profile) => (StatusCode::OK, Json(serde_json::to_value(profile).unwrap_or_default())),
+        Ok(mut profile) => {
+            if api_config.resolve_media(media.resolve_media) {
+                resolve_profile_media(&mut profile, &api_config);
+            }
+            (StatusCode::OK, Json(serde_json::to_value(profile).unwrap_or_default()))
+        },
         Err(diesel::result::Error::NotFound) => (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({
@@ -134,7 +321,9 @@ pub async fn get_profile_by_address(
 /// Get a profile by username
 pub async fn get_profile_by_username(
     State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
     Path(username): Path<String>,
+    Query(media): Query<MediaQuery>,
 ) -> impl IntoResponse {
     let mut conn = match db_pool.get().await {
         Ok(conn) => conn,
@@ -154,7 +343,101 @@ pub async fn get_profile_by_username(
         .await;
     
     match profile_result {
-        Ok(profile) => (StatusCode::OK, Json(serde_json::to_value(profile).unwrap_or_default())),
+        Ok(mut profile) => {
+            if api_config.resolve_media(media.resolve_media) {
+                resolve_profile_media(&mut profile, &api_config);
+            }
+            (StatusCode::OK, Json(serde_json::to_value(profile).unwrap_or_default()))
+        },
+        Err(diesel::result::Error::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Profile not found"
+            }))
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": format!("Failed to fetch profile: {}", e)
+            }))
+        )
+    }
+}
+
+/// Social networks a profile can link a handle for, as accepted by `/profiles/by-social`
+pub const SOCIAL_PLATFORMS: &[&str] = &["x", "mastodon", "facebook", "reddit", "github"];
+
+#[derive(Debug, Deserialize)]
+pub struct SocialQuery {
+    /// One of `SOCIAL_PLATFORMS`
+    pub platform: Option<String>,
+    pub username: Option<String>,
+    /// Rewrite ipfs:// and ar:// media URLs to gateway URLs
+    pub resolve_media: Option<bool>,
+}
+
+/// Get a profile by the handle it linked for a social network (e.g. `?platform=x&username=foo`)
+pub async fn get_profile_by_social(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    Query(query): Query<SocialQuery>,
+) -> impl IntoResponse {
+    let platform = query.platform.as_deref().unwrap_or_default().trim().to_lowercase();
+    // Handles are often written with a leading @
+    let username = query.username.as_deref().unwrap_or_default().trim().trim_start_matches('@').to_string();
+    
+    if username.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "username is required"
+            }))
+        );
+    }
+    
+    let lookup = profiles::table.into_boxed();
+    let lookup = match platform.as_str() {
+        "x" | "twitter" => lookup.filter(profiles::x_username.eq(&username)),
+        "mastodon" => lookup.filter(profiles::mastodon_username.eq(&username)),
+        "facebook" => lookup.filter(profiles::facebook_username.eq(&username)),
+        "reddit" => lookup.filter(profiles::reddit_username.eq(&username)),
+        "github" => lookup.filter(profiles::github_username.eq(&username)),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Unknown social platform: {:?}", query.platform.unwrap_or_default()),
+                    "allowed_platforms": SOCIAL_PLATFORMS
+                }))
+            );
+        }
+    };
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // The handle isn't unique on chain; prefer the most recently updated profile claiming it
+    let profile_result = lookup
+        .order_by((profiles::updated_at.desc(), profiles::id.desc()))
+        .first::<Profile>(&mut conn)
+        .await;
+    
+    match profile_result {
+        Ok(mut profile) => {
+            if api_config.resolve_media(query.resolve_media) {
+                resolve_profile_media(&mut profile, &api_config);
+            }
+            (StatusCode::OK, Json(serde_json::to_value(profile).unwrap_or_default()))
+        },
         Err(diesel::result::Error::NotFound) => (
             StatusCode::NOT_FOUND,
             Json(serde_json::json!({
@@ -168,4 +451,907 @@ pub async fn get_profile_by_username(
             }))
         )
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LookupQuery {
+    /// An owner address, profile object id or username
+    pub q: Option<String>,
+    /// Rewrite ipfs:// and ar:// media URLs to gateway URLs
+    pub resolve_media: Option<bool>,
+}
+
+/// Get a profile from an identifier of unknown kind, trying owner address, then profile_id,
+/// then username
+///
+/// The response is the profile plus `matched_by` (`address`, `profile_id` or `username`).
+pub async fn lookup_profile(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    Query(query): Query<LookupQuery>,
+) -> impl IntoResponse {
+    let q = query.q.as_deref().unwrap_or_default().trim().to_string();
+    if q.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "q is required"
+            }))
+        );
+    }
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let lookups: [(&str, profiles::BoxedQuery<diesel::pg::Pg>); 3] = [
+        ("address", profiles::table.filter(profiles::owner_address.eq(q.clone())).into_boxed()),
+        ("profile_id", profiles::table.filter(profiles::profile_id.eq(q.clone())).into_boxed()),
+        ("username", profiles::table.filter(profiles::username.eq(q.clone())).into_boxed()),
+    ];
+    
+    for (matched_by, lookup) in lookups {
+        match lookup.first::<Profile>(&mut conn).await {
+            Ok(mut profile) => {
+                if api_config.resolve_media(query.resolve_media) {
+                    resolve_profile_media(&mut profile, &api_config);
+                }
+                let mut body = serde_json::to_value(profile).unwrap_or_default();
+                if let Some(fields) = body.as_object_mut() {
+                    fields.insert("matched_by".to_string(), serde_json::json!(matched_by));
+                }
+                return (StatusCode::OK, Json(body));
+            },
+            Err(diesel::result::Error::NotFound) => continue,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({
+                        "error": format!("Failed to fetch profile: {}", e)
+                    }))
+                )
+            }
+        }
+    }
+    
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "error": "Profile not found"
+        }))
+    )
+}
+
+/// Longest range, in days, `/profiles/count-by-day` will bucket
+pub const MAX_COUNT_BY_DAY_RANGE: i64 = 366;
+
+#[derive(Debug, Deserialize)]
+pub struct CountByDayQuery {
+    /// First day, inclusive (`YYYY-MM-DD`); defaults to 29 days before `to`
+    pub from: Option<NaiveDate>,
+    /// Last day, inclusive (`YYYY-MM-DD`); defaults to today (UTC)
+    pub to: Option<NaiveDate>,
+}
+
+/// Profiles created on one day
+#[derive(Debug, QueryableByName, Serialize)]
+struct DailyProfileCount {
+    #[diesel(sql_type = diesel::sql_types::Date)]
+    date: NaiveDate,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    count: i64,
+}
+
+/// Count profile creations per day from `profiles.created_at`
+///
+/// Unlike `daily_statistics` this is computed on request, so it also covers backfilled ranges.
+/// Days without new profiles are returned with a count of 0.
+pub async fn get_profile_counts_by_day(
+    State(db_pool): State<DbPool>,
+    Query(query): Query<CountByDayQuery>,
+) -> impl IntoResponse {
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - chrono::Duration::days(29));
+    
+    if from > to {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "from must not be after to"
+            }))
+        );
+    }
+    if (to - from).num_days() >= MAX_COUNT_BY_DAY_RANGE {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Range can span at most {} days", MAX_COUNT_BY_DAY_RANGE)
+            }))
+        );
+    }
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Filter on the raw column so the created_at index can be used, then bucket with date_trunc
+    let counts_result = diesel::sql_query(
+        "SELECT day::date AS date, COALESCE(c.count, 0) AS count
+         FROM generate_series($1::timestamp, $2::timestamp, INTERVAL '1 day') AS day
+         LEFT JOIN (
+             SELECT date_trunc('day', created_at) AS bucket, COUNT(*) AS count
+             FROM profiles
+             WHERE created_at >= $1::date AND created_at < $2::date + 1
+             GROUP BY bucket
+         ) c ON c.bucket = day
+         ORDER BY day"
+    )
+    .bind::<diesel::sql_types::Date, _>(from)
+    .bind::<diesel::sql_types::Date, _>(to)
+    .load::<DailyProfileCount>(&mut conn)
+    .await;
+    
+    match counts_result {
+        Ok(counts) => {
+            let total: i64 = counts.iter().map(|day| day.count).sum();
+            (StatusCode::OK, Json(serde_json::json!({
+                "from": from,
+                "to": to,
+                "total": total,
+                "counts": counts
+            })))
+        },
+        Err(e) => {
+            error!("Failed to count profiles by day: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to count profiles: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Get a profile's sensitive fields (owner signature or admin token required)
+///
+/// Every successful read is logged with who made it, so access to this data can be audited.
+pub async fn get_profile_sensitive(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    Path(profile_id): Path<String>,
+) -> impl IntoResponse {
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let profile = match profiles::table
+        .filter(profiles::profile_id.eq(&profile_id))
+        .first::<Profile>(&mut conn)
+        .await
+        .optional() {
+        Ok(profile) => profile,
+        Err(e) => {
+            error!("Failed to fetch profile for sensitive read: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch profile: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Authorize before revealing whether the profile exists
+    let owner_address = profile.as_ref().map(|profile| profile.owner_address.as_str());
+    let accessor = match require_owner_or_admin(&headers, &api_config, &profile_id, owner_address) {
+        Ok(accessor) => accessor,
+        Err(rejection) => {
+            warn!("Rejected sensitive field read for profile_id: {}", profile_id);
+            return rejection;
+        }
+    };
+    
+    let Some(profile) = profile else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Profile not found"
+            }))
+        )
+    };
+    
+    info!("Sensitive fields for profile_id: {} read by {:?}", profile_id, accessor);
+    
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "profile_id": profile_id,
+            "birthdate": profile.birthdate,
+            "current_location": profile.current_location,
+            "raised_location": profile.raised_location,
+            "phone": profile.phone,
+            "email": profile.email,
+            "gender": profile.gender,
+            "political_view": profile.political_view,
+            "religion": profile.religion,
+            "education": profile.education,
+            "primary_language": profile.primary_language,
+            "relationship_status": profile.relationship_status,
+            "sensitive_data_updated_at": profile.sensitive_data_updated_at,
+        }))
+    )
+}
+
+/// Export everything held about a profile as a single JSON document (admin only)
+pub async fn export_profile(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    Path(profile_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&headers, &api_config) {
+        return rejection;
+    }
+    
+    info!("Exporting data for profile_id: {}", profile_id);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let profile = match profiles::table
+        .filter(profiles::profile_id.eq(&profile_id))
+        .first::<Profile>(&mut conn)
+        .await {
+        Ok(profile) => profile,
+        Err(diesel::result::Error::NotFound) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Profile not found"
+                }))
+            )
+        },
+        Err(e) => {
+            error!("Failed to fetch profile for export: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch profile: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Block records are keyed by the address emitted on-chain, which may be either identifier
+    let identifiers = vec![profile_id.clone(), profile.owner_address.clone()];
+    
+    let export = async {
+        let following = social_graph_relationships::table
+            .filter(social_graph_relationships::follower_address.eq(&profile_id))
+            .order_by(social_graph_relationships::created_at.desc())
+            .load::<SocialGraphRelationship>(&mut conn)
+            .await?;
+        
+        let followers = social_graph_relationships::table
+            .filter(social_graph_relationships::following_address.eq(&profile_id))
+            .order_by(social_graph_relationships::created_at.desc())
+            .load::<SocialGraphRelationship>(&mut conn)
+            .await?;
+        
+        let memberships = platform_memberships::table
+            .filter(platform_memberships::profile_id.eq(&profile_id))
+            .select((platform_memberships::platform_id, platform_memberships::joined_at))
+            .order_by(platform_memberships::joined_at.desc())
+            .load::<(String, NaiveDateTime)>(&mut conn)
+            .await?;
+        
+        let blocking = profiles_blocked::table
+            .filter(profiles_blocked::blocker_wallet_address.eq_any(&identifiers))
+            .load::<ProfileBlock>(&mut conn)
+            .await?;
+        
+        let blocked_by = profiles_blocked::table
+            .filter(profiles_blocked::blocked_address.eq_any(&identifiers))
+            .load::<ProfileBlock>(&mut conn)
+            .await?;
+        
+        let platform_blocks = platform_blocked_profiles::table
+            .filter(platform_blocked_profiles::profile_id.eq(&profile_id))
+            .load::<PlatformBlockedProfile>(&mut conn)
+            .await?;
+        
+        let events = profile_events::table
+            .filter(profile_events::profile_id.eq(&profile_id))
+            .order_by(profile_events::created_at.asc())
+            .load::<ProfileEvent>(&mut conn)
+            .await?;
+        
+        Ok::<_, diesel::result::Error>(serde_json::json!({
+            "profile": &profile,
+            // Usernames live on the profile row; there is no separate history table
+            "usernames": [&profile.username],
+            "follows": {
+                "following": following,
+                "followers": followers
+            },
+            "platform_memberships": memberships
+                .into_iter()
+                .map(|(platform_id, joined_at)| serde_json::json!({
+                    "platform_id": platform_id,
+                    "joined_at": joined_at
+                }))
+                .collect::<Vec<_>>(),
+            "blocks": {
+                "blocking": blocking,
+                "blocked_by": blocked_by,
+                "platform_blocks": platform_blocks
+            },
+            "profile_events": events,
+            "exported_at": chrono::Utc::now().naive_utc()
+        }))
+    };
+    
+    match export.await {
+        Ok(document) => (StatusCode::OK, Json(document)),
+        Err(e) => {
+            error!("Failed to export profile {}: {}", profile_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to export profile: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Get how a profile relates to platforms: platforms it belongs to, platforms it has left,
+/// and platforms that blocked it
+///
+/// A platform can appear in both `joined` and `blocked_by`; blocking doesn't end a membership.
+pub async fn get_platform_relationships(
+    State(db_pool): State<DbPool>,
+    Path(profile_id): Path<String>,
+) -> impl IntoResponse {
+    info!("Getting platform relationships for profile_id: {}", profile_id);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    match profiles::table
+        .filter(profiles::profile_id.eq(&profile_id))
+        .select(profiles::id)
+        .first::<i32>(&mut conn)
+        .await
+    {
+        Ok(_) => {},
+        Err(diesel::result::Error::NotFound) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Profile not found"
+                }))
+            )
+        },
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch profile: {}", e)
+                }))
+            )
+        }
+    }
+    
+    let memberships = match platform_memberships::table
+        .filter(platform_memberships::profile_id.eq(&profile_id))
+        .order_by(platform_memberships::joined_at.desc())
+        .select((platform_memberships::platform_id, platform_memberships::joined_at))
+        .load::<(String, NaiveDateTime)>(&mut conn)
+        .await {
+        Ok(memberships) => memberships,
+        Err(e) => {
+            error!("Failed to fetch platform memberships: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch platform memberships: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Memberships are deleted on leave, so departures come from the profile's history
+    let leave_events = match profile_events::table
+        .filter(profile_events::profile_id.eq(&profile_id))
+        .filter(profile_events::event_type.eq("PlatformLeftEvent"))
+        .order_by(profile_events::created_at.desc())
+        .load::<ProfileEvent>(&mut conn)
+        .await {
+        Ok(events) => events,
+        Err(e) => {
+            error!("Failed to fetch platform leave history: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch platform leave history: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let blocks = match platform_blocked_profiles::table
+        .filter(platform_blocked_profiles::profile_id.eq(&profile_id))
+        .order_by(platform_blocked_profiles::created_at.desc())
+        .select((
+            platform_blocked_profiles::platform_id,
+            platform_blocked_profiles::blocked_by,
+            platform_blocked_profiles::created_at,
+        ))
+        .load::<(String, String, NaiveDateTime)>(&mut conn)
+        .await {
+        Ok(blocks) => blocks,
+        Err(e) => {
+            error!("Failed to fetch platform blocks: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch platform blocks: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let joined: Vec<serde_json::Value> = memberships
+        .iter()
+        .map(|(platform_id, joined_at)| serde_json::json!({
+            "platform_id": platform_id,
+            "joined_at": joined_at
+        }))
+        .collect();
+    
+    // Latest departure per platform, leaving out platforms the profile has since rejoined
+    let mut left: Vec<serde_json::Value> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for event in &leave_events {
+        let Some(platform_id) = event.event_data.get("platform_id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if memberships.iter().any(|(joined_id, _)| joined_id == platform_id) || !seen.insert(platform_id) {
+            continue;
+        }
+        left.push(serde_json::json!({
+            "platform_id": platform_id,
+            "left_at": event.created_at
+        }));
+    }
+    
+    let blocked_by: Vec<serde_json::Value> = blocks
+        .iter()
+        .map(|(platform_id, blocked_by, blocked_at)| serde_json::json!({
+            "platform_id": platform_id,
+            "blocked_by": blocked_by,
+            "blocked_at": blocked_at
+        }))
+        .collect();
+    
+    (StatusCode::OK, Json(serde_json::json!({
+        "profile_id": profile_id,
+        "joined": joined,
+        "left": left,
+        "blocked_by": blocked_by,
+        "counts": {
+            "joined": joined.len(),
+            "left": left.len(),
+            "blocked_by": blocked_by.len()
+        }
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{insert_profile, test_database, unique_id};
+    use axum::http::Uri;
+
+    fn api_config() -> ApiConfig {
+        ApiConfig {
+            admin_token: None,
+            max_page_size: 100,
+            recent_profiles_default: 10,
+            ipfs_gateway: String::new(),
+            arweave_gateway: String::new(),
+            resolve_media_default: false,
+            max_request_body_bytes: 1024,
+            pretty_json_default: false,
+            hide_unapproved_platforms: false,
+        }
+    }
+
+    fn profile(id: i32) -> Profile {
+        let now = Utc::now().naive_utc();
+        Profile {
+            id,
+            owner_address: format!("0x{}", id),
+            username: format!("user{}", id),
+            display_name: None,
+            bio: None,
+            profile_photo: None,
+            website: None,
+            created_at: now,
+            updated_at: now,
+            last_activity_at: None,
+        }
+    }
+
+    #[test]
+    fn cursor_is_only_set_when_another_page_exists() {
+        let (page, next_cursor) = cursor_page(vec![profile(9), profile(8), profile(7)], 3);
+        assert_eq!(page.len(), 3);
+        assert_eq!(next_cursor, None);
+        
+        let (page, next_cursor) = cursor_page(vec![profile(9), profile(8), profile(7), profile(6)], 3);
+        assert_eq!(page.iter().map(|p| p.id).collect::<Vec<_>>(), vec![9, 8, 7]);
+        assert_eq!(next_cursor, Some(7));
+        
+        assert!(cursor_page(Vec::new(), 3).1.is_none());
+    }
+
+    async fn recent_profiles(db_pool: DbPool, limit: Option<&str>, uri: &str) -> serde_json::Value {
+        let params = ListParams::parse(limit, None, None, None, None, api_config().max_page_size).unwrap();
+        let query = Query::<ProfileQuery>::try_from_uri(&uri.parse::<Uri>().unwrap()).unwrap();
+        let resp = latest_profiles(State(db_pool), Extension(api_config()), params, query)
+            .await
+            .into_response();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn cursor_page_counts_only_profiles_below_the_cursor() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let mut conn = pool.get().await.unwrap();
+        
+        insert_profile(&mut conn, &unique_id("a1"), &unique_id("recent")).await;
+        let middle = insert_profile(&mut conn, &unique_id("a2"), &unique_id("recent")).await;
+        let newest = insert_profile(&mut conn, &unique_id("a3"), &unique_id("recent")).await;
+        let below_newest: i64 = profiles::table
+            .filter(profiles::id.le(newest))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        
+        let page = recent_profiles(pool.clone(), Some("2"), &format!("/recent-profiles?before_id={}", newest + 1)).await;
+        let ids: Vec<i64> = page["profiles"].as_array().unwrap().iter().map(|p| p["id"].as_i64().unwrap()).collect();
+        assert_eq!(ids, vec![newest as i64, middle as i64]);
+        assert_eq!(page["next_cursor"], middle);
+        // The total only covers profiles below the cursor
+        assert_eq!(page["pagination"]["total"], below_newest);
+        assert_eq!(page["pagination"]["offset"], 0);
+    }
+
+    #[tokio::test]
+    async fn recent_profiles_default_and_cap_come_from_the_config() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        
+        let page = recent_profiles(pool.clone(), None, "/recent-profiles").await;
+        assert_eq!(page["pagination"]["limit"], api_config().recent_profiles_default);
+        
+        let page = recent_profiles(pool, Some("1000"), "/recent-profiles").await;
+        assert_eq!(page["pagination"]["limit"], api_config().max_page_size);
+    }
+
+    #[tokio::test]
+    async fn recent_profiles_filter_on_username_and_photo() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (complete, placeholder, no_photo) = {
+            let mut conn = pool.get().await.unwrap();
+            let complete = insert_profile(&mut conn, &unique_id("complete"), &unique_id("user")).await;
+            // A generated `user_` + owner prefix username doesn't count as having one
+            let digits = unique_id("");
+            let owner = format!("0x{}{}", &digits[digits.len() - 6..], unique_id("owner"));
+            let placeholder = insert_profile(&mut conn, &unique_id("placeholder"), &unique_id("user")).await;
+            diesel::update(profiles::table.find(placeholder))
+                .set((profiles::owner_address.eq(&owner), profiles::username.eq(format!("user_{}", &owner[..8]))))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            let no_photo = insert_profile(&mut conn, &unique_id("nophoto"), &unique_id("user")).await;
+            for (id, photo) in [(complete, "ipfs://photo"), (placeholder, "ipfs://photo"), (no_photo, "")] {
+                diesel::update(profiles::table.find(id))
+                    .set(profiles::profile_photo.eq(photo))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+            (complete, placeholder, no_photo)
+        };
+        let ids_for = |filters: &str| {
+            let uri = format!("/recent-profiles?before_id={}&{}", no_photo + 1, filters);
+            let pool = pool.clone();
+            async move {
+                let page = recent_profiles(pool, Some("100"), &uri).await;
+                page["profiles"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|profile| profile["id"].as_i64().unwrap() as i32)
+                    .filter(|id| [complete, placeholder, no_photo].contains(id))
+                    .collect::<Vec<_>>()
+            }
+        };
+        
+        assert_eq!(ids_for("has_username=true").await, [no_photo, complete]);
+        assert_eq!(ids_for("has_username=false").await, [placeholder]);
+        assert_eq!(ids_for("has_photo=true").await, [placeholder, complete]);
+        assert_eq!(ids_for("has_photo=false").await, [no_photo]);
+        assert_eq!(ids_for("has_username=true&has_photo=true").await, [complete]);
+    }
+
+    #[tokio::test]
+    async fn profiles_are_counted_per_day_with_empty_days_filled() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        // Days no other test creates profiles on
+        let day = |day: u32| NaiveDate::from_ymd_opt(1999, 7, day).unwrap();
+        let counts = || {
+            let query = CountByDayQuery { from: Some(day(1)), to: Some(day(3)) };
+            let response = get_profile_counts_by_day(State(pool.clone()), Query(query));
+            async move {
+                let response = response.await.into_response();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body: serde_json::Value =
+                    serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+                body["counts"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|day| (day["date"].as_str().unwrap().to_string(), day["count"].as_i64().unwrap()))
+                    .collect::<Vec<_>>()
+            }
+        };
+        
+        // Earlier runs leave their profiles behind, so compare against the counts before inserting
+        let before = counts().await;
+        {
+            let mut conn = pool.get().await.unwrap();
+            for created_at in [day(1).and_hms_opt(0, 0, 0), day(1).and_hms_opt(23, 59, 59), day(2).and_hms_opt(12, 0, 0)] {
+                let id = insert_profile(&mut conn, &unique_id("profile"), &unique_id("user")).await;
+                diesel::update(profiles::table.find(id))
+                    .set(profiles::created_at.eq(created_at.unwrap()))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        let after = counts().await;
+        
+        let dates: Vec<&str> = after.iter().map(|(date, _)| date.as_str()).collect();
+        assert_eq!(dates, ["1999-07-01", "1999-07-02", "1999-07-03"]);
+        let added: Vec<i64> = after.iter().zip(&before).map(|(after, before)| after.1 - before.1).collect();
+        assert_eq!(added, [2, 1, 0]);
+    }
+
+    #[tokio::test]
+    async fn count_by_day_rejects_backwards_and_oversized_ranges() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let day = |year: i32| NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        
+        for (from, to) in [(day(2024), day(2023)), (day(2022), day(2024))] {
+            let query = CountByDayQuery { from: Some(from), to: Some(to) };
+            let response = get_profile_counts_by_day(State(pool.clone()), Query(query)).await.into_response();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn lookup_resolves_each_kind_of_identifier() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (profile_id, username) = (unique_id("profile"), unique_id("user"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &profile_id, &username).await;
+        }
+        let lookup = |q: &str| {
+            let query = LookupQuery { q: Some(q.to_string()), resolve_media: None };
+            lookup_profile(State(pool.clone()), Extension(api_config()), Query(query))
+        };
+        
+        for (q, matched_by) in [(format!("{}owner", profile_id), "address"), (profile_id.clone(), "profile_id"), (username, "username")] {
+            let response = lookup(&q).await.into_response();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body: serde_json::Value =
+                serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+            assert_eq!(body["profile_id"], profile_id);
+            assert_eq!(body["matched_by"], matched_by);
+        }
+        
+        assert_eq!(lookup(&unique_id("missing")).await.into_response().status(), StatusCode::NOT_FOUND);
+        assert_eq!(lookup("  ").await.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn profile_is_found_by_its_linked_x_handle() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (profile_id, handle) = (unique_id("profile"), unique_id("handle"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &profile_id, &unique_id("user")).await;
+            diesel::update(profiles::table.filter(profiles::profile_id.eq(&profile_id)))
+                .set(profiles::x_username.eq(&handle))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        let lookup = |platform: &str, username: String| {
+            let query = SocialQuery { platform: Some(platform.to_string()), username: Some(username), resolve_media: None };
+            get_profile_by_social(State(pool.clone()), Extension(api_config()), Query(query))
+        };
+        
+        let response = lookup("X", format!("@{}", handle)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["profile_id"], profile_id);
+        
+        assert_eq!(lookup("github", handle.clone()).await.into_response().status(), StatusCode::NOT_FOUND);
+        assert_eq!(lookup("myspace", handle).await.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn platform_relationships_separate_joined_left_and_blocked_by() {
+        use crate::db::test_support::insert_platform;
+        use crate::models::profile_events::NewProfileEvent;
+        
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let profile_id = unique_id("profile");
+        let [joined, left, rejoined, blocker] = ["joined", "left", "rejoined", "blocker"].map(unique_id);
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &profile_id, &unique_id("user")).await;
+            for platform_id in [&joined, &rejoined] {
+                diesel::insert_into(platform_memberships::table)
+                    .values((
+                        platform_memberships::platform_id.eq(platform_id),
+                        platform_memberships::profile_id.eq(&profile_id),
+                        platform_memberships::joined_at.eq(Utc::now().naive_utc()),
+                    ))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+            for platform_id in [&left, &rejoined] {
+                let event = NewProfileEvent::from_blockchain_event(
+                    "PlatformLeftEvent",
+                    profile_id.clone(),
+                    serde_json::json!({ "platform_id": platform_id }),
+                    Some(unique_id("tx")),
+                    Some(1_700_000_000),
+                );
+                crate::db::insert_profile_event(&mut conn, &event).await.unwrap();
+            }
+            let platform_ref = insert_platform(&mut conn, &blocker).await;
+            diesel::insert_into(platform_blocked_profiles::table)
+                .values((
+                    platform_blocked_profiles::platform_id.eq(&blocker),
+                    platform_blocked_profiles::profile_id.eq(&profile_id),
+                    platform_blocked_profiles::blocked_by.eq("0xmoderator"),
+                    platform_blocked_profiles::created_at.eq(Utc::now().naive_utc()),
+                    platform_blocked_profiles::platform_ref.eq(platform_ref),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        
+        let response = get_platform_relationships(State(pool), Path(profile_id)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        let ids = |key: &str| {
+            let mut ids: Vec<String> = body[key].as_array().unwrap().iter().map(|p| p["platform_id"].as_str().unwrap().to_string()).collect();
+            ids.sort();
+            ids
+        };
+        let mut both_joined = vec![joined.clone(), rejoined.clone()];
+        both_joined.sort();
+        
+        assert_eq!(ids("joined"), both_joined);
+        // A platform left and since rejoined only counts as joined
+        assert_eq!(ids("left"), [left]);
+        assert_eq!(ids("blocked_by"), [blocker]);
+        assert_eq!(body["counts"], serde_json::json!({ "joined": 2, "left": 1, "blocked_by": 1 }));
+    }
+
+    #[tokio::test]
+    async fn active_profiles_are_listed_most_recent_first_within_the_window() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (latest, earlier, stale) = (unique_id("profile"), unique_id("profile"), unique_id("profile"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            // Future activity keeps the seeded profiles ahead of anything other tests write
+            let now = Utc::now().naive_utc();
+            for (profile_id, active_at) in [
+                (&latest, now + chrono::Duration::hours(2)),
+                (&earlier, now + chrono::Duration::hours(1)),
+                (&stale, now - chrono::Duration::hours(48)),
+            ] {
+                let id = insert_profile(&mut conn, profile_id, &unique_id("user")).await;
+                diesel::update(profiles::table.find(id))
+                    .set(profiles::last_activity_at.eq(active_at))
+                    .execute(&mut conn)
+                    .await
+                    .unwrap();
+            }
+        }
+        
+        let query = ActiveProfilesQuery { window: Some("24h".to_string()), limit: Some(100), offset: None, page: None, resolve_media: None };
+        let response = get_active_profiles(State(pool.clone()), Extension(api_config()), Query(query)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        let ids: Vec<&str> = body["profiles"].as_array().unwrap().iter().filter_map(|p| p["profile_id"].as_str()).collect();
+        let at = |profile_id: &str| ids.iter().position(|id| *id == profile_id);
+        assert!(at(&latest).unwrap() < at(&earlier).unwrap());
+        assert_eq!(at(&stale), None);
+        
+        let query = ActiveProfilesQuery { window: Some("soon".to_string()), limit: None, offset: None, page: None, resolve_media: None };
+        let response = get_active_profiles(State(pool), Extension(api_config()), Query(query)).await.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
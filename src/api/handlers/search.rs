@@ -0,0 +1,195 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    Extension,
+    Json,
+};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::Deserialize;
+use tracing::{debug, error};
+
+use crate::api::auth::hide_unapproved_platforms;
+use crate::config::ApiConfig;
+use crate::db::DbConnection;
+use crate::db::DbPool;
+use crate::models::{Platform, Profile};
+use crate::schema::{platforms, profiles};
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: Option<String>,
+    /// Comma separated list of result types (profiles, platforms)
+    pub types: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Build an ILIKE pattern that matches the term anywhere, escaping wildcards
+fn contains_pattern(term: &str) -> String {
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Search profiles by username or display name
+pub(crate) async fn search_profiles(
+    conn: &mut DbConnection,
+    term: &str,
+    limit: i64,
+) -> Result<Vec<Profile>, diesel::result::Error> {
+    let pattern = contains_pattern(term);
+    
+    profiles::table
+        .filter(
+            profiles::username.ilike(&pattern)
+                .or(profiles::display_name.ilike(&pattern))
+        )
+        .order_by(profiles::id.desc())
+        .limit(limit)
+        .load::<Profile>(conn)
+        .await
+}
+
+/// Search platforms by name or tagline, optionally only approved ones
+pub(crate) async fn search_platforms(
+    conn: &mut DbConnection,
+    term: &str,
+    limit: i64,
+    approved_only: bool,
+) -> Result<Vec<Platform>, diesel::result::Error> {
+    let pattern = contains_pattern(term);
+    
+    let mut query = platforms::table
+        .filter(
+            platforms::name.ilike(&pattern)
+                .or(platforms::tagline.ilike(&pattern))
+        )
+        .into_boxed();
+    if approved_only {
+        query = query.filter(platforms::is_approved.eq(true));
+    }
+    query
+        .order_by(platforms::created_at.desc())
+        .limit(limit)
+        .load::<Platform>(conn)
+        .await
+}
+
+/// Search profiles and platforms with a single query
+pub async fn search(
+    State(db_pool): State<DbPool>,
+    Extension(api_config): Extension<ApiConfig>,
+    headers: HeaderMap,
+    Query(query): Query<SearchQuery>,
+) -> impl IntoResponse {
+    let term = query.q.unwrap_or_default().trim().to_string();
+    // Clamped to 1..=MAX_PAGE_SIZE so a negative or huge limit can't reach the database
+    let limit = api_config.page_size(query.limit, 20);
+    
+    if term.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "Query parameter 'q' is required"
+            }))
+        )
+    }
+    
+    // Default to searching everything
+    let types: Vec<String> = query.types
+        .map(|t| t.split(',').map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_else(|| vec!["profiles".to_string(), "platforms".to_string()]);
+    
+    debug!("Searching for '{}' in {:?} with limit: {}", term, types, limit);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let mut results = serde_json::Map::new();
+    
+    if types.iter().any(|t| t == "profiles") {
+        match search_profiles(&mut conn, &term, limit).await {
+            Ok(found) => {
+                results.insert("profiles".to_string(), serde_json::to_value(found).unwrap_or_default());
+            },
+            Err(e) => {
+                error!("Failed to search profiles: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({
+                        "error": format!("Failed to search profiles: {}", e)
+                    }))
+                )
+            }
+        }
+    }
+    
+    if types.iter().any(|t| t == "platforms") {
+        let approved_only = hide_unapproved_platforms(&headers, &api_config);
+        match search_platforms(&mut conn, &term, limit, approved_only).await {
+            Ok(found) => {
+                results.insert("platforms".to_string(), serde_json::to_value(found).unwrap_or_default());
+            },
+            Err(e) => {
+                error!("Failed to search platforms: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({
+                        "error": format!("Failed to search platforms: {}", e)
+                    }))
+                )
+            }
+        }
+    }
+    
+    (StatusCode::OK, Json(serde_json::Value::Object(results)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{insert_platform, test_database, unique_id};
+
+    #[test]
+    fn wildcards_in_the_term_are_escaped() {
+        assert_eq!(contains_pattern("50%_off\\"), "%50\\%\\_off\\\\%");
+    }
+
+    #[tokio::test]
+    async fn approved_only_leaves_out_unapproved_platforms() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.pool.get().await.unwrap();
+        let platform_id = unique_id("platform");
+        let id = insert_platform(&mut conn, &platform_id).await;
+
+        let found = search_platforms(&mut conn, &platform_id, 10, false).await.unwrap();
+        assert_eq!(found.len(), 1);
+        let found = search_platforms(&mut conn, &platform_id, 10, true).await.unwrap();
+        assert!(found.is_empty());
+
+        diesel::update(platforms::table.find(id))
+            .set(platforms::is_approved.eq(true))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let found = search_platforms(&mut conn, &platform_id, 10, true).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].platform_id, platform_id);
+    }
+}
@@ -12,25 +12,25 @@ use diesel_async::RunQueryDsl;
 use tracing::{debug, error};
 
 use crate::db::DbPool;
-use crate::models::social_graph::{FollowDetail, FollowsQuery};
-use crate::schema::{social_graph_relationships, profiles};
+use crate::models::social_graph::{parse_window, FollowDetail, FollowerSnapshot, FollowerTrendQuery, GrowthQuery};
+use crate::schema::{profile_follower_snapshots, social_graph_events, social_graph_relationships, profiles};
+use crate::api::params::ListParams;
+
+/// Whether a maintained follow counter contradicts the page just loaded
+///
+/// The counter is too low if rows exist beyond it, and too high if a short page ended before it.
+fn cached_total_is_stale(cached_total: i64, offset: i64, limit: i64, page_len: i64) -> bool {
+    let seen = offset.saturating_add(page_len);
+    cached_total < 0 || cached_total < seen || (page_len < limit && (page_len > 0 || offset == 0) && cached_total > seen)
+}
 
 /// Get a list of profiles that a user is following
 pub async fn get_following(
     State(db_pool): State<DbPool>,
     Path(profile_id): Path<String>,
-    Query(query): Query<FollowsQuery>,
+    params: ListParams,
 ) -> impl IntoResponse {
-    let limit = query.limit.unwrap_or(50);
-    let offset = query.offset.unwrap_or(0);
-    let page = query.page.unwrap_or(1);
-    
-    // If page is provided, calculate the offset
-    let offset = if page > 1 {
-        (page - 1) * limit
-    } else {
-        offset
-    };
+    let (limit, offset, page) = (params.limit, params.offset, params.page);
     
     debug!("Getting following for profile_id: {}, limit: {}, offset: {}", profile_id, limit, offset);
     
@@ -47,13 +47,23 @@ pub async fn get_following(
         }
     };
     
-    // First verify the profile exists using profile_id
-    let profile_exists = match profiles::table
+    // First verify the profile exists using profile_id, reading its maintained count on the way
+    let cached_total = match profiles::table
         .filter(profiles::profile_id.eq(&profile_id))
-        .count()
-        .get_result::<i64>(&mut conn)
-        .await {
-        Ok(count) => count > 0,
+        .select(profiles::following_count)
+        .first::<i32>(&mut conn)
+        .await
+        .optional() {
+        Ok(Some(count)) => count as i64,
+        Ok(None) => {
+            debug!("Profile not found with profile_id: {}", profile_id);
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Profile not found"
+                }))
+            )
+        },
         Err(e) => {
             error!("Failed to check profile: {}", e);
             return (
@@ -65,16 +75,6 @@ pub async fn get_following(
         }
     };
     
-    if !profile_exists {
-        debug!("Profile not found with profile_id: {}", profile_id);
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
-                "error": "Profile not found"
-            }))
-        )
-    }
-    
     // Get following relationships and join with profiles to get details
     // Now using profile_id instead of owner_address
     let following_query = social_graph_relationships::table
@@ -95,20 +95,27 @@ pub async fn get_following(
         ))
         .limit(limit)
         .offset(offset)
-        .order_by(social_graph_relationships::created_at.desc());
+        // Matches the (address, created_at, id) indexes so pages come straight off the index
+        .order_by((social_graph_relationships::created_at.desc(), social_graph_relationships::id.desc()));
         
     let following_result = following_query
         .load::<(i32, Option<String>, String, String, Option<String>, Option<String>, Option<String>, Option<String>, chrono::NaiveDateTime)>(&mut conn)
         .await;
         
-    // Also get the total count for pagination info
-    let total_count = match social_graph_relationships::table
-        .filter(social_graph_relationships::follower_address.eq(&profile_id))
-        .count()
-        .get_result::<i64>(&mut conn)
-        .await {
-        Ok(count) => count,
-        Err(_) => 0,
+    // Serve the total from the maintained counter unless it can't be right for this page
+    let page_len = following_result.as_ref().map(|rows| rows.len() as i64).unwrap_or(0);
+    let total_count = if cached_total_is_stale(cached_total, offset, limit, page_len) {
+        debug!("Cached count {} looks stale for {}, counting relationships", cached_total, profile_id);
+        match social_graph_relationships::table
+            .filter(social_graph_relationships::follower_address.eq(&profile_id))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await {
+            Ok(count) => count,
+            Err(_) => cached_total.max(0),
+        }
+    } else {
+        cached_total
     };
     
     let total_pages = (total_count as f64 / limit as f64).ceil() as i64;
@@ -160,18 +167,9 @@ pub async fn get_following(
 pub async fn get_followers(
     State(db_pool): State<DbPool>,
     Path(profile_id): Path<String>,
-    Query(query): Query<FollowsQuery>,
+    params: ListParams,
 ) -> impl IntoResponse {
-    let limit = query.limit.unwrap_or(50);
-    let offset = query.offset.unwrap_or(0);
-    let page = query.page.unwrap_or(1);
-    
-    // If page is provided, calculate the offset
-    let offset = if page > 1 {
-        (page - 1) * limit
-    } else {
-        offset
-    };
+    let (limit, offset, page) = (params.limit, params.offset, params.page);
     
     debug!("Getting followers for profile_id: {}, limit: {}, offset: {}", profile_id, limit, offset);
     
@@ -188,13 +186,23 @@ pub async fn get_followers(
         }
     };
     
-    // First verify the profile exists using profile_id
-    let profile_exists = match profiles::table
+    // First verify the profile exists using profile_id, reading its maintained count on the way
+    let cached_total = match profiles::table
         .filter(profiles::profile_id.eq(&profile_id))
-        .count()
-        .get_result::<i64>(&mut conn)
-        .await {
-        Ok(count) => count > 0,
+        .select(profiles::followers_count)
+        .first::<i32>(&mut conn)
+        .await
+        .optional() {
+        Ok(Some(count)) => count as i64,
+        Ok(None) => {
+            debug!("Profile not found with profile_id: {}", profile_id);
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Profile not found"
+                }))
+            )
+        },
         Err(e) => {
             error!("Failed to check profile: {}", e);
             return (
@@ -206,16 +214,6 @@ pub async fn get_followers(
         }
     };
     
-    if !profile_exists {
-        debug!("Profile not found with profile_id: {}", profile_id);
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({
-                "error": "Profile not found"
-            }))
-        )
-    }
-    
     // Get follower relationships and join with profiles to get details
     // Now using profile_id instead of owner_address
     let followers_query = social_graph_relationships::table
@@ -236,20 +234,27 @@ pub async fn get_followers(
         ))
         .limit(limit)
         .offset(offset)
-        .order_by(social_graph_relationships::created_at.desc());
+        // Matches the (address, created_at, id) indexes so pages come straight off the index
+        .order_by((social_graph_relationships::created_at.desc(), social_graph_relationships::id.desc()));
         
     let followers_result = followers_query
         .load::<(i32, Option<String>, String, String, Option<String>, Option<String>, Option<String>, Option<String>, chrono::NaiveDateTime)>(&mut conn)
         .await;
         
-    // Also get the total count for pagination info
-    let total_count = match social_graph_relationships::table
-        .filter(social_graph_relationships::following_address.eq(&profile_id))
-        .count()
-        .get_result::<i64>(&mut conn)
-        .await {
-        Ok(count) => count,
-        Err(_) => 0,
+    // Serve the total from the maintained counter unless it can't be right for this page
+    let page_len = followers_result.as_ref().map(|rows| rows.len() as i64).unwrap_or(0);
+    let total_count = if cached_total_is_stale(cached_total, offset, limit, page_len) {
+        debug!("Cached count {} looks stale for {}, counting relationships", cached_total, profile_id);
+        match social_graph_relationships::table
+            .filter(social_graph_relationships::following_address.eq(&profile_id))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await {
+            Ok(count) => count,
+            Err(_) => cached_total.max(0),
+        }
+    } else {
+        cached_total
     };
     
     let total_pages = (total_count as f64 / limit as f64).ceil() as i64;
@@ -468,4 +473,681 @@ pub async fn get_follow_stats(
             )
         }
     }
-}
\ No newline at end of file
+}
+
+/// Follow counts behind `/profile/:profile_id/reciprocity`
+#[derive(Debug, QueryableByName)]
+struct ReciprocityCounts {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    following_count: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    followers_count: i64,
+    /// Profiles the profile follows that follow it back
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    mutual_count: i64,
+}
+
+/// Get how many of the profiles a profile follows follow it back
+///
+/// Counted from `social_graph_relationships` rather than the cached counters, so the three
+/// numbers agree with each other. `reciprocity` is `mutual_count / following_count`, or null
+/// when the profile follows no one.
+pub async fn get_follow_reciprocity(
+    State(db_pool): State<DbPool>,
+    Path(profile_id): Path<String>,
+) -> impl IntoResponse {
+    debug!("Getting follow reciprocity for profile_id: {}", profile_id);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let exists = profiles::table
+        .filter(profiles::profile_id.eq(&profile_id))
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await;
+    match exists {
+        Ok(0) => {
+            debug!("Profile not found with profile_id: {}", profile_id);
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": "Profile not found"
+                }))
+            )
+        },
+        Ok(_) => {},
+        Err(e) => {
+            error!("Failed to check profile: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to check profile: {}", e)
+                }))
+            )
+        }
+    }
+    
+    let counts = diesel::sql_query(
+        "SELECT
+             (SELECT COUNT(*) FROM social_graph_relationships WHERE follower_address = $1) AS following_count,
+             (SELECT COUNT(*) FROM social_graph_relationships WHERE following_address = $1) AS followers_count,
+             (SELECT COUNT(*)
+              FROM social_graph_relationships f
+              JOIN social_graph_relationships b
+                ON b.follower_address = f.following_address AND b.following_address = f.follower_address
+              WHERE f.follower_address = $1) AS mutual_count"
+    )
+    .bind::<diesel::sql_types::Text, _>(&profile_id)
+    .get_result::<ReciprocityCounts>(&mut conn)
+    .await;
+    
+    match counts {
+        Ok(counts) => {
+            let reciprocity = (counts.following_count > 0)
+                .then(|| counts.mutual_count as f64 / counts.following_count as f64);
+            
+            (StatusCode::OK, Json(serde_json::json!({
+                "profile_id": profile_id,
+                "following_count": counts.following_count,
+                "followers_count": counts.followers_count,
+                "mutual_count": counts.mutual_count,
+                "reciprocity": reciprocity
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch follow reciprocity: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch follow reciprocity: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Get follows gained and lost by a profile over a time window, from the follow/unfollow history
+pub async fn get_follow_growth(
+    State(db_pool): State<DbPool>,
+    Path(profile_id): Path<String>,
+    Query(query): Query<GrowthQuery>,
+) -> impl IntoResponse {
+    let window = query.window.unwrap_or_else(|| "7d".to_string());
+    let duration = match parse_window(&window) {
+        Some(duration) => duration,
+        None => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Invalid window '{}': expected a number followed by h, d or w (e.g. 7d)", window)
+                }))
+            )
+        }
+    };
+    
+    debug!("Getting follow growth for profile_id: {}, window: {}", profile_id, window);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let profile_exists = match profiles::table
+        .filter(profiles::profile_id.eq(&profile_id))
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await {
+        Ok(count) => count > 0,
+        Err(e) => {
+            error!("Failed to check profile: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to check profile: {}", e)
+                }))
+            )
+        }
+    };
+    
+    if !profile_exists {
+        debug!("Profile not found with profile_id: {}", profile_id);
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Profile not found"
+            }))
+        )
+    }
+    
+    let until = chrono::Utc::now().naive_utc();
+    let since = until - duration;
+    
+    // Count follow/unfollow events received (followers) and made (following) within the window
+    let incoming = social_graph_events::table
+        .filter(social_graph_events::created_at.ge(since))
+        .filter(social_graph_events::following_address.eq(&profile_id))
+        .group_by(social_graph_events::event_type)
+        .select((social_graph_events::event_type, diesel::dsl::count_star()))
+        .load::<(String, i64)>(&mut conn)
+        .await;
+        
+    let outgoing = social_graph_events::table
+        .filter(social_graph_events::created_at.ge(since))
+        .filter(social_graph_events::follower_address.eq(&profile_id))
+        .group_by(social_graph_events::event_type)
+        .select((social_graph_events::event_type, diesel::dsl::count_star()))
+        .load::<(String, i64)>(&mut conn)
+        .await;
+        
+    match incoming.and_then(|incoming| outgoing.map(|outgoing| (incoming, outgoing))) {
+        Ok((incoming, outgoing)) => {
+            let count_of = |rows: &[(String, i64)], event_type: &str| {
+                rows.iter()
+                    .filter(|(kind, _)| kind == event_type)
+                    .map(|(_, count)| *count)
+                    .sum::<i64>()
+            };
+            
+            let followers_gained = count_of(&incoming, "follow");
+            let followers_lost = count_of(&incoming, "unfollow");
+            let following_added = count_of(&outgoing, "follow");
+            let following_removed = count_of(&outgoing, "unfollow");
+            
+            (StatusCode::OK, Json(serde_json::json!({
+                "profile_id": profile_id,
+                "window": window,
+                "since": since,
+                "until": until,
+                "followers": {
+                    "gained": followers_gained,
+                    "lost": followers_lost,
+                    "net": followers_gained - followers_lost
+                },
+                "following": {
+                    "added": following_added,
+                    "removed": following_removed,
+                    "net": following_added - following_removed
+                }
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch follow growth: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch follow growth: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Longest range, in days, `/profile/:profile_id/follower-trend` will return
+pub const MAX_FOLLOWER_TREND_RANGE: i64 = 366;
+
+/// Get a profile's daily follower and following counts from the snapshot table
+///
+/// Only days on which the profile was snapshotted are returned, so gaps mean no activity.
+pub async fn get_follower_trend(
+    State(db_pool): State<DbPool>,
+    Path(profile_id): Path<String>,
+    Query(query): Query<FollowerTrendQuery>,
+) -> impl IntoResponse {
+    let to = query.to.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - chrono::Duration::days(29));
+    
+    if from > to {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": "from must not be after to"
+            }))
+        );
+    }
+    if (to - from).num_days() >= MAX_FOLLOWER_TREND_RANGE {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!("Range can span at most {} days", MAX_FOLLOWER_TREND_RANGE)
+            }))
+        );
+    }
+    
+    debug!("Getting follower trend for profile_id: {}, from: {}, to: {}", profile_id, from, to);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    let profile_exists = match profiles::table
+        .filter(profiles::profile_id.eq(&profile_id))
+        .count()
+        .get_result::<i64>(&mut conn)
+        .await {
+        Ok(count) => count > 0,
+        Err(e) => {
+            error!("Failed to check profile: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to check profile: {}", e)
+                }))
+            )
+        }
+    };
+    
+    if !profile_exists {
+        debug!("Profile not found with profile_id: {}", profile_id);
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": "Profile not found"
+            }))
+        )
+    }
+    
+    let snapshots_result = profile_follower_snapshots::table
+        .filter(profile_follower_snapshots::profile_id.eq(&profile_id))
+        .filter(profile_follower_snapshots::date.between(from, to))
+        .order_by(profile_follower_snapshots::date.asc())
+        .select(FollowerSnapshot::as_select())
+        .load::<FollowerSnapshot>(&mut conn)
+        .await;
+    
+    match snapshots_result {
+        Ok(snapshots) => {
+            (StatusCode::OK, Json(serde_json::json!({
+                "profile_id": profile_id,
+                "from": from,
+                "to": to,
+                "snapshots": snapshots
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch follower trend: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch follower trend: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+/// Get the follow relationship between two profiles: whether, since when, and whether it is mutual
+pub async fn get_follow_relationship(
+    State(db_pool): State<DbPool>,
+    Path((profile_id, other_profile_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    debug!("Getting follow relationship {} -> {}", profile_id, other_profile_id);
+    
+    let mut conn = match db_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Database error: {}", e)
+                }))
+            )
+        }
+    };
+    
+    // Both profiles must exist
+    let found = match profiles::table
+        .filter(profiles::profile_id.eq_any([&profile_id, &other_profile_id]))
+        .select(profiles::profile_id)
+        .load::<Option<String>>(&mut conn)
+        .await {
+        Ok(found) => found,
+        Err(e) => {
+            error!("Failed to check profiles: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to check profiles: {}", e)
+                }))
+            )
+        }
+    };
+    
+    for id in [&profile_id, &other_profile_id] {
+        if !found.iter().any(|found_id| found_id.as_ref() == Some(id)) {
+            debug!("Profile not found with profile_id: {}", id);
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({
+                    "error": format!("Profile not found: {}", id)
+                }))
+            )
+        }
+    }
+    
+    // Load both directions of the relationship at once
+    let relationships = social_graph_relationships::table
+        .filter(
+            social_graph_relationships::follower_address.eq(&profile_id)
+                .and(social_graph_relationships::following_address.eq(&other_profile_id))
+                .or(social_graph_relationships::follower_address.eq(&other_profile_id)
+                    .and(social_graph_relationships::following_address.eq(&profile_id)))
+        )
+        .select((social_graph_relationships::follower_address, social_graph_relationships::created_at))
+        .load::<(String, chrono::NaiveDateTime)>(&mut conn)
+        .await;
+        
+    match relationships {
+        Ok(rows) => {
+            let since = rows.iter()
+                .find(|(follower, _)| *follower == profile_id)
+                .map(|(_, created_at)| *created_at);
+            let followed_back_since = rows.iter()
+                .find(|(follower, _)| *follower == other_profile_id)
+                .map(|(_, created_at)| *created_at);
+            
+            (StatusCode::OK, Json(serde_json::json!({
+                "profile_id": profile_id,
+                "other_profile_id": other_profile_id,
+                "following": since.is_some(),
+                "since": since,
+                "followed_by": followed_back_since.is_some(),
+                "mutual": since.is_some() && followed_back_since.is_some()
+            })))
+        },
+        Err(e) => {
+            error!("Failed to fetch follow relationship: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to fetch follow relationship: {}", e)
+                }))
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::Response;
+    use crate::api::params::MAX_OFFSET;
+    use crate::db::test_support::{insert_profile, test_database, unique_id};
+    use diesel_async::AsyncPgConnection;
+
+    async fn follow(conn: &mut AsyncPgConnection, follower: &str, following: &str) {
+        diesel::insert_into(social_graph_relationships::table)
+            .values((
+                social_graph_relationships::follower_address.eq(follower),
+                social_graph_relationships::following_address.eq(following),
+                social_graph_relationships::created_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(conn)
+            .await
+            .unwrap();
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn offset_past_the_end_returns_an_empty_page() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (profile, follower) = (unique_id("profile"), unique_id("profile"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &profile, &unique_id("user")).await;
+            insert_profile(&mut conn, &follower, &unique_id("user")).await;
+            follow(&mut conn, &follower, &profile).await;
+        }
+        let params = ListParams::parse(None, Some("99999999999"), None, None, None, 100).unwrap();
+        
+        let response = get_followers(State(pool), Path(profile), params).await.into_response();
+        
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["profiles"], serde_json::json!([]));
+        assert_eq!(body["pagination"]["total"], 1);
+        assert_eq!(body["pagination"]["offset"], MAX_OFFSET);
+    }
+
+    #[test]
+    fn cached_total_is_only_replaced_when_the_page_contradicts_it() {
+        // A full first page can't tell a larger counter wrong
+        assert!(!cached_total_is_stale(50, 0, 10, 10));
+        // Rows beyond the counter, or a short page ending before it
+        assert!(cached_total_is_stale(5, 0, 10, 10));
+        assert!(cached_total_is_stale(50, 0, 10, 3));
+        assert!(cached_total_is_stale(-1, 0, 10, 0));
+        // An empty page past the end says nothing about the total
+        assert!(!cached_total_is_stale(50, 100, 10, 0));
+    }
+
+    #[tokio::test]
+    async fn follower_total_comes_from_the_profile_counter() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let (profile, follower) = (unique_id("profile"), unique_id("profile"));
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &profile, &unique_id("user")).await;
+            insert_profile(&mut conn, &follower, &unique_id("user")).await;
+            follow(&mut conn, &follower, &profile).await;
+            diesel::update(profiles::table.filter(profiles::profile_id.eq(&profile)))
+                .set(profiles::followers_count.eq(50))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        let total = |limit: &str| {
+            let params = ListParams::parse(Some(limit), None, None, None, None, 100).unwrap();
+            let response = get_followers(State(pool.clone()), Path(profile.clone()), params);
+            async move { body_json(response.await.into_response()).await["pagination"]["total"].clone() }
+        };
+        
+        // A full page is consistent with the counter, so no COUNT query replaces it
+        assert_eq!(total("1").await, 50);
+        // A short page proves the counter wrong, so the live count is served
+        assert_eq!(total("10").await, 1);
+    }
+
+    #[tokio::test]
+    async fn reciprocity_is_the_share_of_follows_returned() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let [me, b, c, d, e] = ["me", "b", "c", "d", "e"].map(unique_id);
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &me, &unique_id("user")).await;
+            // me follows b, c and d; b and c follow back, e follows without being followed
+            for following in [&b, &c, &d] {
+                follow(&mut conn, &me, following).await;
+            }
+            for follower in [&b, &c, &e] {
+                follow(&mut conn, follower, &me).await;
+            }
+        }
+        
+        let response = get_follow_reciprocity(State(pool), Path(me)).await.into_response();
+        
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["following_count"], 3);
+        assert_eq!(body["followers_count"], 3);
+        assert_eq!(body["mutual_count"], 2);
+        assert!((body["reciprocity"].as_f64().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn reciprocity_is_null_when_following_no_one() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let profile = unique_id("profile");
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &profile, &unique_id("user")).await;
+        }
+        
+        let body = body_json(get_follow_reciprocity(State(pool), Path(profile)).await.into_response()).await;
+        assert_eq!(body["mutual_count"], 0);
+        assert!(body["reciprocity"].is_null());
+    }
+
+    async fn follow_event(conn: &mut AsyncPgConnection, event_type: &str, follower: &str, following: &str, days_ago: i64) {
+        diesel::insert_into(social_graph_events::table)
+            .values((
+                social_graph_events::event_type.eq(event_type),
+                social_graph_events::follower_address.eq(follower),
+                social_graph_events::following_address.eq(following),
+                social_graph_events::created_at.eq(chrono::Utc::now().naive_utc() - chrono::Duration::days(days_ago)),
+            ))
+            .execute(conn)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn growth_counts_only_history_inside_the_window() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let [me, a, b, c, d] = ["me", "a", "b", "c", "d"].map(unique_id);
+        {
+            let mut conn = pool.get().await.unwrap();
+            insert_profile(&mut conn, &me, &unique_id("user")).await;
+            follow_event(&mut conn, "follow", &a, &me, 1).await;
+            follow_event(&mut conn, "follow", &b, &me, 2).await;
+            follow_event(&mut conn, "unfollow", &a, &me, 1).await;
+            follow_event(&mut conn, "follow", &me, &c, 3).await;
+            // Older than the window
+            follow_event(&mut conn, "follow", &d, &me, 10).await;
+            follow_event(&mut conn, "unfollow", &me, &d, 10).await;
+        }
+        let growth = |window: Option<&str>| {
+            let query = GrowthQuery { window: window.map(str::to_string) };
+            get_follow_growth(State(pool.clone()), Path(me.clone()), Query(query))
+        };
+        
+        let response = growth(None).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_json(response).await;
+        assert_eq!(body["window"], "7d");
+        assert_eq!(body["followers"], serde_json::json!({ "gained": 2, "lost": 1, "net": 1 }));
+        assert_eq!(body["following"], serde_json::json!({ "added": 1, "removed": 0, "net": 1 }));
+        
+        let body = body_json(growth(Some("2w")).await.into_response()).await;
+        assert_eq!(body["followers"], serde_json::json!({ "gained": 3, "lost": 1, "net": 2 }));
+        assert_eq!(body["following"], serde_json::json!({ "added": 1, "removed": 1, "net": 0 }));
+        
+        assert_eq!(growth(Some("7x")).await.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn relationship_reports_following_not_following_and_mutual() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let [a, b, c] = ["a", "b", "c"].map(unique_id);
+        {
+            let mut conn = pool.get().await.unwrap();
+            for profile in [&a, &b, &c] {
+                insert_profile(&mut conn, profile, &unique_id("user")).await;
+            }
+            // a and b follow each other; a follows c without a follow back
+            follow(&mut conn, &a, &b).await;
+            follow(&mut conn, &b, &a).await;
+            follow(&mut conn, &a, &c).await;
+        }
+        let relationship = |from: &str, to: &str| {
+            let response = get_follow_relationship(State(pool.clone()), Path((from.to_string(), to.to_string())));
+            async move { body_json(response.await.into_response()).await }
+        };
+        
+        let body = relationship(&a, &c).await;
+        assert_eq!(body["following"], true);
+        assert!(body["since"].is_string());
+        assert_eq!(body["followed_by"], false);
+        assert_eq!(body["mutual"], false);
+        
+        let body = relationship(&c, &a).await;
+        assert_eq!(body["following"], false);
+        assert!(body["since"].is_null());
+        assert_eq!(body["followed_by"], true);
+        assert_eq!(body["mutual"], false);
+        
+        let body = relationship(&a, &b).await;
+        assert_eq!(body["following"], true);
+        assert_eq!(body["mutual"], true);
+        
+        let response = get_follow_relationship(State(pool), Path((a, unique_id("missing")))).await.into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[derive(QueryableByName)]
+    struct PlanLine {
+        #[diesel(sql_type = diesel::sql_types::Text, column_name = "QUERY PLAN")]
+        line: String,
+    }
+
+    #[tokio::test]
+    async fn follow_lists_are_served_from_the_created_at_indexes() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let mut conn = pool.get().await.unwrap();
+        
+        for (column, index) in [
+            ("following_address", "idx_social_graph_relationships_following_created_at"),
+            ("follower_address", "idx_social_graph_relationships_follower_created_at"),
+        ] {
+            // The test table is tiny, so keep the planner off a sequential scan it would otherwise prefer
+            let plan = conn.build_transaction()
+                .run(|conn| Box::pin(async move {
+                    diesel::sql_query("SET LOCAL enable_seqscan = off").execute(conn).await?;
+                    diesel::sql_query(format!(
+                        "EXPLAIN SELECT id FROM social_graph_relationships WHERE {} = $1 \
+                         ORDER BY created_at DESC, id DESC LIMIT 20",
+                        column
+                    ))
+                    .bind::<diesel::sql_types::Text, _>("0xprofile")
+                    .load::<PlanLine>(conn)
+                    .await
+                }))
+                .await
+                .unwrap();
+            let plan: Vec<String> = plan.into_iter().map(|line| line.line).collect();
+            
+            assert!(plan.iter().any(|line| line.contains(index)), "{} not used:\n{}", index, plan.join("\n"));
+            assert!(!plan.iter().any(|line| line.contains("Sort")), "ordering needed a sort:\n{}", plan.join("\n"));
+        }
+    }
+}
+
@@ -0,0 +1,86 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+
+use crate::config::ApiConfig;
+use crate::models::Profile;
+
+/// Query parameter toggling media URL rewriting (`?resolve_media=true`)
+#[derive(Debug, Default, Deserialize)]
+pub struct MediaQuery {
+    pub resolve_media: Option<bool>,
+}
+
+/// Rewrite `ipfs://CID` and `ar://TX` URLs to the configured HTTP gateways
+///
+/// Any other URL is returned unchanged.
+pub fn resolve_media_url(url: &str, config: &ApiConfig) -> String {
+    if let Some(path) = url.strip_prefix("ipfs://") {
+        // Some clients write ipfs://ipfs/<cid>
+        let path = path.strip_prefix("ipfs/").unwrap_or(path);
+        join_gateway(&config.ipfs_gateway, path)
+    } else if let Some(path) = url.strip_prefix("ar://") {
+        join_gateway(&config.arweave_gateway, path)
+    } else {
+        url.to_string()
+    }
+}
+
+/// Rewrite an optional media field
+pub fn resolve_media_field(url: Option<String>, config: &ApiConfig) -> Option<String> {
+    url.map(|url| resolve_media_url(&url, config))
+}
+
+/// Rewrite the media fields of a profile for a response
+pub fn resolve_profile_media(profile: &mut Profile, config: &ApiConfig) {
+    profile.profile_photo = resolve_media_field(profile.profile_photo.take(), config);
+    profile.cover_photo = resolve_media_field(profile.cover_photo.take(), config);
+}
+
+fn join_gateway(gateway: &str, path: &str) -> String {
+    format!("{}/{}", gateway.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(resolve_media_default: bool) -> ApiConfig {
+        ApiConfig {
+            admin_token: None,
+            max_page_size: 100,
+            recent_profiles_default: 10,
+            ipfs_gateway: "https://ipfs.io/ipfs/".to_string(),
+            arweave_gateway: "https://arweave.net".to_string(),
+            resolve_media_default,
+            max_request_body_bytes: 1024,
+            pretty_json_default: false,
+            hide_unapproved_platforms: false,
+        }
+    }
+
+    #[test]
+    fn ipfs_urls_go_through_the_ipfs_gateway() {
+        assert_eq!(resolve_media_url("ipfs://bafyCID", &config(false)), "https://ipfs.io/ipfs/bafyCID");
+        assert_eq!(resolve_media_url("ipfs://ipfs/bafyCID/logo.png", &config(false)), "https://ipfs.io/ipfs/bafyCID/logo.png");
+    }
+
+    #[test]
+    fn arweave_urls_go_through_the_arweave_gateway() {
+        assert_eq!(resolve_media_url("ar://TXID", &config(false)), "https://arweave.net/TXID");
+    }
+
+    #[test]
+    fn http_urls_pass_through_unchanged() {
+        assert_eq!(resolve_media_url("https://example.com/a.png", &config(false)), "https://example.com/a.png");
+        assert_eq!(resolve_media_field(None, &config(false)), None);
+    }
+
+    #[test]
+    fn the_query_overrides_the_configured_default() {
+        assert!(config(true).resolve_media(None));
+        assert!(!config(true).resolve_media(Some(false)));
+        assert!(config(false).resolve_media(Some(true)));
+    }
+}
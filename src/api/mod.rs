@@ -3,21 +3,40 @@
 
 pub mod routes;
 pub mod handlers;
+pub mod auth;
+pub mod fallback;
+pub mod media;
+pub mod params;
+pub mod readiness;
+pub mod response;
+pub mod versioning;
 
 use axum::{
-    routing::{get},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, post},
+    Extension,
     Router,
 };
 use std::net::SocketAddr;
+use axum::http::Request;
+use tower_http::catch_panic::CatchPanicLayer;
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::trace::TraceLayer;
 
 use std::sync::Arc;
 use crate::db::Database;
 use crate::config::Config;
+use crate::blockchain::CatchUpStatus;
+use readiness::Readiness;
 
 /// Setup the API server
-pub async fn setup_api_server(config: &Config, db: Arc<Database>) -> anyhow::Result<()> {
-    let app = create_router(db);
+///
+/// Routes other than `/health`, `/ready` and `/metrics` answer 503 until `readiness` is released.
+/// `catch_up` is reported on `/indexer/status`.
+pub async fn setup_api_server(config: &Config, db: Arc<Database>, readiness: Readiness, catch_up: CatchUpStatus) -> anyhow::Result<()> {
+    let app = create_router(db, config, readiness, catch_up);
     
     // Create socket address
     let addr = SocketAddr::new(
@@ -34,24 +53,56 @@ pub async fn setup_api_server(config: &Config, db: Arc<Database>) -> anyhow::Res
 }
 
 /// Create the API router
-fn create_router(db: Arc<Database>) -> Router {
-    // Get a clone of the unwrapped pool for API handlers
-    let pool = db.pool.as_ref().clone();
+///
+/// `get` routes also answer `HEAD`; other methods on a known path get a 405 with an `Allow` header.
+/// Wrap a route in `versioning::deprecated` to announce its removal date.
+fn create_router(db: Arc<Database>, config: &Config, readiness: Readiness, catch_up: CatchUpStatus) -> Router {
+    // Handlers read through the read pool (a replica when configured); routes that write
+    // get the primary pool
+    let read_pool = db.read_pool.as_ref().clone();
+    let write_pool = db.pool.as_ref().clone();
+    
+    // Admin routes (require ADMIN_TOKEN); these write, so they always use the primary
+    let admin_routes = Router::new()
+        .route("/admin/dead-letters", get(handlers::admin::list_dead_letters))
+        .route("/admin/dead-letters/:id", delete(handlers::admin::delete_dead_letter))
+        .route("/admin/consistency/follower-counts", get(handlers::admin::check_follower_counts))
+        .route("/admin/recompute-stats", post(handlers::admin::recompute_stats))
+        .with_state(write_pool);
     
     Router::new()
         // Health routes
         .route("/health", get(handlers::health::health_check))
+        .route("/ready", get(handlers::health::ready))
+        .route("/metrics", get(handlers::metrics::metrics))
+        .route("/indexer/config", get(handlers::indexer::get_indexer_config))
+        .route("/indexer/status", get(handlers::indexer::get_indexer_status))
+        .route("/indexer/event-status", get(handlers::indexer::get_event_status))
         
         // Profile routes
         .route("/recent-profiles", get(handlers::profiles::latest_profiles))
-        .route("/profile/:address", get(handlers::profiles::get_profile_by_address))
+        .route("/profile/:profile_id", get(handlers::profiles::get_profile_by_id))
         .route("/profile/username/:username", get(handlers::profiles::get_profile_by_username))
+        .route("/profile/lookup", get(handlers::profiles::lookup_profile))
+        .route("/profiles/by-social", get(handlers::profiles::get_profile_by_social))
+        .route("/profiles/count-by-day", get(handlers::profiles::get_profile_counts_by_day))
+        .route("/profiles/active", get(handlers::profiles::get_active_profiles))
+        .route("/profile/:profile_id/export", get(handlers::profiles::export_profile))
+        .route("/profile/:profile_id/sensitive", get(handlers::profiles::get_profile_sensitive))
+        .route("/profile/:profile_id/platform-relationships", get(handlers::profiles::get_platform_relationships))
+        
+        // Search routes
+        .route("/search", get(handlers::search::search))
         
         // Social graph routes
         .route("/profile/following/:profile_id", get(handlers::social_graph::get_following))
         .route("/profile/followers/:profile_id", get(handlers::social_graph::get_followers))
         .route("/profile/is-following/:follower_profile_id/:following_profile_id", get(handlers::social_graph::check_following))
         .route("/profile/stats/:profile_id", get(handlers::social_graph::get_follow_stats))
+        .route("/profile/:profile_id/growth", get(handlers::social_graph::get_follow_growth))
+        .route("/profile/:profile_id/follower-trend", get(handlers::social_graph::get_follower_trend))
+        .route("/profile/:profile_id/follows/:other_profile_id", get(handlers::social_graph::get_follow_relationship))
+        .route("/profile/:profile_id/reciprocity", get(handlers::social_graph::get_follow_reciprocity))
         
         // Profile blocking routes
         .route("/profile/blocked/:profile_id", get(handlers::blocking::get_blocked_profiles))
@@ -61,22 +112,124 @@ fn create_router(db: Arc<Database>) -> Router {
         .route("/profile-events/:profile_id", get(handlers::profile_events::get_profile_events))
         .route("/profile-events/:profile_id/platforms", get(handlers::profile_events::get_platform_memberships))
         .route("/profile-events/:profile_id/blocking", get(handlers::profile_events::get_blocking_history))
+        .route("/profile/:profile_id/timeline.rss", get(handlers::profile_events::get_profile_timeline_rss))
         
         // Platform routes
         .route("/platforms", get(handlers::platforms::get_platforms))
         .route("/platforms/approved", get(handlers::platforms::get_approved_platforms))
+        .route("/platforms/pending", get(handlers::platforms::get_pending_platforms))
+        .route("/platforms/status-breakdown", get(handlers::platforms::get_platform_status_breakdown))
+        .route("/developer/:address/platforms", get(handlers::platforms::get_developer_platforms))
         .route("/platform/:platform_id", get(handlers::platforms::get_platform_by_id))
         .route("/platform/:platform_id/approval", get(handlers::platforms::get_platform_approval_status))
         .route("/platform/:platform_id/moderators", get(handlers::platforms::get_platform_moderators))
         .route("/platform/:platform_id/blocked", get(handlers::platforms::get_platform_blocked_profiles))
+        .route("/platform/:platform_id/members/joined", get(handlers::platforms::get_platform_members_joined))
+        .route("/platform/:platform_id/timeline", get(handlers::platforms::get_platform_timeline))
+        
+        // Content routes
+        .route("/content/:content_id", get(handlers::content::get_content_by_id))
+        .route("/content/:content_id/interactions/timeline", get(handlers::content::get_content_interaction_timeline))
         
         // Platform blocking routes
         .route("/platforms/blocked-by/:profile_id", get(handlers::blocking::get_blocked_platforms))
         .route("/platform/is-blocked/:profile_id/:platform_id", get(handlers::blocking::check_platform_blocked))
+        
+        // Bulk exports (require ADMIN_TOKEN)
+        .route("/export/social-graph", get(handlers::export::export_social_graph))
 
         // Add shared state
-        .with_state(pool)
+        .with_state(read_pool)
+        .merge(admin_routes)
+
+        // JSON 404 for unknown paths
+        .fallback(fallback::not_found)
+        
+        // Turn handler panics into a JSON 500 instead of a dropped connection
+        .layer(CatchPanicLayer::custom(fallback::panic_response))
+        
+        // Reject oversized bodies with 413 before they are buffered; this replaces axum's
+        // per-extractor default limit
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(config.api.max_request_body_bytes))
+        .layer(middleware::map_response(fallback::payload_too_large_json))
+        
+        // JSON body for 405 responses
+        .layer(middleware::map_response(fallback::method_not_allowed_json))
+        
+        // Report the API version on every response
+        .layer(middleware::map_response(versioning::add_version_header))
+        
+        // Pretty-print JSON bodies on request (?pretty=true) or by default (API_PRETTY_JSON)
+        .layer(middleware::from_fn(response::pretty_json))
         
-        // Add tracing
-        .layer(TraceLayer::new_for_http())
-}
\ No newline at end of file
+        // Hold back everything but the probes until startup has finished
+        .layer(middleware::from_fn(readiness::require_ready))
+        
+        // Make API and blockchain settings available to handlers
+        .layer(Extension(config.api.clone()))
+        .layer(Extension(config.blockchain.clone()))
+        .layer(Extension(readiness))
+        .layer(Extension(catch_up))
+        
+        // Add tracing, with the request id on the span so every log line for a request carries it
+        .layer(TraceLayer::new_for_http().make_span_with(|request: &Request<_>| {
+            let request_id = request
+                .headers()
+                .get("x-request-id")
+                .and_then(|id| id.to_str().ok())
+                .unwrap_or_default();
+            tracing::info_span!(
+                "request",
+                method = %request.method(),
+                uri = %request.uri(),
+                request_id = %request_id,
+            )
+        }))
+        
+        // Tag each request with an `x-request-id` (kept if the client sent one) and echo it back
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::{header, StatusCode}};
+    use diesel_async::AsyncPgConnection;
+    use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+    use diesel_async::pooled_connection::deadpool::Pool;
+    use tower::ServiceExt;
+
+    use crate::db::test_support::test_database;
+
+    async fn status(app: Router, path: &str) -> StatusCode {
+        let request = axum::http::Request::builder()
+            .uri(path)
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn reads_use_the_read_pool_and_admin_routes_the_primary() {
+        let Some(db) = test_database().await else { return };
+        // A primary nothing listens on, so any handler that reaches it fails
+        let unreachable = AsyncDieselConnectionManager::<AsyncPgConnection>::new("postgres://indexer@127.0.0.1:1/unreachable");
+        let primary = Pool::builder(unreachable).max_size(1).build().unwrap();
+        let db = Arc::new(Database::with_read_pool(primary, db.pool.as_ref().clone()));
+        let config = Config::from_lookup(|name| match name {
+            "DATABASE_URL" => Some("postgres://indexer@127.0.0.1:1/unreachable".to_string()),
+            "ADMIN_TOKEN" => Some("secret".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        let readiness = Readiness::new();
+        readiness.mark_ready();
+        let app = create_router(db, &config, readiness, CatchUpStatus::new());
+        
+        assert_eq!(status(app.clone(), "/profile/0xmissing").await, StatusCode::NOT_FOUND);
+        assert_eq!(status(app, "/admin/dead-letters").await, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,302 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    Json,
+};
+use serde::Deserialize;
+
+use crate::config::ApiConfig;
+
+/// Sort direction for list endpoints (`?order=asc|desc`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    /// Parse an `order` query value, defaulting to descending
+    pub fn from_param(value: Option<&str>) -> Option<Self> {
+        match value.map(|v| v.to_lowercase()) {
+            None => Some(SortOrder::Desc),
+            Some(v) if v == "desc" => Some(SortOrder::Desc),
+            Some(v) if v == "asc" => Some(SortOrder::Asc),
+            Some(_) => None,
+        }
+    }
+    
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        }
+    }
+}
+
+/// Page size used when a list request doesn't give a `limit`
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+
+/// Default for `MAX_PAGE_SIZE`, and the cap used if a request somehow arrives without an `ApiConfig`
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Largest offset passed to the database; anything further is past the end of every list
+pub const MAX_OFFSET: i64 = 1_000_000_000;
+
+/// Offset for a `page` (when greater than 1) or an explicit `offset`, clamped to `0..=MAX_OFFSET`
+///
+/// Huge values can't overflow; they just land past the end and return an empty page.
+pub fn page_offset(page: i64, limit: i64, offset: i64) -> i64 {
+    let offset = if page > 1 {
+        (page - 1).saturating_mul(limit.max(0))
+    } else {
+        offset
+    };
+    offset.clamp(0, MAX_OFFSET)
+}
+
+/// Pagination and sorting parameters shared by list endpoints
+///
+/// `page` takes precedence over `offset` when greater than 1. `limit` is capped at
+/// `MAX_PAGE_SIZE`; malformed or out-of-range values are rejected with a 400.
+#[derive(Debug, Clone)]
+pub struct ListParams {
+    pub limit: i64,
+    /// Effective offset, derived from `page` when one is given
+    pub offset: i64,
+    pub page: i64,
+    /// Column to sort by, validated by each handler
+    pub sort: Option<String>,
+    pub order: SortOrder,
+    /// Whether the request gave a `limit`, rather than getting `DEFAULT_PAGE_SIZE`
+    pub limit_given: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawListParams {
+    limit: Option<String>,
+    offset: Option<String>,
+    page: Option<String>,
+    sort: Option<String>,
+    order: Option<String>,
+}
+
+impl ListParams {
+    /// Build list parameters from raw query values
+    pub fn parse(
+        limit: Option<&str>,
+        offset: Option<&str>,
+        page: Option<&str>,
+        sort: Option<String>,
+        order: Option<&str>,
+        max_page_size: i64,
+    ) -> Result<Self, String> {
+        let limit = parse_number("limit", limit)?;
+        let limit_given = limit.is_some();
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if limit < 1 {
+            return Err("limit must be at least 1".to_string());
+        }
+        let limit = limit.min(max_page_size.max(1));
+        
+        let offset = parse_number("offset", offset)?.unwrap_or(0);
+        if offset < 0 {
+            return Err("offset must not be negative".to_string());
+        }
+        
+        let page = parse_number("page", page)?.unwrap_or(1);
+        if page < 1 {
+            return Err("page must be at least 1".to_string());
+        }
+        
+        // If page is provided, calculate the offset
+        let offset = page_offset(page, limit, offset);
+        
+        let order = SortOrder::from_param(order)
+            .ok_or_else(|| "order must be 'asc' or 'desc'".to_string())?;
+        let sort = sort.filter(|s| !s.is_empty());
+        
+        Ok(Self { limit, offset, page, sort, order, limit_given })
+    }
+    
+    /// Use `default` (capped at `max_page_size`) as the page size when no `limit` was given
+    pub fn default_limit(mut self, default: i64, max_page_size: i64) -> Self {
+        if !self.limit_given {
+            self.limit = default.clamp(1, max_page_size.max(1));
+            if self.page > 1 {
+                self.offset = page_offset(self.page, self.limit, 0);
+            }
+        }
+        self
+    }
+    
+    /// Pagination envelope for a list response
+    pub fn pagination(&self, total: i64) -> serde_json::Value {
+        serde_json::json!({
+            "total": total,
+            "limit": self.limit,
+            "offset": self.offset,
+            "page": self.page,
+            "total_pages": (total as f64 / self.limit as f64).ceil() as i64
+        })
+    }
+}
+
+fn parse_number(name: &str, value: Option<&str>) -> Result<Option<i64>, String> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        None => Ok(None),
+        Some(v) => v
+            .parse::<i64>()
+            .map(Some)
+            .map_err(|_| format!("{} must be an integer (got {:?})", name, v)),
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ListParams {
+    type Rejection = (StatusCode, Json<serde_json::Value>);
+    
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let bad_request = |error: String| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": error
+                }))
+            )
+        };
+        
+        let Query(raw) = Query::<RawListParams>::try_from_uri(&parts.uri)
+            .map_err(|e| bad_request(format!("Invalid query string: {}", e)))?;
+        let max_page_size = parts
+            .extensions
+            .get::<ApiConfig>()
+            .map(|config| config.max_page_size)
+            .unwrap_or(MAX_PAGE_SIZE);
+        
+        Self::parse(
+            raw.limit.as_deref(),
+            raw.offset.as_deref(),
+            raw.page.as_deref(),
+            raw.sort,
+            raw.order.as_deref(),
+            max_page_size,
+        )
+        .map_err(bad_request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    fn parse(limit: Option<&str>, offset: Option<&str>, page: Option<&str>) -> Result<ListParams, String> {
+        ListParams::parse(limit, offset, page, None, None, 100)
+    }
+
+    async fn extract(uri: &str, config: Option<ApiConfig>) -> Result<ListParams, StatusCode> {
+        let mut request = Request::builder().uri(uri).body(()).unwrap();
+        if let Some(config) = config {
+            request.extensions_mut().insert(config);
+        }
+        let (mut parts, _) = request.into_parts();
+        ListParams::from_request_parts(&mut parts, &()).await.map_err(|(status, _)| status)
+    }
+
+    #[test]
+    fn defaults_apply_when_nothing_is_given() {
+        let params = parse(None, None, None).unwrap();
+        assert_eq!((params.limit, params.offset, params.page), (DEFAULT_PAGE_SIZE, 0, 1));
+        assert_eq!(params.order, SortOrder::Desc);
+        assert!(params.sort.is_none());
+        
+        // Blank values count as absent
+        let params = parse(Some(""), Some(" "), Some("")).unwrap();
+        assert_eq!((params.limit, params.offset, params.page), (DEFAULT_PAGE_SIZE, 0, 1));
+    }
+
+    #[test]
+    fn limit_is_capped_at_max_page_size() {
+        assert_eq!(parse(Some("1000"), None, None).unwrap().limit, 100);
+        assert_eq!(parse(Some("100"), None, None).unwrap().limit, 100);
+        assert_eq!(parse(Some("7"), None, None).unwrap().limit, 7);
+        assert_eq!(ListParams::parse(Some("10"), None, None, None, None, 0).unwrap().limit, 1);
+    }
+
+    #[test]
+    fn page_takes_precedence_over_offset() {
+        let params = parse(Some("20"), Some("5"), Some("3")).unwrap();
+        assert_eq!((params.offset, params.page), (40, 3));
+        
+        let params = parse(Some("20"), Some("5"), Some("1")).unwrap();
+        assert_eq!(params.offset, 5);
+    }
+
+    #[test]
+    fn page_offset_saturates_instead_of_overflowing() {
+        assert_eq!(page_offset(i64::MAX, 100, 0), MAX_OFFSET);
+        assert_eq!(page_offset(1, 100, i64::MAX), MAX_OFFSET);
+        assert_eq!(page_offset(2, -5, 0), 0);
+        assert_eq!(page_offset(1, 10, -3), 0);
+    }
+
+    #[test]
+    fn huge_offsets_and_pages_clamp_instead_of_failing() {
+        assert_eq!(parse(None, Some("99999999999"), None).unwrap().offset, MAX_OFFSET);
+        assert_eq!(parse(Some("100"), None, Some(&i64::MAX.to_string())).unwrap().offset, MAX_OFFSET);
+        
+        // Past i64 itself is a malformed number, not an overflow
+        assert!(parse(None, Some("99999999999999999999"), None).is_err());
+    }
+
+    #[test]
+    fn malformed_or_out_of_range_values_are_rejected() {
+        assert!(parse(Some("ten"), None, None).is_err());
+        assert!(parse(Some("0"), None, None).is_err());
+        assert!(parse(Some("-1"), None, None).is_err());
+        assert!(parse(None, Some("-1"), None).is_err());
+        assert!(parse(None, None, Some("0")).is_err());
+        assert!(ListParams::parse(None, None, None, None, Some("sideways"), 100).is_err());
+        assert_eq!(ListParams::parse(None, None, None, None, Some("ASC"), 100).unwrap().order, SortOrder::Asc);
+    }
+
+    #[test]
+    fn default_limit_only_replaces_a_missing_limit() {
+        let params = parse(None, None, Some("3")).unwrap().default_limit(10, 100);
+        assert_eq!((params.limit, params.offset), (10, 20));
+        
+        let params = parse(Some("7"), None, None).unwrap().default_limit(10, 100);
+        assert_eq!(params.limit, 7);
+        
+        let params = parse(None, None, None).unwrap().default_limit(500, 100);
+        assert_eq!(params.limit, 100);
+    }
+
+    #[tokio::test]
+    async fn extractor_caps_at_the_configured_page_size() {
+        let config = ApiConfig {
+            admin_token: None,
+            max_page_size: 25,
+            recent_profiles_default: 10,
+            ipfs_gateway: String::new(),
+            arweave_gateway: String::new(),
+            resolve_media_default: false,
+            max_request_body_bytes: 1024,
+            pretty_json_default: false,
+            hide_unapproved_platforms: false,
+        };
+        assert_eq!(extract("/x?limit=500", Some(config)).await.unwrap().limit, 25);
+    }
+
+    #[tokio::test]
+    async fn extractor_without_config_falls_back_to_the_default_cap() {
+        assert_eq!(extract("/x?limit=1000000", None).await.unwrap().limit, MAX_PAGE_SIZE);
+        assert_eq!(extract("/x?limit=abc", None).await.unwrap_err(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension,
+    Json,
+};
+
+/// Paths served while the indexer is still starting, so orchestrators can probe it
+const ALWAYS_SERVED_PATHS: &[&str] = &["/health", "/ready", "/metrics"];
+
+/// Set once migrations have run and the database pool has been verified
+///
+/// The API starts listening before that so `/ready` can report progress; every other route
+/// answers 503 until the gate is released.
+#[derive(Debug, Clone, Default)]
+pub struct Readiness(Arc<AtomicBool>);
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Release the gate; the API serves every route from now on
+    pub fn mark_ready(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+    
+    pub fn is_ready(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// Answer 503 for everything but the probe paths until the indexer is ready
+pub async fn require_ready(
+    Extension(readiness): Extension<Readiness>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if readiness.is_ready() || ALWAYS_SERVED_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(serde_json::json!({
+            "error": "Indexer is starting up"
+        }))
+    ).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    use crate::api::handlers::health;
+
+    /// The probe plus one gated route, behind the same middleware as the real router
+    fn app(readiness: Readiness) -> Router {
+        Router::new()
+            .route("/ready", get(health::ready))
+            .route("/profiles", get(|| async { "ok" }))
+            .layer(middleware::from_fn(require_ready))
+            .layer(Extension(readiness))
+    }
+
+    async fn status(app: Router, path: &str) -> StatusCode {
+        let request = axum::http::Request::builder().uri(path).body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn ready_reports_503_until_released_then_200() {
+        let readiness = Readiness::new();
+        assert_eq!(status(app(readiness.clone()), "/ready").await, StatusCode::SERVICE_UNAVAILABLE);
+        
+        readiness.mark_ready();
+        assert_eq!(status(app(readiness), "/ready").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn other_routes_are_held_back_until_released() {
+        let readiness = Readiness::new();
+        assert_eq!(status(app(readiness.clone()), "/profiles").await, StatusCode::SERVICE_UNAVAILABLE);
+        
+        readiness.mark_ready();
+        assert_eq!(status(app(readiness), "/profiles").await, StatusCode::OK);
+    }
+}
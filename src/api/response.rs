@@ -0,0 +1,193 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension,
+    Json,
+};
+use tracing::{debug, error, warn};
+
+use crate::config::ApiConfig;
+
+/// Content type sent with every JSON body
+pub const JSON_CONTENT_TYPE: &str = "application/json; charset=utf-8";
+
+/// JSON body sent with an explicit `application/json; charset=utf-8` content type
+#[derive(Debug)]
+pub struct JsonBody(pub serde_json::Value);
+
+impl IntoResponse for JsonBody {
+    fn into_response(self) -> Response {
+        (
+            [(header::CONTENT_TYPE, HeaderValue::from_static(JSON_CONTENT_TYPE))],
+            Json(self.0),
+        )
+            .into_response()
+    }
+}
+
+/// Status and JSON body returned by handlers
+pub type JsonResponse = (StatusCode, JsonBody);
+
+/// Map a database error to a response: `NotFound` becomes a 404, anything else a 500
+pub fn db_error(e: diesel::result::Error, not_found: &str, context: &str) -> JsonResponse {
+    match e {
+        diesel::result::Error::NotFound => {
+            debug!("{}", not_found);
+            (
+                StatusCode::NOT_FOUND,
+                JsonBody(serde_json::json!({
+                    "error": not_found
+                }))
+            )
+        },
+        e => {
+            error!("{}: {}", context, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                JsonBody(serde_json::json!({
+                    "error": format!("{}: {}", context, e)
+                }))
+            )
+        }
+    }
+}
+
+/// The request's `pretty` query parameter, if any; a bare `pretty` counts as true
+fn pretty_requested(query: Option<&str>) -> Option<bool> {
+    query?.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, "true"));
+        (key == "pretty").then(|| matches!(value, "true" | "1"))
+    })
+}
+
+/// Pretty-print JSON responses when the request passes `pretty=true` or `API_PRETTY_JSON` is set
+///
+/// Handlers always serialize compactly; this re-indents the finished body, so it only costs
+/// anything when pretty output was asked for. Non-JSON responses pass through untouched.
+pub async fn pretty_json(
+    Extension(api_config): Extension<ApiConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let pretty = pretty_requested(request.uri().query()).unwrap_or(api_config.pretty_json_default);
+    let response = next.run(request).await;
+    
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !pretty || !is_json {
+        return response;
+    }
+    
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Failed to buffer response for pretty-printing: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": "Internal server error"
+                }))
+            ).into_response();
+        }
+    };
+    
+    let pretty_body = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .and_then(|value| serde_json::to_vec_pretty(&value));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    match pretty_body {
+        Ok(pretty_body) => Response::from_parts(parts, Body::from(pretty_body)),
+        // Not valid JSON after all; send it as the handler wrote it
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content_type(response: &Response) -> &str {
+        response.headers().get(header::CONTENT_TYPE).unwrap().to_str().unwrap()
+    }
+
+    #[test]
+    fn found_body_carries_the_charset() {
+        let response = (StatusCode::OK, JsonBody(serde_json::json!({ "id": 1 }))).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(content_type(&response), JSON_CONTENT_TYPE);
+    }
+
+    #[test]
+    fn not_found_maps_to_404() {
+        let response = db_error(diesel::result::Error::NotFound, "Platform not found", "Failed to fetch platform").into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(content_type(&response), JSON_CONTENT_TYPE);
+    }
+
+    #[test]
+    fn other_database_errors_map_to_500() {
+        let response = db_error(diesel::result::Error::RollbackTransaction, "Platform not found", "Failed to fetch platform").into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(content_type(&response), JSON_CONTENT_TYPE);
+    }
+
+    /// A JSON and a text route behind the pretty-print middleware
+    fn app(pretty_json_default: bool) -> axum::Router {
+        use axum::{middleware, routing::get, Router};
+        
+        let config = ApiConfig {
+            admin_token: None,
+            max_page_size: 100,
+            recent_profiles_default: 10,
+            ipfs_gateway: String::new(),
+            arweave_gateway: String::new(),
+            resolve_media_default: false,
+            max_request_body_bytes: 1024,
+            pretty_json_default,
+            hide_unapproved_platforms: false,
+        };
+        Router::new()
+            .route("/json", get(|| async { Json(serde_json::json!({ "id": 1, "tags": ["a"] })) }))
+            .route("/text", get(|| async { "{\"id\":1}" }))
+            .layer(middleware::from_fn(pretty_json))
+            .layer(Extension(config))
+    }
+
+    async fn body(app: axum::Router, uri: &str) -> String {
+        use tower::ServiceExt;
+        
+        let request = axum::http::Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        String::from_utf8(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn pretty_param_switches_between_compact_and_indented_json() {
+        let compact = body(app(false), "/json").await;
+        let pretty = body(app(false), "/json?pretty=true").await;
+        
+        assert_eq!(compact, r#"{"id":1,"tags":["a"]}"#);
+        assert_eq!(pretty, "{\n  \"id\": 1,\n  \"tags\": [\n    \"a\"\n  ]\n}");
+        assert_eq!(body(app(false), "/json?pretty").await, pretty);
+    }
+
+    #[tokio::test]
+    async fn pretty_default_can_be_turned_off_per_request() {
+        assert!(body(app(true), "/json").await.contains('\n'));
+        assert!(!body(app(true), "/json?pretty=false").await.contains('\n'));
+    }
+
+    #[tokio::test]
+    async fn non_json_bodies_are_left_alone() {
+        assert_eq!(body(app(true), "/text?pretty=true").await, r#"{"id":1}"#);
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    http::{HeaderName, HeaderValue},
+    middleware,
+    response::Response,
+    routing::MethodRouter,
+};
+
+use crate::API_VERSION;
+
+/// Header carrying `API_VERSION` on every response
+pub const API_VERSION_HEADER: HeaderName = HeaderName::from_static("x-api-version");
+/// Marks a response as coming from a deprecated endpoint
+pub const DEPRECATION_HEADER: HeaderName = HeaderName::from_static("deprecation");
+/// HTTP date after which a deprecated endpoint may be removed
+pub const SUNSET_HEADER: HeaderName = HeaderName::from_static("sunset");
+
+/// Add the `X-API-Version` header to a response
+pub async fn add_version_header(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert(API_VERSION_HEADER, HeaderValue::from_static(API_VERSION));
+    response
+}
+
+/// Mark a route as deprecated, adding `Deprecation: true` and a `Sunset` date to its responses
+///
+/// `sunset` is an HTTP date, e.g. `deprecated(get(handler), "Wed, 31 Dec 2025 23:59:59 GMT")`.
+pub fn deprecated<S>(route: MethodRouter<S>, sunset: &'static str) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route.layer(middleware::map_response(move |mut response: Response| async move {
+        let headers = response.headers_mut();
+        headers.insert(DEPRECATION_HEADER, HeaderValue::from_static("true"));
+        headers.insert(SUNSET_HEADER, HeaderValue::from_static(sunset));
+        response
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    const SUNSET: &str = "Wed, 31 Dec 2025 23:59:59 GMT";
+
+    /// A current and a deprecated route behind the same version layer as the real router
+    fn app() -> Router {
+        Router::new()
+            .route("/current", get(|| async { "ok" }))
+            .route("/old", deprecated(get(|| async { "ok" }), SUNSET))
+            .layer(middleware::map_response(add_version_header))
+    }
+
+    async fn call(path: &str) -> Response {
+        let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+        app().oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn deprecated_route_carries_version_deprecation_and_sunset() {
+        let response = call("/old").await;
+        assert_eq!(response.headers()[API_VERSION_HEADER], API_VERSION);
+        assert_eq!(response.headers()[DEPRECATION_HEADER], "true");
+        assert_eq!(response.headers()[SUNSET_HEADER], SUNSET);
+    }
+
+    #[tokio::test]
+    async fn other_routes_only_carry_the_version() {
+        let response = call("/current").await;
+        assert_eq!(response.headers()[API_VERSION_HEADER], API_VERSION);
+        assert!(response.headers().get(DEPRECATION_HEADER).is_none());
+        assert!(response.headers().get(SUNSET_HEADER).is_none());
+    }
+}
+
@@ -14,6 +14,8 @@ use crate::events::blocking_events::{
     process_block_list_created_event
 };
 
+use super::dead_letter::record_dead_letter;
+use super::publisher::{publish_applied, EventPublisher, NoopPublisher};
 use super::listener::BlockchainEvent;
 
 /// Handler for block list related blockchain events
@@ -22,17 +24,29 @@ pub struct BlockListEventHandler {
     db: Arc<Database>,
     /// Event receiver channel
     rx: mpsc::Receiver<BlockchainEvent>,
+    /// Worker ID for recording failed events
+    worker_id: String,
+    /// Publisher for events this handler has applied
+    publisher: Arc<dyn EventPublisher>,
 }
 
 impl BlockListEventHandler {
     /// Create a new block list event handler
-    pub fn new(db: Arc<Database>, rx: mpsc::Receiver<BlockchainEvent>, _worker_id: String) -> Self {
+    pub fn new(db: Arc<Database>, rx: mpsc::Receiver<BlockchainEvent>, worker_id: String) -> Self {
         Self {
             db,
             rx,
+            worker_id,
+            publisher: Arc::new(NoopPublisher),
         }
     }
     
+    /// Publish the block list events this handler applies
+    pub fn with_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = publisher;
+        self
+    }
+    
     /// Get a database connection from the pool
     async fn get_connection(&self) -> Result<DbConnection> {
         self.db.get_connection()
@@ -45,7 +59,7 @@ impl BlockListEventHandler {
         debug!("BlockList handler examining event: {}", event.event_type);
         
         // Only process events from the block_list module
-        if !event.event_type.contains("::block_list::") {
+        if event.module_name != crate::BLOCK_LIST_MODULE_NAME {
             // Not from block_list module, skip it
             return Ok(());
         }
@@ -58,18 +72,23 @@ impl BlockListEventHandler {
         let mut conn = self.get_connection().await?;
         
         // Process based on specific event type
-        if event.event_type.contains("BlockListCreatedEvent") {
-            info!("Processing BlockList creation event");
-            process_block_list_created_event(&mut conn, &event.data).await?;
-        } else if event.event_type.contains("BlockProfileEvent") {
-            info!("Processing profile block event");
-            process_profile_block_event(&mut conn, &event.data).await?;
-        } else if event.event_type.contains("UnblockProfileEvent") {
-            info!("Processing profile unblock event");
-            process_profile_unblock_event(&mut conn, &event.data).await?;
-        } else {
-            // Unknown block_list event type
-            debug!("Unknown block_list event type: {}", event.event_type);
+        match event.struct_name.as_str() {
+            "BlockListCreatedEvent" => {
+                info!("Processing BlockList creation event");
+                process_block_list_created_event(&mut conn, &event.data).await?;
+            },
+            "BlockProfileEvent" => {
+                info!("Processing profile block event");
+                process_profile_block_event(&mut conn, &event.data, Some(&event.event_id)).await?;
+            },
+            "UnblockProfileEvent" => {
+                info!("Processing profile unblock event");
+                process_profile_unblock_event(&mut conn, &event.data, Some(&event.event_id)).await?;
+            },
+            _ => {
+                // Unknown block_list event type
+                debug!("Unknown block_list event type: {}", event.event_type);
+            }
         }
         
         Ok(())
@@ -82,8 +101,13 @@ impl BlockListEventHandler {
         while let Some(event) = self.rx.recv().await {
             debug!("Received event: {:?}", event.event_type);
             
-            if let Err(e) = self.process_event(event).await {
-                error!("Error processing event: {}", e);
+            let applied_event = event.clone();
+            match self.process_event(event).await {
+                Ok(()) => publish_applied(self.publisher.as_ref(), crate::BLOCK_LIST_MODULE_NAME, &applied_event).await,
+                Err(e) => {
+                    error!("Error processing event: {}", e);
+                    record_dead_letter(&self.db, &self.worker_id, &applied_event, &e).await;
+                }
             }
         }
         
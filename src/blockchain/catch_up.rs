@@ -0,0 +1,69 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tracing::{info, warn};
+
+use crate::metrics::CAUGHT_UP;
+
+/// Whether the listener has indexed everything up to the chain tip
+///
+/// Starts out behind; the listener flips it as it reaches or loses the tip, and each
+/// transition is logged once. Shared with the API for `/indexer/status`.
+#[derive(Debug, Clone, Default)]
+pub struct CatchUpStatus(Arc<AtomicBool>);
+
+impl CatchUpStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record whether the listener is at the tip, returning true if that changed
+    pub fn update(&self, caught_up: bool) -> bool {
+        let was_caught_up = self.0.swap(caught_up, Ordering::AcqRel);
+        CAUGHT_UP.set(caught_up as i64);
+        if was_caught_up == caught_up {
+            return false;
+        }
+        
+        if caught_up {
+            info!(caught_up = true, "Indexer caught up with the chain tip");
+        } else {
+            warn!(caught_up = false, "Indexer fell behind the chain tip");
+        }
+        true
+    }
+    
+    pub fn is_caught_up(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_reports_only_transitions() {
+        let status = CatchUpStatus::new();
+        assert!(!status.is_caught_up());
+        assert!(!status.update(false));
+        
+        assert!(status.update(true));
+        assert!(status.is_caught_up());
+        assert!(!status.update(true));
+        
+        assert!(status.update(false));
+        assert!(!status.is_caught_up());
+    }
+
+    #[test]
+    fn clones_share_the_flag() {
+        let status = CatchUpStatus::new();
+        let api_view = status.clone();
+        status.update(true);
+        assert!(api_view.is_caught_up());
+    }
+}
@@ -0,0 +1,570 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Text};
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::db::{Database, DbConnection};
+use crate::events::{ContentCreatedEvent, ContentInteractionEvent, ContentInteractionRemovedEvent, IPRegisteredEvent};
+use crate::schema;
+
+use super::dead_letter::record_dead_letter;
+use super::ignored::skip_ignored;
+use super::listener::BlockchainEvent;
+use super::platform_handler::record_platform_activity;
+use super::publisher::{publish_metered, EventPublisher, NoopPublisher};
+
+/// Counter on `content` kept for an interaction type; other types are recorded but not counted
+fn counter_column(interaction_type: &str) -> Option<&'static str> {
+    match interaction_type {
+        "like" => Some("like_count"),
+        "view" => Some("view_count"),
+        "share" => Some("share_count"),
+        _ => None,
+    }
+}
+
+/// Add `delta` to the content counter for `interaction_type`, never going below zero
+///
+/// Unknown content is left alone; it takes its counters from `content_interactions` once it
+/// is stored.
+async fn adjust_counter(
+    conn: &mut AsyncPgConnection,
+    content_id: &str,
+    interaction_type: &str,
+    delta: i32,
+) -> QueryResult<usize> {
+    let Some(column) = counter_column(interaction_type) else {
+        return Ok(0);
+    };
+    diesel::sql_query(format!("UPDATE content SET {0} = GREATEST({0} + $2, 0) WHERE id = $1", column))
+        .bind::<Text, _>(content_id)
+        .bind::<Integer, _>(delta)
+        .execute(conn)
+        .await
+}
+
+/// Set a content row's counters from the interactions recorded for it
+///
+/// Interactions indexed before their content had no counter to bump.
+async fn recount_interactions(conn: &mut AsyncPgConnection, content_id: &str) -> QueryResult<usize> {
+    diesel::sql_query(
+        "UPDATE content SET
+             like_count = (SELECT COUNT(*) FROM content_interactions WHERE content_id = $1 AND interaction_type = 'like'),
+             view_count = (SELECT COUNT(*) FROM content_interactions WHERE content_id = $1 AND interaction_type = 'view'),
+             share_count = (SELECT COUNT(*) FROM content_interactions WHERE content_id = $1 AND interaction_type = 'share')
+         WHERE id = $1"
+    )
+    .bind::<Text, _>(content_id)
+    .execute(conn)
+    .await
+}
+
+/// Count everyone who interacted with new content as active on its platform that day
+///
+/// Interactions indexed before the content didn't know its platform yet.
+async fn backfill_platform_activity(conn: &mut AsyncPgConnection, content_id: &str, platform_id: &str) -> QueryResult<usize> {
+    diesel::sql_query(
+        "INSERT INTO platform_daily_active_profiles (platform_id, date, profile_id)
+         SELECT $2, created_at::date, profile_id FROM content_interactions WHERE content_id = $1
+         ON CONFLICT DO NOTHING"
+    )
+    .bind::<Text, _>(content_id)
+    .bind::<Text, _>(platform_id)
+    .execute(conn)
+    .await
+}
+
+/// Time of the checkpoint holding an event
+fn checkpoint_time(timestamp_ms: u64) -> Result<NaiveDateTime> {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms as i64)
+        .map(|at| at.naive_utc())
+        .ok_or_else(|| anyhow!("Invalid checkpoint timestamp: {}", timestamp_ms))
+}
+
+/// Handler for content related blockchain events
+pub struct ContentEventHandler {
+    /// Database connection
+    db: Arc<Database>,
+    /// Event receiver channel
+    rx: mpsc::Receiver<BlockchainEvent>,
+    /// Worker ID for recording failed events
+    worker_id: String,
+    /// Publisher for events this handler has applied
+    publisher: Arc<dyn EventPublisher>,
+}
+
+impl ContentEventHandler {
+    /// Create a new content event handler
+    pub fn new(db: Arc<Database>, rx: mpsc::Receiver<BlockchainEvent>, worker_id: String) -> Self {
+        Self {
+            db,
+            rx,
+            worker_id,
+            publisher: Arc::new(NoopPublisher),
+        }
+    }
+    
+    /// Publish the content events this handler applies
+    pub fn with_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = publisher;
+        self
+    }
+    
+    /// Get a database connection from the pool
+    async fn get_connection(&self) -> Result<DbConnection> {
+        self.db.get_connection()
+            .await
+            .map_err(|e| anyhow!("Failed to get database connection: {}", e))
+    }
+    
+    /// Store new content, dated at the checkpoint that holds the event
+    ///
+    /// Re-indexing the same content is a no-op, so a reply only bumps its parent's
+    /// `comment_count` the first time. Interactions indexed before the content are counted,
+    /// and their profiles marked active on the platform, when it's stored; likewise an IP
+    /// registered before the content sets its `has_ip_registered`.
+    async fn process_content_created(&self, event: &ContentCreatedEvent, timestamp_ms: u64) -> Result<()> {
+        let new_content = event.into_model(timestamp_ms)?;
+        let mut conn = self.get_connection().await?;
+        
+        let inserted = conn.build_transaction()
+            .run(|mut conn| Box::pin(async move {
+                let inserted = diesel::insert_into(schema::content::table)
+                    .values(&new_content)
+                    .on_conflict(schema::content::id)
+                    .do_nothing()
+                    .execute(&mut conn)
+                    .await?;
+                if inserted == 0 {
+                    return Result::<_, diesel::result::Error>::Ok(false);
+                }
+                recount_interactions(&mut conn, &new_content.id).await?;
+                backfill_platform_activity(&mut conn, &new_content.id, &new_content.platform_id).await?;
+                diesel::update(schema::content::table.find(&new_content.id))
+                    .filter(diesel::dsl::exists(schema::ip_registrations::table.find(&new_content.id)))
+                    .set(schema::content::has_ip_registered.eq(true))
+                    .execute(&mut conn)
+                    .await?;
+                
+                if let Some(parent_id) = &new_content.parent_id {
+                    diesel::update(schema::content::table.find(parent_id))
+                        .set(schema::content::comment_count.eq(schema::content::comment_count + 1))
+                        .execute(&mut conn)
+                        .await?;
+                }
+                record_platform_activity(&mut conn, &new_content.platform_id, &new_content.creator_id, new_content.created_at).await?;
+                
+                Result::<_, diesel::result::Error>::Ok(true)
+            }))
+            .await?;
+        
+        if inserted {
+            info!("Stored content {} on platform {}", event.content_id, event.platform_id);
+        } else {
+            debug!("Content {} already stored", event.content_id);
+        }
+        Ok(())
+    }
+    
+    /// Record an interaction and bump its content counter, all in one transaction
+    ///
+    /// A repeated interaction by the same profile is recorded once and counted once. The profile
+    /// is active on the content's platform that day; when the content isn't stored yet its
+    /// platform is unknown, so that waits for the content.
+    async fn process_content_interaction(&self, event: &ContentInteractionEvent, timestamp_ms: u64) -> Result<()> {
+        let at = checkpoint_time(timestamp_ms)?;
+        let mut conn = self.get_connection().await?;
+        
+        let counted = conn.build_transaction()
+            .run(|mut conn| Box::pin(async move {
+                let inserted = diesel::insert_into(schema::content_interactions::table)
+                    .values((
+                        schema::content_interactions::content_id.eq(&event.content_id),
+                        schema::content_interactions::profile_id.eq(&event.profile_id),
+                        schema::content_interactions::interaction_type.eq(&event.interaction_type),
+                        schema::content_interactions::created_at.eq(at),
+                    ))
+                    .on_conflict_do_nothing()
+                    .execute(&mut conn)
+                    .await?;
+                
+                let counted = if inserted > 0 {
+                    adjust_counter(&mut conn, &event.content_id, &event.interaction_type, 1).await? > 0
+                } else {
+                    false
+                };
+                let platform_id = schema::content::table
+                    .find(&event.content_id)
+                    .select(schema::content::platform_id)
+                    .first::<String>(&mut conn)
+                    .await
+                    .optional()?;
+                match platform_id {
+                    Some(platform_id) => record_platform_activity(&mut conn, &platform_id, &event.profile_id, at).await?,
+                    None => {
+                        crate::db::touch_profile_activity(&mut conn, &event.profile_id, at).await?;
+                    }
+                }
+                
+                Result::<_, diesel::result::Error>::Ok(counted)
+            }))
+            .await?;
+        
+        if !counted {
+            debug!("{} interaction by {} on {} not counted (repeated, uncounted type or unknown content)",
+                event.interaction_type, event.profile_id, event.content_id);
+        }
+        Ok(())
+    }
+    
+    /// Remove an interaction and take it off its content counter, all in one transaction
+    ///
+    /// Removing an interaction that was never recorded changes nothing.
+    async fn process_content_interaction_removed(&self, event: &ContentInteractionRemovedEvent) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+        
+        let removed = conn.build_transaction()
+            .run(|mut conn| Box::pin(async move {
+                let deleted = diesel::delete(schema::content_interactions::table.find((
+                        &event.content_id,
+                        &event.profile_id,
+                        &event.interaction_type,
+                    )))
+                    .execute(&mut conn)
+                    .await?;
+                
+                if deleted > 0 {
+                    adjust_counter(&mut conn, &event.content_id, &event.interaction_type, -1).await?;
+                }
+                Result::<_, diesel::result::Error>::Ok(deleted > 0)
+            }))
+            .await?;
+        
+        if !removed {
+            debug!("No {} interaction by {} on {} to remove",
+                event.interaction_type, event.profile_id, event.content_id);
+        }
+        Ok(())
+    }
+    
+    /// Record an IP registration and flag the content it covers
+    ///
+    /// The registration is kept even when the content isn't stored yet, so the content is
+    /// flagged once it lands.
+    async fn process_ip_registered(&self, event: &IPRegisteredEvent, timestamp_ms: u64) -> Result<()> {
+        let at = checkpoint_time(timestamp_ms)?;
+        let mut conn = self.get_connection().await?;
+        
+        let flagged = conn.build_transaction()
+            .run(|mut conn| Box::pin(async move {
+                diesel::insert_into(schema::ip_registrations::table)
+                    .values((
+                        schema::ip_registrations::ip_id.eq(&event.ip_id),
+                        schema::ip_registrations::registered_at.eq(at),
+                    ))
+                    .on_conflict_do_nothing()
+                    .execute(&mut conn)
+                    .await?;
+                let flagged = diesel::update(schema::content::table.find(&event.ip_id))
+                    .set(schema::content::has_ip_registered.eq(true))
+                    .execute(&mut conn)
+                    .await?;
+                
+                Result::<_, diesel::result::Error>::Ok(flagged > 0)
+            }))
+            .await?;
+        
+        if !flagged {
+            debug!("IP {} registered before its content was indexed", event.ip_id);
+        }
+        Ok(())
+    }
+    
+    /// Process raw blockchain events
+    ///
+    /// Returns whether the event was a content event, so only those are published.
+    async fn process_event(&self, event: &BlockchainEvent) -> Result<bool> {
+        match event.struct_name.as_str() {
+            "ContentCreatedEvent" => {
+                let content_event = crate::events::parse_event::<ContentCreatedEvent>(&event.data)?;
+                if skip_ignored("content", event, &[&content_event.creator_id]) {
+                    return Ok(false);
+                }
+                self.process_content_created(&content_event, event.timestamp_ms).await?;
+            }
+            "ContentInteractionEvent" => {
+                let interaction = crate::events::parse_event::<ContentInteractionEvent>(&event.data)?;
+                if skip_ignored("content", event, &[&interaction.profile_id]) {
+                    return Ok(false);
+                }
+                self.process_content_interaction(&interaction, event.timestamp_ms).await?;
+            }
+            "ContentInteractionRemovedEvent" => {
+                let removal = crate::events::parse_event::<ContentInteractionRemovedEvent>(&event.data)?;
+                if skip_ignored("content", event, &[&removal.profile_id]) {
+                    return Ok(false);
+                }
+                self.process_content_interaction_removed(&removal).await?;
+            }
+            "IPRegisteredEvent" => {
+                let registration = crate::events::parse_event::<IPRegisteredEvent>(&event.data)?;
+                self.process_ip_registered(&registration, event.timestamp_ms).await?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+    
+    /// Start listening for content events
+    pub async fn start(&mut self) -> Result<()> {
+        info!("Starting content event handler");
+        
+        while let Some(event) = self.rx.recv().await {
+            match self.process_event(&event).await {
+                Ok(true) => publish_metered(self.publisher.as_ref(), &event).await,
+                Ok(false) => {}
+                Err(e) => {
+                    error!("Error processing content event {}: {}", event.event_id, e);
+                    record_dead_letter(&self.db, &self.worker_id, &event, &e).await;
+                }
+            }
+        }
+        
+        warn!("Content event handler channel closed");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{test_database, unique_id};
+
+    fn blockchain_event(struct_name: &str, data: serde_json::Value) -> BlockchainEvent {
+        let tx_digest = unique_id("tx");
+        BlockchainEvent {
+            event_id: format!("{}:0", tx_digest),
+            tx_digest,
+            event_type: format!("0x1::content::{}", struct_name),
+            package_address: "0x1".to_string(),
+            module_name: "content".to_string(),
+            struct_name: struct_name.to_string(),
+            sender: "0x2".to_string(),
+            data,
+            timestamp_ms: 1_700_000_000_000,
+        }
+    }
+
+    fn content_event(content_id: &str, parent_id: Option<&str>) -> BlockchainEvent {
+        blockchain_event("ContentCreatedEvent", serde_json::json!({
+            "content_id": content_id,
+            "creator_id": "0xcreator",
+            "platform_id": "0xplatform",
+            "parent_id": parent_id,
+        }))
+    }
+
+    fn interaction_event(content_id: &str, profile_id: &str, interaction_type: &str) -> BlockchainEvent {
+        blockchain_event("ContentInteractionEvent", serde_json::json!({
+            "content_id": content_id,
+            "profile_id": profile_id,
+            "interaction_type": interaction_type,
+        }))
+    }
+
+    fn removal_event(content_id: &str, profile_id: &str, interaction_type: &str) -> BlockchainEvent {
+        blockchain_event("ContentInteractionRemovedEvent", serde_json::json!({
+            "content_id": content_id,
+            "profile_id": profile_id,
+            "interaction_type": interaction_type,
+        }))
+    }
+
+    async fn active_days(db: &Database, platform_id: &str, profile_id: &str) -> i64 {
+        let mut conn = db.get_connection().await.unwrap();
+        schema::platform_daily_active_profiles::table
+            .filter(schema::platform_daily_active_profiles::platform_id.eq(platform_id))
+            .filter(schema::platform_daily_active_profiles::profile_id.eq(profile_id))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap()
+    }
+
+    async fn has_ip_registered(db: &Database, content_id: &str) -> bool {
+        let mut conn = db.get_connection().await.unwrap();
+        schema::content::table
+            .find(content_id)
+            .select(schema::content::has_ip_registered)
+            .first(&mut conn)
+            .await
+            .unwrap()
+    }
+
+    async fn counters(db: &Database, content_id: &str) -> (i32, i32, i32) {
+        let mut conn = db.get_connection().await.unwrap();
+        schema::content::table
+            .find(content_id)
+            .select((schema::content::like_count, schema::content::view_count, schema::content::share_count))
+            .first(&mut conn)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn only_likes_views_and_shares_have_counters() {
+        assert_eq!(counter_column("like"), Some("like_count"));
+        assert_eq!(counter_column("view"), Some("view_count"));
+        assert_eq!(counter_column("share"), Some("share_count"));
+        assert_eq!(counter_column("bookmark"), None);
+    }
+
+    async fn comment_count(db: &Database, content_id: &str) -> Option<i32> {
+        let mut conn = db.get_connection().await.unwrap();
+        schema::content::table
+            .find(content_id)
+            .select(schema::content::comment_count)
+            .first::<i32>(&mut conn)
+            .await
+            .optional()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn created_content_is_stored_once() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db.clone(), mpsc::channel(1).1, "content-test".to_string());
+        let parent = unique_id("content");
+        let reply = unique_id("content");
+        
+        assert!(handler.process_event(&content_event(&parent, None)).await.unwrap());
+        let reply_event = content_event(&reply, Some(&parent));
+        handler.process_event(&reply_event).await.unwrap();
+        handler.process_event(&reply_event).await.unwrap();
+        
+        assert_eq!(comment_count(&db, &reply).await, Some(0));
+        assert_eq!(comment_count(&db, &parent).await, Some(1), "a re-indexed reply counts once");
+    }
+
+    #[tokio::test]
+    async fn other_events_are_not_content() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db, mpsc::channel(1).1, "content-test".to_string());
+        let mut event = content_event(&unique_id("content"), None);
+        event.struct_name = "FollowEvent".to_string();
+        
+        assert!(!handler.process_event(&event).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn repeated_interactions_count_once() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db.clone(), mpsc::channel(1).1, "content-test".to_string());
+        let content_id = unique_id("content");
+        let profile_id = unique_id("profile");
+        
+        handler.process_event(&content_event(&content_id, None)).await.unwrap();
+        handler.process_event(&interaction_event(&content_id, &profile_id, "like")).await.unwrap();
+        handler.process_event(&interaction_event(&content_id, &profile_id, "like")).await.unwrap();
+        handler.process_event(&interaction_event(&content_id, &profile_id, "share")).await.unwrap();
+        
+        assert_eq!(counters(&db, &content_id).await, (1, 0, 1));
+    }
+
+    #[tokio::test]
+    async fn interactions_before_their_content_are_counted_when_it_lands() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db.clone(), mpsc::channel(1).1, "content-test".to_string());
+        let content_id = unique_id("content");
+        
+        // Neither interaction fails although the content is unknown
+        handler.process_event(&interaction_event(&content_id, &unique_id("profile"), "like")).await.unwrap();
+        handler.process_event(&interaction_event(&content_id, &unique_id("profile"), "view")).await.unwrap();
+        handler.process_event(&content_event(&content_id, None)).await.unwrap();
+        
+        assert_eq!(counters(&db, &content_id).await, (1, 1, 0));
+    }
+
+    #[tokio::test]
+    async fn unlike_decrements_like_count_once() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db.clone(), mpsc::channel(1).1, "content-test".to_string());
+        let content_id = unique_id("content");
+        let profile_id = unique_id("profile");
+        
+        handler.process_event(&content_event(&content_id, None)).await.unwrap();
+        handler.process_event(&interaction_event(&content_id, &profile_id, "like")).await.unwrap();
+        handler.process_event(&interaction_event(&content_id, &unique_id("profile"), "like")).await.unwrap();
+        handler.process_event(&removal_event(&content_id, &profile_id, "like")).await.unwrap();
+        assert_eq!(counters(&db, &content_id).await, (1, 0, 0));
+        
+        // A repeated unlike has no interaction left to remove
+        handler.process_event(&removal_event(&content_id, &profile_id, "like")).await.unwrap();
+        assert_eq!(counters(&db, &content_id).await, (1, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn a_profile_active_several_times_in_a_day_counts_once() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db.clone(), mpsc::channel(1).1, "content-test".to_string());
+        let profile_id = unique_id("profile");
+        let first = unique_id("content");
+        let second = unique_id("content");
+        
+        handler.process_event(&content_event(&first, None)).await.unwrap();
+        handler.process_event(&content_event(&second, None)).await.unwrap();
+        handler.process_event(&interaction_event(&first, &profile_id, "like")).await.unwrap();
+        handler.process_event(&interaction_event(&first, &profile_id, "view")).await.unwrap();
+        handler.process_event(&interaction_event(&second, &profile_id, "share")).await.unwrap();
+        
+        assert_eq!(active_days(&db, "0xplatform", &profile_id).await, 1);
+    }
+
+    #[tokio::test]
+    async fn interactors_before_the_content_become_active_on_its_platform() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db.clone(), mpsc::channel(1).1, "content-test".to_string());
+        let profile_id = unique_id("profile");
+        let content_id = unique_id("content");
+        
+        handler.process_event(&interaction_event(&content_id, &profile_id, "like")).await.unwrap();
+        assert_eq!(active_days(&db, "0xplatform", &profile_id).await, 0);
+        
+        handler.process_event(&content_event(&content_id, None)).await.unwrap();
+        assert_eq!(active_days(&db, "0xplatform", &profile_id).await, 1);
+    }
+
+    #[tokio::test]
+    async fn ip_registered_before_its_content_flags_the_content() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db.clone(), mpsc::channel(1).1, "content-test".to_string());
+        let content_id = unique_id("content");
+        let registration = blockchain_event("IPRegisteredEvent", serde_json::json!({ "ip_id": content_id }));
+        
+        handler.process_event(&registration).await.unwrap();
+        handler.process_event(&content_event(&content_id, None)).await.unwrap();
+        
+        assert!(has_ip_registered(&db, &content_id).await);
+    }
+
+    #[tokio::test]
+    async fn ip_registered_after_its_content_flags_the_content() {
+        let Some(db) = test_database().await else { return };
+        let handler = ContentEventHandler::new(db.clone(), mpsc::channel(1).1, "content-test".to_string());
+        let content_id = unique_id("content");
+        
+        handler.process_event(&content_event(&content_id, None)).await.unwrap();
+        assert!(!has_ip_registered(&db, &content_id).await);
+        
+        let registration = blockchain_event("IPRegisteredEvent", serde_json::json!({ "ip_id": content_id }));
+        handler.process_event(&registration).await.unwrap();
+        assert!(has_ip_registered(&db, &content_id).await);
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use chrono::NaiveDate;
+use diesel::sql_types::Date;
+use diesel_async::RunQueryDsl;
+
+/// Rows written by `recompute_daily_statistics`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecomputedStatistics {
+    pub daily_rows: usize,
+    pub platform_rows: usize,
+}
+
+/// Rebuild `daily_statistics` and `platform_daily_statistics` for `from..=to` from the source tables
+///
+/// Every row in the range is deleted and rewritten in one transaction, so stats damaged by a
+/// counting bug are replaced rather than adjusted. Each day in the range gets a
+/// `daily_statistics` row, even with nothing in it; platforms only get rows for days they had
+/// activity. A profile is active on a day it created content, interacted with content or
+/// followed someone.
+pub async fn recompute_daily_statistics(
+    conn: &mut diesel_async::AsyncPgConnection,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<RecomputedStatistics, diesel::result::Error> {
+    conn.build_transaction()
+        .run(|conn| Box::pin(async move {
+            diesel::sql_query("DELETE FROM daily_statistics WHERE date BETWEEN $1 AND $2")
+                .bind::<Date, _>(from)
+                .bind::<Date, _>(to)
+                .execute(conn)
+                .await?;
+
+            let daily_rows = diesel::sql_query(
+                "INSERT INTO daily_statistics (
+                     date, new_profiles_count, active_profiles_count, new_content_count,
+                     total_interactions_count, new_follows_count, new_ip_registrations_count, updated_at
+                 )
+                 SELECT d.date,
+                        (SELECT COUNT(*) FROM profiles
+                         WHERE created_at >= d.date AND created_at < d.date + 1),
+                        (SELECT COUNT(*) FROM (
+                             SELECT creator_id FROM content
+                             WHERE created_at >= d.date AND created_at < d.date + 1
+                             UNION SELECT profile_id FROM content_interactions
+                             WHERE created_at >= d.date AND created_at < d.date + 1
+                             UNION SELECT follower_address FROM social_graph_relationships
+                             WHERE created_at >= d.date AND created_at < d.date + 1
+                         ) active),
+                        (SELECT COUNT(*) FROM content
+                         WHERE created_at >= d.date AND created_at < d.date + 1),
+                        (SELECT COUNT(*) FROM content_interactions
+                         WHERE created_at >= d.date AND created_at < d.date + 1),
+                        (SELECT COUNT(*) FROM social_graph_relationships
+                         WHERE created_at >= d.date AND created_at < d.date + 1),
+                        (SELECT COUNT(*) FROM ip_registrations
+                         WHERE registered_at >= d.date AND registered_at < d.date + 1),
+                        NOW()
+                 FROM (SELECT generate_series($1::date, $2::date, INTERVAL '1 day')::date AS date) d"
+            )
+            .bind::<Date, _>(from)
+            .bind::<Date, _>(to)
+            .execute(conn)
+            .await?;
+
+            diesel::sql_query("DELETE FROM platform_daily_statistics WHERE date BETWEEN $1 AND $2")
+                .bind::<Date, _>(from)
+                .bind::<Date, _>(to)
+                .execute(conn)
+                .await?;
+
+            let platform_rows = diesel::sql_query(
+                "WITH joined AS (
+                     SELECT platform_id, joined_at::date AS date, COUNT(*) AS n FROM platform_memberships
+                     WHERE joined_at >= $1 AND joined_at < $2::date + 1
+                     GROUP BY 1, 2
+                 ),
+                 active AS (
+                     SELECT platform_id, date, COUNT(*) AS n FROM platform_daily_active_profiles
+                     WHERE date BETWEEN $1 AND $2
+                     GROUP BY 1, 2
+                 ),
+                 created AS (
+                     SELECT platform_id, created_at::date AS date, COUNT(*) AS n FROM content
+                     WHERE created_at >= $1 AND created_at < $2::date + 1
+                     GROUP BY 1, 2
+                 ),
+                 interactions AS (
+                     SELECT c.platform_id, i.created_at::date AS date, COUNT(*) AS n
+                     FROM content_interactions i
+                     JOIN content c ON c.id = i.content_id
+                     WHERE i.created_at >= $1 AND i.created_at < $2::date + 1
+                     GROUP BY 1, 2
+                 ),
+                 days AS (
+                     SELECT platform_id, date FROM joined
+                     UNION SELECT platform_id, date FROM active
+                     UNION SELECT platform_id, date FROM created
+                     UNION SELECT platform_id, date FROM interactions
+                 )
+                 INSERT INTO platform_daily_statistics (
+                     platform_id, date, new_users_count, active_users_count,
+                     content_created_count, total_interactions_count, updated_at
+                 )
+                 SELECT days.platform_id, days.date,
+                        COALESCE(joined.n, 0), COALESCE(active.n, 0),
+                        COALESCE(created.n, 0), COALESCE(interactions.n, 0), NOW()
+                 FROM days
+                 LEFT JOIN joined USING (platform_id, date)
+                 LEFT JOIN active USING (platform_id, date)
+                 LEFT JOIN created USING (platform_id, date)
+                 LEFT JOIN interactions USING (platform_id, date)"
+            )
+            .bind::<Date, _>(from)
+            .bind::<Date, _>(to)
+            .execute(conn)
+            .await?;
+
+            Ok(RecomputedStatistics { daily_rows, platform_rows })
+        }))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use diesel::prelude::*;
+    use crate::db::test_support::{insert_profile, test_database, unique_id};
+    use crate::schema::{content, content_interactions, daily_statistics, platform_daily_statistics, platform_memberships, profiles};
+
+    /// A day far from the ones other tests write to
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2001, 2, 3).unwrap()
+    }
+
+    fn at(hour: u32) -> NaiveDateTime {
+        day().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn corrupted_rows_are_rebuilt_from_source_data() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let mut conn = pool.get().await.unwrap();
+        let (platform, creator, viewer) = (unique_id("platform"), unique_id("creator"), unique_id("viewer"));
+        let content_id = unique_id("content");
+
+        insert_profile(&mut conn, &creator, &unique_id("user")).await;
+        diesel::update(profiles::table.filter(profiles::profile_id.eq(&creator)))
+            .set(profiles::created_at.eq(at(9)))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        diesel::insert_into(platform_memberships::table)
+            .values((
+                platform_memberships::platform_id.eq(&platform),
+                platform_memberships::profile_id.eq(&creator),
+                platform_memberships::joined_at.eq(at(10)),
+            ))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        diesel::insert_into(content::table)
+            .values((
+                content::id.eq(&content_id),
+                content::creator_id.eq(&creator),
+                content::platform_id.eq(&platform),
+                content::like_count.eq(0),
+                content::view_count.eq(0),
+                content::share_count.eq(0),
+                content::comment_count.eq(0),
+                content::has_ip_registered.eq(false),
+                content::created_at.eq(at(11)),
+            ))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        for interaction_type in ["like", "view"] {
+            diesel::insert_into(content_interactions::table)
+                .values((
+                    content_interactions::content_id.eq(&content_id),
+                    content_interactions::profile_id.eq(&viewer),
+                    content_interactions::interaction_type.eq(interaction_type),
+                    content_interactions::created_at.eq(at(12)),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+
+        // Rows left behind by a counting bug
+        diesel::insert_into(platform_daily_statistics::table)
+            .values((
+                platform_daily_statistics::platform_id.eq(&platform),
+                platform_daily_statistics::date.eq(day()),
+                platform_daily_statistics::new_users_count.eq(40),
+                platform_daily_statistics::content_created_count.eq(-3),
+                platform_daily_statistics::total_interactions_count.eq(0),
+                platform_daily_statistics::updated_at.eq(at(0)),
+            ))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        diesel::insert_into(daily_statistics::table)
+            .values((
+                daily_statistics::date.eq(day()),
+                daily_statistics::new_profiles_count.eq(-1),
+                daily_statistics::updated_at.eq(at(0)),
+            ))
+            .on_conflict(daily_statistics::date)
+            .do_update()
+            .set(daily_statistics::new_profiles_count.eq(-1))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let next_day = day().succ_opt().unwrap();
+        let written = recompute_daily_statistics(&mut conn, day(), next_day).await.unwrap();
+        assert_eq!(written.daily_rows, 2);
+        assert!(written.platform_rows >= 1);
+
+        let platform_row: (i64, i64, i64, i64) = platform_daily_statistics::table
+            .find((platform.as_str(), day()))
+            .select((
+                platform_daily_statistics::new_users_count,
+                platform_daily_statistics::active_users_count,
+                platform_daily_statistics::content_created_count,
+                platform_daily_statistics::total_interactions_count,
+            ))
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(platform_row, (1, 0, 1, 2));
+
+        // Other runs may have left profiles on the same day, so compare with the source table
+        let profiles_that_day: i64 = profiles::table
+            .filter(profiles::created_at.ge(at(0)))
+            .filter(profiles::created_at.lt(next_day.and_hms_opt(0, 0, 0).unwrap()))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        let new_profiles: i64 = daily_statistics::table
+            .find(day())
+            .select(daily_statistics::new_profiles_count)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert!(profiles_that_day >= 1);
+        assert_eq!(new_profiles, profiles_that_day);
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel_async::RunQueryDsl;
+use tracing::error;
+
+use crate::db::Database;
+use crate::models::dead_letter::NewDeadLetterEvent;
+use crate::schema::dead_letter_events;
+
+use super::listener::BlockchainEvent;
+
+/// Record an event that a handler failed to process
+///
+/// A repeated failure of the same event on the same worker bumps `retry_count`.
+pub(crate) async fn record_dead_letter(
+    db: &Database,
+    worker_id: &str,
+    event: &BlockchainEvent,
+    failure: &anyhow::Error,
+) {
+    let mut conn = match db.get_connection().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Failed to get connection to record dead letter {}: {}", event.event_id, e);
+            return;
+        }
+    };
+    
+    let now = Utc::now().naive_utc();
+    let dead_letter = NewDeadLetterEvent {
+        event_id: Some(event.event_id.clone()),
+        event_type: event.event_type.clone(),
+        worker_id: worker_id.to_string(),
+        event_data: event.data.clone(),
+        error_message: failure.to_string(),
+        created_at: now,
+        updated_at: now,
+    };
+    
+    let result = diesel::insert_into(dead_letter_events::table)
+        .values(&dead_letter)
+        .on_conflict((dead_letter_events::event_id, dead_letter_events::worker_id))
+        .do_update()
+        .set((
+            dead_letter_events::error_message.eq(excluded(dead_letter_events::error_message)),
+            dead_letter_events::retry_count.eq(dead_letter_events::retry_count + 1),
+            dead_letter_events::updated_at.eq(excluded(dead_letter_events::updated_at)),
+        ))
+        .execute(&mut conn)
+        .await;
+        
+    if let Err(e) = result {
+        error!("Failed to record dead letter {}: {}", event.event_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{test_database, unique_id};
+
+    #[tokio::test]
+    async fn a_repeated_failure_bumps_the_retry_count() {
+        let Some(db) = test_database().await else { return };
+        let event = BlockchainEvent {
+            tx_digest: "tx".to_string(),
+            event_id: unique_id("tx"),
+            event_type: "0x1::social_graph::FollowEvent".to_string(),
+            package_address: "0x1".to_string(),
+            module_name: "social_graph".to_string(),
+            struct_name: "FollowEvent".to_string(),
+            sender: "0x2".to_string(),
+            data: serde_json::json!({}),
+            timestamp_ms: 1_700_000_000_000,
+        };
+        
+        record_dead_letter(&db, "social-graph", &event, &anyhow::anyhow!("first")).await;
+        record_dead_letter(&db, "social-graph", &event, &anyhow::anyhow!("second")).await;
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let rows: Vec<(String, i32)> = dead_letter_events::table
+            .filter(dead_letter_events::event_id.eq(&event.event_id))
+            .select((dead_letter_events::error_message, dead_letter_events::retry_count))
+            .load(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![("second".to_string(), 1)]);
+    }
+}
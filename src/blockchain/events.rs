@@ -10,40 +10,113 @@ use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 use crate::db::{Database, DbConnection};
-use crate::events::profile_events::ProfileCreatedEvent;
+use crate::events::profile_events::{ProfileCreatedEvent, ProfileUpdatedEvent, ProfileVerifiedEvent};
+use crate::events::profile_event_types::{PlatformJoinedEvent, PlatformLeftEvent, ProfileEventType};
+use crate::models::profile::ProfileFieldLimits;
+use crate::models::profile_events::NewProfileEvent;
 use crate::events::blocking_events;
 use crate::models::indexer::NewIndexerProgress;
 use crate::schema;
 
+use super::dead_letter::record_dead_letter;
+use super::ignored::skip_ignored;
+use crate::events::sentinel::skip_sentinel;
 use super::listener::BlockchainEvent;
+use super::publisher::{publish_applied, EventPublisher, NoopPublisher};
+use super::store::{DieselProfileStore, ProfileStore};
 
 /// Listener for profile events
-pub struct ProfileEventListener {
-    /// Database connection
-    db: Arc<Database>,
+///
+/// Profile writes go through `S`; the remaining events still use the database directly.
+pub struct ProfileEventListener<S: ProfileStore = DieselProfileStore> {
+    /// Database connection, absent for a listener built on a store alone
+    db: Option<Arc<Database>>,
     /// Event receiver channel
     rx: mpsc::Receiver<BlockchainEvent>,
     /// Worker ID for tracking progress
     worker_id: String,
+    /// Profile writes made by the handlers that have moved off direct connections
+    store: Arc<S>,
+    /// Keep the raw event on the profile row it wrote
+    store_source_events: bool,
+    /// Maximum lengths of the free-text fields written to profiles
+    field_limits: ProfileFieldLimits,
+    /// Publisher for profile events this listener has applied
+    publisher: Arc<dyn EventPublisher>,
 }
 
 impl ProfileEventListener {
     /// Create a new profile event listener
     pub fn new(db: Arc<Database>, rx: mpsc::Receiver<BlockchainEvent>, worker_id: String) -> Self {
+        let mut listener = Self::from_store(Arc::new(DieselProfileStore::new(db.clone())), rx, worker_id);
+        listener.db = Some(db);
+        listener
+    }
+}
+
+impl<S: ProfileStore> ProfileEventListener<S> {
+    /// Create a listener that writes profiles only through `store`, e.g. `MemoryProfileStore`
+    ///
+    /// Events that need a database connection fail and can't be dead-lettered.
+    pub fn from_store(store: Arc<S>, rx: mpsc::Receiver<BlockchainEvent>, worker_id: String) -> Self {
         Self {
-            db,
+            db: None,
             rx,
             worker_id,
+            store,
+            store_source_events: false,
+            field_limits: ProfileFieldLimits::default(),
+            publisher: Arc::new(NoopPublisher),
         }
     }
     
+    /// Keep the raw event that last wrote each profile in `profiles.source_event`
+    pub fn with_source_events(mut self, enabled: bool) -> Self {
+        self.store_source_events = enabled;
+        self
+    }
+    
+    /// Cap profile text fields at these lengths instead of the defaults
+    pub fn with_field_limits(mut self, limits: ProfileFieldLimits) -> Self {
+        self.field_limits = limits;
+        self
+    }
+    
+    /// Use a different profile store, keeping the database for the other events
+    pub fn with_store<T: ProfileStore>(self, store: Arc<T>) -> ProfileEventListener<T> {
+        ProfileEventListener {
+            db: self.db,
+            rx: self.rx,
+            worker_id: self.worker_id,
+            store,
+            store_source_events: self.store_source_events,
+            field_limits: self.field_limits,
+            publisher: self.publisher,
+        }
+    }
+    
+    /// Publish the profile events this listener applies
+    pub fn with_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = publisher;
+        self
+    }
+    
     /// Get a database connection from the pool
     async fn get_connection(&self) -> Result<DbConnection> {
-        self.db.get_connection()
+        let db = self.db.as_ref().ok_or_else(|| anyhow!("Profile listener has no database"))?;
+        db.get_connection()
             .await
             .map_err(|e| anyhow!("Failed to get database connection: {}", e))
     }
     
+    /// Record a failed event for later replay, when the listener has a database
+    async fn dead_letter(&self, event: &BlockchainEvent, err: &anyhow::Error) {
+        match &self.db {
+            Some(db) => record_dead_letter(db, &self.worker_id, event, err).await,
+            None => warn!("No database to dead-letter event {}", event.event_id),
+        }
+    }
+    
     /// Update worker progress with timestamp
     async fn update_progress(&self, timestamp: u64) -> Result<()> {
         let mut conn = self.get_connection().await?;
@@ -93,7 +166,11 @@ impl ProfileEventListener {
     }
     
     /// Try to manually parse a profile event from the raw JSON data
-    async fn try_manual_profile_parse(&self, data: &serde_json::Value) -> Result<()> {
+    ///
+    /// Returns false when a required field is a placeholder or the extracted owner is on the
+    /// ignore list.
+    async fn try_manual_profile_parse(&self, event: &BlockchainEvent) -> Result<bool> {
+        let data = &event.data;
         info!("Manually extracting profile data from: {}", serde_json::to_string_pretty(data).unwrap_or_default());
         
         // Try different JSON paths to extract the profile data
@@ -206,74 +283,263 @@ impl ProfileEventListener {
                 bio,
                 profile_photo,
                 cover_photo,
+                // The photo URLs above decide the flags
+                has_profile_photo: None,
+                has_cover_photo: None,
                 created_at: chrono::Utc::now().timestamp() as u64,
             }
         } else {
             return Err(anyhow!("Data is not an object"));
         };
         
+        // The manual parser fills in "unknown" for anything it can't find
+        if skip_sentinel("profile", "ProfileCreatedEvent", &[
+            ("profile_id", &profile.profile_id),
+            ("owner_address", &profile.owner_address),
+            ("username", profile.username.as_deref().unwrap_or_default()),
+        ]) {
+            return Ok(false);
+        }
+        
+        // The owner is only known now, so the ignore list is checked again
+        if skip_ignored("profile", event, &[&profile.owner_address]) {
+            return Ok(false);
+        }
+        
         // Process the manually constructed profile
         info!("Manually parsed profile: {:?}", profile);
-        self.process_profile_created(&profile).await
+        self.process_profile_created(&profile, data).await?;
+        Ok(true)
     }
     
     /// Process a profile created event
-    async fn process_profile_created(&self, event: &ProfileCreatedEvent) -> Result<()> {
-        let mut conn = self.get_connection().await?;
+    ///
+    /// `source_event` is the raw event data, kept on the row when source events are stored.
+    pub async fn process_profile_created(&self, event: &ProfileCreatedEvent, source_event: &serde_json::Value) -> Result<()> {
+        // Don't store rows keyed on a placeholder the parsers filled in. A missing username gets
+        // a generated one, but a placeholder would collide with the next event that has it.
+        let mut required = vec![("profile_id", event.profile_id.as_str()), ("owner_address", event.owner_address.as_str())];
+        if let Some(username) = &event.username {
+            required.push(("username", username.as_str()));
+        }
+        if skip_sentinel("profile", "ProfileCreatedEvent", &required) {
+            return Ok(());
+        }
         
         // Convert event to database model
-        let new_profile = event.into_model()?;
+        let mut new_profile = event.into_model()?;
+        new_profile.sanitize(&self.field_limits);
+        if self.store_source_events {
+            new_profile.source_event = Some(source_event.clone());
+        }
         
-        // Insert the profile
-        diesel::insert_into(schema::profiles::table)
-            .values(&new_profile)
-            .on_conflict(schema::profiles::username)
-            .do_update()
-            .set((
-                schema::profiles::owner_address.eq(&new_profile.owner_address),
-                schema::profiles::display_name.eq(&new_profile.display_name),
-                schema::profiles::bio.eq(&new_profile.bio),
-                schema::profiles::profile_photo.eq(&new_profile.profile_photo),
-                schema::profiles::website.eq(&new_profile.website),
-                schema::profiles::updated_at.eq(&new_profile.updated_at),
-                schema::profiles::cover_photo.eq(&new_profile.cover_photo),
-                schema::profiles::profile_id.eq(&new_profile.profile_id),
-                schema::profiles::sensitive_data_updated_at.eq(&new_profile.sensitive_data_updated_at),
-                // Sensitive fields
-                schema::profiles::birthdate.eq(&new_profile.birthdate),
-                schema::profiles::current_location.eq(&new_profile.current_location),
-                schema::profiles::raised_location.eq(&new_profile.raised_location),
-                schema::profiles::phone.eq(&new_profile.phone),
-                schema::profiles::email.eq(&new_profile.email),
-                schema::profiles::gender.eq(&new_profile.gender),
-                schema::profiles::political_view.eq(&new_profile.political_view),
-                schema::profiles::religion.eq(&new_profile.religion),
-                schema::profiles::education.eq(&new_profile.education),
-                schema::profiles::primary_language.eq(&new_profile.primary_language),
-                schema::profiles::relationship_status.eq(&new_profile.relationship_status),
-                schema::profiles::x_username.eq(&new_profile.x_username),
-                schema::profiles::mastodon_username.eq(&new_profile.mastodon_username),
-                schema::profiles::facebook_username.eq(&new_profile.facebook_username),
-                schema::profiles::reddit_username.eq(&new_profile.reddit_username),
-                schema::profiles::github_username.eq(&new_profile.github_username)
-            ))
-            .execute(&mut conn)
-            .await?;
+        // Insert the profile, or update it if this profile_id was seen before
+        self.store.upsert_profile(&new_profile).await?;
             
         info!("Processed profile created: {}", event.profile_id);
+        
+        // Follows that arrived before this profile existed were only recorded as events
+        if !event.profile_id.is_empty() {
+            match self.store.resolve_pending_relationships(&event.profile_id).await {
+                Ok(resolved) if resolved > 0 => {
+                    info!("Resolved {} pending follow relationships on profile creation", resolved);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Failed to resolve pending relationships for {}: {}", event.profile_id, e);
+                }
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Process a profile updated event
+    ///
+    /// An update can be indexed before its profile, so an unknown profile is created from the
+    /// update instead of the update being dropped. `source_event` is kept as for creation.
+    pub async fn process_profile_updated(&self, event: &ProfileUpdatedEvent, source_event: &serde_json::Value) -> Result<()> {
+        if skip_sentinel("profile", "ProfileUpdatedEvent", &[("profile_id", &event.profile_id)]) {
+            return Ok(());
+        }
+        
+        let mut update = event.into_update_model();
+        update.sanitize(&self.field_limits);
+        if self.store_source_events {
+            update.source_event = Some(source_event.clone());
+        }
+        if self.store.update_profile(&event.profile_id, &update).await? {
+            info!("Processed profile updated: {}", event.profile_id);
+            return Ok(());
+        }
+        
+        let mut stub = event.into_stub_model();
+        stub.sanitize(&self.field_limits);
+        stub.source_event = update.source_event;
+        self.store.upsert_profile(&stub).await?;
+        info!("Created missing profile from update event: {}", event.profile_id);
+        
+        Ok(())
+    }
+
+    /// Process a profile verification event - sets or clears the verified flag
+    async fn process_profile_verified(&self, event: &ProfileVerifiedEvent, blockchain_event: &BlockchainEvent) -> Result<()> {
+        let mut conn = self.get_connection().await?;
+        
+        // Prefer the event's own time, falling back to the checkpoint time
+        let verified_at = match event.timestamp {
+            Some(ts) => chrono::DateTime::from_timestamp(ts as i64, 0),
+            None => chrono::DateTime::from_timestamp_millis(blockchain_event.timestamp_ms as i64),
+        }
+        .unwrap_or_else(|| Utc::now())
+        .naive_utc();
+        
+        let profile_event = NewProfileEvent::from_blockchain_event(
+            ProfileEventType::ProfileVerified,
+            event.profile_id.clone(),
+            serde_json::to_value(event).unwrap_or_default(),
+            Some(blockchain_event.event_id.clone()),
+            event.timestamp,
+        );
+        
+        let verified = event.verified;
+        let verified_by = event.verified_by.clone();
+        let profile_id = event.profile_id.clone();
+        
+        let updated = conn.build_transaction()
+            .run(|mut conn| Box::pin(async move {
+                // Record the event for profile history
+                crate::db::insert_profile_event(&mut conn, &profile_event).await?;
+                
+                let updated = diesel::update(schema::profiles::table
+                    .filter(schema::profiles::profile_id.eq(&profile_id)))
+                    .set((
+                        schema::profiles::is_verified.eq(verified),
+                        schema::profiles::verified_at.eq(if verified { Some(verified_at) } else { None }),
+                        schema::profiles::verified_by.eq(if verified { verified_by } else { None }),
+                        schema::profiles::updated_at.eq(Utc::now().naive_utc()),
+                    ))
+                    .execute(&mut conn)
+                    .await?;
+                
+                Result::<_, diesel::result::Error>::Ok(updated)
+            }))
+            .await?;
+        
+        if updated == 0 {
+            warn!("Verification event for unknown profile: {}", event.profile_id);
+        } else {
+            info!("Processed profile verification: {} verified={}", event.profile_id, event.verified);
+        }
+        
+        Ok(())
+    }
+
+    /// Record a profile-module `PlatformJoinedEvent`/`PlatformLeftEvent` in the profile's history
+    async fn process_profile_platform_event(&self, event: &BlockchainEvent) -> Result<()> {
+        let event_id = Some(event.event_id.clone());
+        // History timestamps are in seconds; fall back to the checkpoint time
+        let fallback_timestamp = event.timestamp_ms / 1000;
+        
+        let profile_event = if event.event_type.ends_with("::PlatformJoinedEvent") {
+            let mut joined = crate::events::parse_event::<PlatformJoinedEvent>(&event.data)?;
+            if joined.timestamp == 0 {
+                joined.timestamp = fallback_timestamp;
+            }
+            NewProfileEvent::from_platform_joined(&joined, event_id)
+        } else {
+            let mut left = crate::events::parse_event::<PlatformLeftEvent>(&event.data)?;
+            if left.timestamp == 0 {
+                left.timestamp = fallback_timestamp;
+            }
+            NewProfileEvent::from_platform_left(&left, event_id)
+        };
+        
+        let mut conn = self.get_connection().await?;
+        let inserted = crate::db::insert_profile_event(&mut conn, &profile_event).await?;
+        if inserted > 0 {
+            info!("Recorded {} for profile {}", profile_event.event_type, profile_event.profile_id);
+        } else {
+            debug!("{} {:?} already recorded", profile_event.event_type, profile_event.event_id);
+        }
+        
         Ok(())
     }
 
     /// Process platform block event
-    async fn process_platform_block_event(&self, event_data: &serde_json::Value) -> Result<()> {
+    async fn process_platform_block_event(&self, event_data: &serde_json::Value, event_id: &str) -> Result<()> {
         let mut conn = self.get_connection().await?;
-        blocking_events::process_platform_block_event(&mut conn, event_data).await
+        blocking_events::process_platform_block_event(&mut conn, event_data, Some(event_id)).await
     }
 
     /// Process platform unblock event
-    async fn process_platform_unblock_event(&self, event_data: &serde_json::Value) -> Result<()> {
+    async fn process_platform_unblock_event(&self, event_data: &serde_json::Value, event_id: &str) -> Result<()> {
         let mut conn = self.get_connection().await?;
-        blocking_events::process_platform_unblock_event(&mut conn, event_data).await
+        blocking_events::process_platform_unblock_event(&mut conn, event_data, Some(event_id)).await
+    }
+
+    /// Apply one profile-module event, returning false when it was skipped
+    ///
+    /// Failures come back as errors for the caller to dead-letter; only applied events are
+    /// published.
+    async fn handle_profile_event(&self, event: &BlockchainEvent) -> Result<bool> {
+        // Skip spam and test accounts
+        if skip_ignored("profile", event, &[]) {
+            return Ok(false);
+        }
+        
+        if event.event_type.ends_with("::ProfileCreatedEvent") {
+            // Log the raw event data for debugging
+            info!("Profile event detected with data: {}", serde_json::to_string_pretty(&event.data).unwrap_or_default());
+            
+            let profile_event = match crate::events::parse_event::<ProfileCreatedEvent>(&event.data) {
+                Ok(profile_event) => profile_event,
+                Err(e) => {
+                    error!("Failed to deserialize profile created event: {}", e);
+                    
+                    // Try to parse the profile event struct manually
+                    info!("Attempting manual profile event parsing...");
+                    return self.try_manual_profile_parse(event)
+                        .await
+                        .map_err(|parse_err| parse_err.context("Manual profile parsing also failed"));
+                }
+            };
+            if skip_ignored("profile", event, &[&profile_event.owner_address]) {
+                return Ok(false);
+            }
+            
+            info!("Successfully parsed profile event: {:?}", profile_event);
+            self.process_profile_created(&profile_event, &event.data)
+                .await
+                .map_err(|e| e.context("Failed to process profile created event"))?;
+        } else if event.event_type.ends_with("::ProfileUpdatedEvent") {
+            let updated_event = crate::events::parse_event::<ProfileUpdatedEvent>(&event.data)
+                .map_err(|e| e.context("Failed to deserialize profile updated event"))?;
+            if skip_ignored("profile", event, &[&updated_event.owner_address]) {
+                return Ok(false);
+            }
+            
+            self.process_profile_updated(&updated_event, &event.data)
+                .await
+                .map_err(|e| e.context("Failed to process profile updated event"))?;
+        } else if event.event_type.ends_with("::ProfileVerifiedEvent") {
+            let verified_event = crate::events::parse_event::<ProfileVerifiedEvent>(&event.data)
+                .map_err(|e| e.context("Failed to deserialize profile verified event"))?;
+            self.process_profile_verified(&verified_event, event)
+                .await
+                .map_err(|e| e.context("Failed to process profile verified event"))?;
+        } else if event.event_type.ends_with("::PlatformJoinedEvent")
+            || event.event_type.ends_with("::PlatformLeftEvent")
+        {
+            // Membership changes emitted by the profile module are only recorded in history;
+            // the platform module's join/leave events maintain platform_memberships
+            self.process_profile_platform_event(event)
+                .await
+                .map_err(|e| e.context("Failed to process profile platform membership event"))?;
+        }
+        // Add other profile event types as needed
+        
+        Ok(true)
     }
 
     /// Start listening for profile events
@@ -286,42 +552,28 @@ impl ProfileEventListener {
             // Check if this is a profile event
             if event.event_type.contains("::profile::") {
                 info!("Processing profile event: {}", event.event_type);
-                
-                // Handle profile created event
-                if event.event_type.ends_with("::ProfileCreatedEvent") {
-                    // Log the raw event data for debugging
-                    info!("Profile event detected with data: {}", serde_json::to_string_pretty(&event.data).unwrap_or_default());
-                    
-                    match crate::events::parse_event::<ProfileCreatedEvent>(&event.data) {
-                        Ok(profile_event) => {
-                            info!("Successfully parsed profile event: {:?}", profile_event);
-                            if let Err(e) = self.process_profile_created(&profile_event).await {
-                                error!("Failed to process profile created event: {}", e);
-                            }
-                        },
-                        Err(e) => {
-                            error!("Failed to deserialize profile created event: {}", e);
-                            
-                            // Try to parse the profile event struct manually
-                            info!("Attempting manual profile event parsing...");
-                            let manual_parse = self.try_manual_profile_parse(&event.data).await;
-                            if let Err(parse_err) = manual_parse {
-                                error!("Manual parsing also failed: {}", parse_err);
-                            }
-                        }
+                let applied = match self.handle_profile_event(&event).await {
+                    Ok(applied) => applied,
+                    Err(e) => {
+                        error!("Failed to process profile event {}: {:#}", event.event_id, e);
+                        self.dead_letter(&event, &e).await;
+                        false
                     }
-                }
-                // Add other profile event types as needed
+                };
                 
                 // Update progress after processing the event
                 if let Err(e) = self.update_progress(event.timestamp_ms).await {
                     error!("Failed to update progress: {}", e);
                 }
+                
+                if applied {
+                    publish_applied(self.publisher.as_ref(), crate::PROFILE_MODULE_NAME, &event).await;
+                }
             }
             // Handle platform blocking events
             else if event.event_type.ends_with("::PlatformBlockedProfileEvent") {
                 info!("Processing platform block event: {}", event.event_type);
-                if let Err(e) = self.process_platform_block_event(&event.data).await {
+                if let Err(e) = self.process_platform_block_event(&event.data, &event.event_id).await {
                     error!("Failed to process platform block event: {}", e);
                 }
                 
@@ -333,7 +585,7 @@ impl ProfileEventListener {
             // Handle platform unblocking events
             else if event.event_type.ends_with("::PlatformUnblockedProfileEvent") {
                 info!("Processing platform unblock event: {}", event.event_type);
-                if let Err(e) = self.process_platform_unblock_event(&event.data).await {
+                if let Err(e) = self.process_platform_unblock_event(&event.data, &event.event_id).await {
                     error!("Failed to process platform unblock event: {}", e);
                 }
                 
@@ -342,251 +594,15 @@ impl ProfileEventListener {
                     error!("Failed to update progress: {}", e);
                 }
             }
-            // Handle profile blocking events from block_list module
+            // Handle profile blocking events; module_name decides between profile and platform blocks
             else if event.event_type.ends_with("::UserBlockEvent") {
-                info!("⚠️ DETECTED USER BLOCK EVENT: {}", event.event_type);
-                info!("⚠️ EVENT DATA: {}", serde_json::to_string_pretty(&event.data).unwrap_or_default());
-                
-                // Try to extract information from any possible structure
-                let blocker_value = if let Some(obj) = event.data.as_object() {
-                    // Try fields.blocker
-                    if let Some(fields) = obj.get("fields").and_then(|f| f.as_object()) {
-                        if let Some(blocker) = fields.get("blocker").and_then(|v| v.as_str()) {
-                            info!("📌 Found blocker in fields.blocker: {}", blocker);
-                            blocker
-                        } else if let Some(blocker) = obj.get("blocker").and_then(|v| v.as_str()) {
-                            info!("📌 Found blocker at root level: {}", blocker);
-                            blocker
-                        } else {
-                            info!("❌ Could not find blocker in standard locations");
-                            "unknown"
-                        }
-                    } else if let Some(blocker) = obj.get("blocker").and_then(|v| v.as_str()) {
-                        info!("📌 Found blocker at root level: {}", blocker);
-                        blocker
-                    } else {
-                        info!("❌ Could not find blocker in any location");
-                        "unknown"
-                    }
-                } else {
-                    info!("❌ Event data is not an object");
-                    "unknown"
-                };
-                
-                let blocked_value = if let Some(obj) = event.data.as_object() {
-                    // Try fields.blocked
-                    if let Some(fields) = obj.get("fields").and_then(|f| f.as_object()) {
-                        if let Some(blocked) = fields.get("blocked").and_then(|v| v.as_str()) {
-                            info!("📌 Found blocked in fields.blocked: {}", blocked);
-                            blocked
-                        } else if let Some(blocked) = obj.get("blocked").and_then(|v| v.as_str()) {
-                            info!("📌 Found blocked at root level: {}", blocked);
-                            blocked
-                        } else {
-                            info!("❌ Could not find blocked in standard locations");
-                            "unknown"
-                        }
-                    } else if let Some(blocked) = obj.get("blocked").and_then(|v| v.as_str()) {
-                        info!("📌 Found blocked at root level: {}", blocked);
-                        blocked
-                    } else {
-                        info!("❌ Could not find blocked in any location");
-                        "unknown"
-                    }
-                } else {
-                    info!("❌ Event data is not an object");
-                    "unknown"
-                };
-                
-                // Check for the new module_name field
-                let module_name = if let Some(obj) = event.data.as_object() {
-                    if let Some(fields) = obj.get("fields").and_then(|f| f.as_object()) {
-                        if let Some(module) = fields.get("module_name").and_then(|v| v.as_str()) {
-                            info!("📌 Found module_name in fields.module_name: {}", module);
-                            Some(module.to_string())
-                        } else {
-                            info!("❌ Could not find module_name in fields");
-                            None
-                        }
-                    } else {
-                        info!("❌ Could not find fields container");
-                        None
-                    }
-                } else {
-                    info!("❌ Event data is not an object");
-                    None
-                };
-                
-                info!("🔵 BLOCK EVENT SUMMARY: blocker={}, blocked={}, module={:?}", 
-                    blocker_value, blocked_value, module_name);
-                
-                info!("------------------------------------------------------------------------------");
-                info!("DETECTED BLOCK PROFILE EVENT: {}", event.event_type);
-                info!("------------------------------------------------------------------------------");
-                
+                info!("Processing block event: {}", event.event_type);
                 let mut conn = self.get_connection().await?;
                 
-                // Log extensive database connection info
-                info!("Verifying database connection and schema...");
-                
-                // Check database version
-                let db_verification = diesel::dsl::select(diesel::dsl::sql::<diesel::sql_types::Text>("version()"))
-                    .get_result::<String>(&mut conn)
-                    .await;
-                
-                match db_verification {
-                    Ok(ver) => info!("Connected to database: {}", ver),
-                    Err(e) => error!("Failed to verify database connection: {}", e),
-                }
-                
-                // Check if profiles_blocked table exists
-                let table_check = diesel::dsl::select(
-                    diesel::dsl::sql::<diesel::sql_types::Bool>(
-                        "EXISTS (SELECT 1 FROM information_schema.tables WHERE table_name = 'profiles_blocked')"
-                    )
-                )
-                .get_result::<bool>(&mut conn)
-                .await;
-                
-                match table_check {
-                    Ok(exists) => {
-                        if exists {
-                            info!("✅ 'profiles_blocked' table exists in the database");
-                            
-                            // Use a more compatible approach to check the table schema
-                            let col_count = diesel::dsl::select(
-                                diesel::dsl::sql::<diesel::sql_types::BigInt>(
-                                    "COUNT(*) FROM information_schema.columns WHERE table_name = 'profiles_blocked'"
-                                )
-                            )
-                            .get_result::<i64>(&mut conn)
-                            .await;
-                            
-                            match col_count {
-                                Ok(count) => {
-                                    info!("'profiles_blocked' table has {} columns", count);
-                                    
-                                    // Check if we have our required columns
-                                    let required_cols = diesel::dsl::select(
-                                        diesel::dsl::sql::<diesel::sql_types::Bool>(
-                                            "EXISTS (SELECT 1 FROM information_schema.columns 
-                                             WHERE table_name = 'profiles_blocked' 
-                                             AND column_name = 'blocker_wallet_address')"
-                                        )
-                                    )
-                                    .get_result::<bool>(&mut conn)
-                                    .await;
-                                    
-                                    match required_cols {
-                                        Ok(has_cols) => {
-                                            if has_cols {
-                                                info!("✅ Required columns exist in profiles_blocked table");
-                                            } else {
-                                                error!("❌ Required column 'blocker_wallet_address' not found in profiles_blocked table");
-                                            }
-                                        },
-                                        Err(e) => error!("Failed to check for required columns: {}", e),
-                                    }
-                                    
-                                    // Count existing records
-                                    let record_count = diesel::dsl::select(
-                                        diesel::dsl::sql::<diesel::sql_types::BigInt>(
-                                            "COUNT(*) FROM profiles_blocked"
-                                        )
-                                    )
-                                    .get_result::<i64>(&mut conn)
-                                    .await;
-                                    
-                                    match record_count {
-                                        Ok(count) => info!("Current records in profiles_blocked: {}", count),
-                                        Err(e) => error!("Failed to count records: {}", e),
-                                    }
-                                },
-                                Err(e) => error!("Failed to check table structure: {}", e),
-                            }
-                        } else {
-                            error!("❌ 'profiles_blocked' table DOES NOT exist in the database!");
-                            
-                            // List some available tables
-                            let table_count = diesel::dsl::select(
-                                diesel::dsl::sql::<diesel::sql_types::BigInt>(
-                                    "COUNT(*) FROM information_schema.tables WHERE table_schema = 'public'"
-                                )
-                            )
-                            .get_result::<i64>(&mut conn)
-                            .await;
-                            
-                            match table_count {
-                                Ok(count) => info!("Found {} tables in the database", count),
-                                Err(e) => error!("Failed to count tables: {}", e),
-                            }
-                        }
-                    },
-                    Err(e) => error!("Failed to check if table exists: {}", e),
-                }
-                
-                // Log the raw event data to debug JSON structure with full details
-                let pretty_json = serde_json::to_string_pretty(&event.data).unwrap_or_default();
-                info!("BLOCK EVENT RAW DATA:\n{}", pretty_json);
-                
-                // Try to process the event
-                match blocking_events::process_profile_block_event(&mut conn, &event.data).await {
-                    Ok(_) => {
-                        info!("✅ Successfully processed profile block event");
-                        
-                        // Verify the database entry was created
-                        use diesel::prelude::*;
-                        use diesel_async::RunQueryDsl;
-                        use crate::schema::profiles_blocked::dsl::*;
-                        
-                        // Extract blocker and blocked from the event data
-                        let blocker_value = if let Some(obj) = event.data.as_object() {
-                            if let Some(fields) = obj.get("fields").and_then(|f| f.as_object()) {
-                                fields.get("blocker").and_then(|v| v.as_str()).unwrap_or_default()
-                            } else {
-                                obj.get("blocker").and_then(|v| v.as_str()).unwrap_or_default()
-                            }
-                        } else {
-                            ""
-                        };
-                        
-                        let blocked_value = if let Some(obj) = event.data.as_object() {
-                            if let Some(fields) = obj.get("fields").and_then(|f| f.as_object()) {
-                                fields.get("blocked").and_then(|v| v.as_str()).unwrap_or_default()
-                            } else {
-                                obj.get("blocked").and_then(|v| v.as_str()).unwrap_or_default()
-                            }
-                        } else {
-                            ""
-                        };
-                        
-                        if !blocker_value.is_empty() && !blocked_value.is_empty() {
-                            let query = profiles_blocked
-                                .filter(blocker_wallet_address.eq(blocker_value))
-                                .filter(blocked_address.eq(blocked_value))
-                                .select(id);
-                                
-                            info!("Executing verification query for blocker={}, blocked={}", 
-                                blocker_value, blocked_value);
-                                
-                            // Use count to see if any records exist
-                            let count_result = query.count().get_result::<i64>(&mut conn).await;
-                            
-                            match count_result {
-                                Ok(count) => {
-                                    if count > 0 {
-                                        info!("✅ Verified database entry exists - found {} records", count);
-                                    } else {
-                                        error!("❌ No database entries found for this block relationship");
-                                    }
-                                },
-                                Err(e) => error!("❌ Failed to verify database entry: {}", e),
-                            }
-                        }
-                    },
-                    Err(e) => {
-                        error!("❌ Failed to process profile block event: {}", e);
-                    }
+                if let Err(e) = blocking_events::process_user_block_event(&mut conn, &event.data, &event.sender, Some(&event.event_id)).await {
+                    error!("Failed to process block event: {}", e);
+                } else {
+                    info!("Successfully processed block event");
                 }
                 
                 // Update progress after processing the event
@@ -599,10 +615,7 @@ impl ProfileEventListener {
                 info!("Processing profile unblock event: {}", event.event_type);
                 let mut conn = self.get_connection().await?;
                 
-                // Log the raw event data to debug JSON structure
-                info!("Raw unblock event data: {}", serde_json::to_string_pretty(&event.data).unwrap_or_default());
-                
-                if let Err(e) = blocking_events::process_profile_unblock_event(&mut conn, &event.data).await {
+                if let Err(e) = blocking_events::process_user_unblock_event(&mut conn, &event.data, &event.sender, Some(&event.event_id)).await {
                     error!("Failed to process profile unblock event: {}", e);
                 } else {
                     info!("Successfully processed profile unblock event");
@@ -631,4 +644,345 @@ impl ProfileEventListener {
         warn!("Profile event listener channel closed");
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{test_database, unique_id};
+
+    fn update_event(profile_id: &str, display_name: &str) -> ProfileUpdatedEvent {
+        serde_json::from_value(serde_json::json!({
+            "profile_id": profile_id,
+            "owner_address": format!("{}owner", profile_id),
+            "display_name": display_name,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn update_for_an_unknown_profile_creates_it() {
+        let Some(db) = test_database().await else { return };
+        let listener = ProfileEventListener::new(db.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        let profile_id = unique_id("profile");
+        
+        listener.process_profile_updated(&update_event(&profile_id, "First"), &serde_json::Value::Null).await.unwrap();
+        listener.process_profile_updated(&update_event(&profile_id, "Second"), &serde_json::Value::Null).await.unwrap();
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let display_names: Vec<Option<String>> = schema::profiles::table
+            .filter(schema::profiles::profile_id.eq(&profile_id))
+            .select(schema::profiles::display_name)
+            .load(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(display_names, vec![Some("Second".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn profile_created_through_the_memory_store() {
+        use crate::blockchain::store::MemoryProfileStore;
+        
+        let store = Arc::new(MemoryProfileStore::default());
+        store.pending_follows.lock().await.push(("0xfollower".to_string(), "0xnew".to_string()));
+        store.profiles.lock().await.insert(
+            "0xfollower".to_string(),
+            (1, update_event("0xfollower", "Follower").into_stub_model()),
+        );
+        let listener = ProfileEventListener::from_store(store.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        let created: ProfileCreatedEvent = serde_json::from_value(serde_json::json!({
+            "profile_id": "0xnew",
+            "owner_address": "0xowner",
+            "username": "newcomer",
+            "display_name": "New",
+        }))
+        .unwrap();
+        
+        listener.process_profile_created(&created, &serde_json::Value::Null).await.unwrap();
+        
+        let profiles = store.profiles.lock().await;
+        let (_, profile) = &profiles["0xnew"];
+        assert_eq!(profile.username, "newcomer");
+        assert_eq!(profile.owner_address, "0xowner");
+        assert!(profile.source_event.is_none());
+        // The follow waiting on this profile was materialized
+        assert_eq!(*store.relationships.lock().await, vec![("0xfollower".to_string(), "0xnew".to_string())]);
+        assert!(store.pending_follows.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stub_is_promoted_without_losing_an_early_follow() {
+        use crate::db::test_support::insert_profile;
+        
+        let Some(db) = test_database().await else { return };
+        let listener = ProfileEventListener::new(db.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        let (follower, profile_id) = (unique_id("profile"), unique_id("profile"));
+        {
+            // The follow arrived before the profile did, so it was only recorded as an event
+            let mut conn = db.get_connection().await.unwrap();
+            insert_profile(&mut conn, &follower, &unique_id("user")).await;
+            diesel::insert_into(schema::social_graph_events::table)
+                .values((
+                    schema::social_graph_events::event_type.eq("follow"),
+                    schema::social_graph_events::follower_address.eq(&follower),
+                    schema::social_graph_events::following_address.eq(&profile_id),
+                    schema::social_graph_events::created_at.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        // An early update leaves a stub row behind
+        listener.process_profile_updated(&update_event(&profile_id, "Early"), &serde_json::Value::Null).await.unwrap();
+        let created: ProfileCreatedEvent = serde_json::from_value(serde_json::json!({
+            "profile_id": profile_id,
+            "owner_address": format!("{}owner", profile_id),
+            "username": unique_id("user"),
+        }))
+        .unwrap();
+        
+        listener.process_profile_created(&created, &serde_json::Value::Null).await.unwrap();
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let rows: Vec<(String, i32)> = schema::profiles::table
+            .filter(schema::profiles::profile_id.eq(&profile_id))
+            .select((schema::profiles::username, schema::profiles::followers_count))
+            .load(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(rows, vec![(created.username.clone().unwrap(), 1)]);
+        let relationships: i64 = schema::social_graph_relationships::table
+            .filter(schema::social_graph_relationships::follower_address.eq(&follower))
+            .filter(schema::social_graph_relationships::following_address.eq(&profile_id))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(relationships, 1);
+        let following_count: i32 = schema::profiles::table
+            .filter(schema::profiles::profile_id.eq(&follower))
+            .select(schema::profiles::following_count)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(following_count, 1);
+    }
+
+    async fn verified_state(db: &Database, profile_id: &str) -> (bool, Option<chrono::NaiveDateTime>, Option<String>) {
+        let mut conn = db.get_connection().await.unwrap();
+        schema::profiles::table
+            .filter(schema::profiles::profile_id.eq(profile_id))
+            .select((schema::profiles::is_verified, schema::profiles::verified_at, schema::profiles::verified_by))
+            .first(&mut conn)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn verification_event_sets_and_revocation_clears_the_flag() {
+        use crate::db::test_support::insert_profile;
+        
+        let Some(db) = test_database().await else { return };
+        let listener = ProfileEventListener::new(db.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        let profile_id = unique_id("profile");
+        {
+            let mut conn = db.get_connection().await.unwrap();
+            insert_profile(&mut conn, &profile_id, &unique_id("user")).await;
+        }
+        let verification = |data: serde_json::Value| BlockchainEvent {
+            event_id: unique_id("tx"),
+            event_type: "0x1::profile::ProfileVerifiedEvent".to_string(),
+            struct_name: "ProfileVerifiedEvent".to_string(),
+            data,
+            ..created_event(&profile_id, "0xowner")
+        };
+        
+        // `verified` defaults to true when the event leaves it out
+        let event = verification(serde_json::json!({ "profile_id": profile_id, "verifier": "0xauthority", "timestamp": "1700000000" }));
+        listener.handle_profile_event(&event).await.unwrap();
+        let (is_verified, verified_at, verified_by) = verified_state(&db, &profile_id).await;
+        assert!(is_verified);
+        assert_eq!(verified_at, chrono::DateTime::from_timestamp(1_700_000_000, 0).map(|t| t.naive_utc()));
+        assert_eq!(verified_by.as_deref(), Some("0xauthority"));
+        
+        let event = verification(serde_json::json!({ "profile_id": profile_id, "verified": false, "verified_by": "0xauthority" }));
+        listener.handle_profile_event(&event).await.unwrap();
+        assert_eq!(verified_state(&db, &profile_id).await, (false, None, None));
+    }
+
+    #[tokio::test]
+    async fn profile_module_join_and_leave_are_recorded_once() {
+        let Some(db) = test_database().await else { return };
+        let listener = ProfileEventListener::new(db.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        let (profile_id, platform_id) = (unique_id("profile"), unique_id("platform"));
+        let membership_event = |struct_name: &str, event_id: &str, data: serde_json::Value| BlockchainEvent {
+            event_id: event_id.to_string(),
+            event_type: format!("0x1::profile::{}", struct_name),
+            struct_name: struct_name.to_string(),
+            data,
+            ..created_event(&profile_id, "0xowner")
+        };
+        // The join has no timestamp of its own, so it takes the checkpoint time
+        let joined = membership_event("PlatformJoinedEvent", &unique_id("tx"), serde_json::json!({
+            "profile_id": profile_id,
+            "platform_id": platform_id,
+        }));
+        let left = membership_event("PlatformLeftEvent", &unique_id("tx"), serde_json::json!({
+            "profile_id": profile_id,
+            "platform_id": platform_id,
+            "timestamp": 1_700_000_500,
+        }));
+        
+        for event in [&joined, &joined, &left, &left] {
+            listener.handle_profile_event(event).await.unwrap();
+        }
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let history: Vec<(String, chrono::NaiveDateTime)> = schema::profile_events::table
+            .filter(schema::profile_events::profile_id.eq(&profile_id))
+            .order_by(schema::profile_events::created_at.asc())
+            .select((schema::profile_events::event_type, schema::profile_events::created_at))
+            .load(&mut conn)
+            .await
+            .unwrap();
+        let at = |seconds: i64| chrono::DateTime::from_timestamp(seconds, 0).unwrap().naive_utc();
+        assert_eq!(history, vec![
+            ("PlatformJoinedEvent".to_string(), at(1_700_000_000)),
+            ("PlatformLeftEvent".to_string(), at(1_700_000_500)),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn update_merges_into_the_stored_profile() {
+        use crate::blockchain::store::MemoryProfileStore;
+        
+        let store = Arc::new(MemoryProfileStore::default());
+        let listener = ProfileEventListener::from_store(store.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        let mut first = update_event("0xprofile", "First");
+        first.bio = Some("Kept".to_string());
+        
+        listener.process_profile_updated(&first, &serde_json::Value::Null).await.unwrap();
+        listener.process_profile_updated(&update_event("0xprofile", "Second"), &serde_json::Value::Null).await.unwrap();
+        
+        let profiles = store.profiles.lock().await;
+        let (_, profile) = &profiles["0xprofile"];
+        assert_eq!(profile.display_name.as_deref(), Some("Second"));
+        assert_eq!(profile.bio.as_deref(), Some("Kept"));
+    }
+
+    #[tokio::test]
+    async fn update_keeps_its_source_event_without_serializing_it() {
+        use crate::blockchain::store::MemoryProfileStore;
+        
+        let store = Arc::new(MemoryProfileStore::default());
+        let listener = ProfileEventListener::from_store(store.clone(), mpsc::channel(1).1, "profile-test".to_string())
+            .with_source_events(true);
+        let raw = serde_json::json!({ "profile_id": "0xprofile", "phone": "encrypted" });
+        
+        listener.process_profile_updated(&update_event("0xprofile", "First"), &raw).await.unwrap();
+        listener.process_profile_updated(&update_event("0xprofile", "Second"), &raw).await.unwrap();
+        
+        let profiles = store.profiles.lock().await;
+        let (_, profile) = &profiles["0xprofile"];
+        assert_eq!(profile.source_event.as_ref(), Some(&raw));
+        assert!(serde_json::to_value(profile).unwrap().get("source_event").is_none());
+    }
+
+    fn created_event(profile_id: &str, owner: &str) -> BlockchainEvent {
+        BlockchainEvent {
+            tx_digest: "tx".to_string(),
+            event_id: "tx:0".to_string(),
+            event_type: "0x1::profile::ProfileCreatedEvent".to_string(),
+            package_address: "0x1".to_string(),
+            module_name: "profile".to_string(),
+            struct_name: "ProfileCreatedEvent".to_string(),
+            sender: "0x2".to_string(),
+            data: serde_json::json!({
+                "profile_id": profile_id,
+                "owner_address": owner,
+                "username": profile_id,
+            }),
+            timestamp_ms: 1_700_000_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn profile_from_an_ignored_owner_is_skipped() {
+        use crate::blockchain::ignored::{ignore_test_address, TEST_IGNORED_ADDRESS};
+        use crate::blockchain::store::MemoryProfileStore;
+        
+        ignore_test_address();
+        let store = Arc::new(MemoryProfileStore::default());
+        let listener = ProfileEventListener::from_store(store.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        
+        assert!(!listener.handle_profile_event(&created_event("0xspam", TEST_IGNORED_ADDRESS)).await.unwrap());
+        assert!(listener.handle_profile_event(&created_event("0xreal", "0xowner")).await.unwrap());
+        
+        let profiles = store.profiles.lock().await;
+        assert!(!profiles.contains_key("0xspam"));
+        assert!(profiles.contains_key("0xreal"));
+    }
+
+    #[tokio::test]
+    async fn ignored_owner_is_skipped_on_the_manual_parse_path() {
+        use crate::blockchain::ignored::{ignore_test_address, TEST_IGNORED_ADDRESS};
+        use crate::blockchain::store::MemoryProfileStore;
+        
+        ignore_test_address();
+        let store = Arc::new(MemoryProfileStore::default());
+        let listener = ProfileEventListener::from_store(store.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        // The manual parser reads the owner from `owner` in a Move fields container
+        let event = |profile_id: &str, owner: &str| BlockchainEvent {
+            data: serde_json::json!({ "fields": { "profile_id": profile_id, "owner": owner, "username": profile_id } }),
+            ..created_event(profile_id, owner)
+        };
+        
+        assert!(!listener.try_manual_profile_parse(&event("0xmanualspam", TEST_IGNORED_ADDRESS)).await.unwrap());
+        assert!(listener.try_manual_profile_parse(&event("0xmanualreal", "0xowner")).await.unwrap());
+        
+        let profiles = store.profiles.lock().await;
+        assert!(!profiles.contains_key("0xmanualspam"));
+        assert!(profiles.contains_key("0xmanualreal"));
+    }
+
+    #[tokio::test]
+    async fn placeholder_username_is_skipped_on_both_create_paths() {
+        use crate::blockchain::store::MemoryProfileStore;
+        
+        let store = Arc::new(MemoryProfileStore::default());
+        let listener = ProfileEventListener::from_store(store.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        let typed: ProfileCreatedEvent = serde_json::from_value(serde_json::json!({
+            "profile_id": "0xtyped",
+            "owner_address": "0xtypedowner",
+            "username": "unknown",
+        }))
+        .unwrap();
+        // No username at all, so the manual parser fills in "unknown"
+        let manual = BlockchainEvent {
+            data: serde_json::json!({ "fields": { "profile_id": "0xmanual", "owner": "0xmanualowner" } }),
+            ..created_event("0xmanual", "0xmanualowner")
+        };
+        
+        listener.process_profile_created(&typed, &serde_json::Value::Null).await.unwrap();
+        assert!(!listener.try_manual_profile_parse(&manual).await.unwrap());
+        
+        assert!(store.profiles.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_username_gets_a_generated_one() {
+        use crate::blockchain::store::MemoryProfileStore;
+        
+        let store = Arc::new(MemoryProfileStore::default());
+        let listener = ProfileEventListener::from_store(store.clone(), mpsc::channel(1).1, "profile-test".to_string());
+        let created: ProfileCreatedEvent = serde_json::from_value(serde_json::json!({
+            "profile_id": "0xnameless",
+            "owner_address": "0xabcdef0123",
+        }))
+        .unwrap();
+        
+        listener.process_profile_created(&created, &serde_json::Value::Null).await.unwrap();
+        
+        assert_eq!(store.profiles.lock().await["0xnameless"].1.username, "user_0xabcdef");
+    }
+}
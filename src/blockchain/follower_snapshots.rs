@@ -0,0 +1,125 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use chrono::NaiveDate;
+use diesel::sql_types::Date;
+use diesel_async::RunQueryDsl;
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info};
+
+use crate::db::Database;
+
+/// Write `date`'s follower and following counts for every active profile, returning the rows written
+///
+/// A profile is active when it was updated, followed or unfollowed someone, or was followed
+/// or unfollowed in the 30 days before `date`. Rerunning for the same day overwrites its rows.
+pub async fn snapshot_follower_counts(
+    conn: &mut diesel_async::AsyncPgConnection,
+    date: NaiveDate,
+) -> Result<usize, diesel::result::Error> {
+    diesel::sql_query(
+        "WITH active AS (
+             SELECT profile_id FROM profiles
+             WHERE profile_id IS NOT NULL AND updated_at >= $1::date - 30
+             UNION SELECT follower_address FROM social_graph_events WHERE created_at >= $1::date - 30
+             UNION SELECT following_address FROM social_graph_events WHERE created_at >= $1::date - 30
+         )
+         INSERT INTO profile_follower_snapshots (profile_id, date, followers_count, following_count)
+         SELECT p.profile_id, $1, p.followers_count, p.following_count
+         FROM profiles p
+         JOIN active a ON a.profile_id = p.profile_id
+         ON CONFLICT (profile_id, date) DO UPDATE
+         SET followers_count = EXCLUDED.followers_count,
+             following_count = EXCLUDED.following_count"
+    )
+    .bind::<Date, _>(date)
+    .execute(conn)
+    .await
+}
+
+/// Periodically snapshot follower counts for today (UTC)
+///
+/// Each pass overwrites today's rows, so a day ends up holding the counts from its last pass.
+pub fn spawn_follower_snapshotter(db: Arc<Database>, every: Duration) -> tokio::task::JoinHandle<()> {
+    crate::tasks::spawn_named("follower-snapshotter", async move {
+        let mut ticker = interval(every);
+        loop {
+            ticker.tick().await;
+            let today = chrono::Utc::now().date_naive();
+
+            let mut conn = match db.get_connection().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    error!("Follower snapshot skipped, no database connection: {}", e);
+                    continue;
+                }
+            };
+
+            match snapshot_follower_counts(&mut conn, today).await {
+                Ok(0) => debug!("Follower snapshot for {} found no active profiles", today),
+                Ok(rows) => info!("Snapshotted follower counts for {} profiles on {}", rows, today),
+                Err(e) => error!("Follower snapshot failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::{Path, Query, State}, http::StatusCode, response::IntoResponse};
+    use diesel::prelude::*;
+    use crate::api::handlers::social_graph::get_follower_trend;
+    use crate::db::test_support::{insert_profile, test_database, unique_id};
+    use crate::models::social_graph::FollowerTrendQuery;
+    use crate::schema::{profile_follower_snapshots, profiles};
+
+    async fn set_counts(conn: &mut diesel_async::AsyncPgConnection, profile_id: &str, followers: i32, following: i32) {
+        diesel::update(profiles::table.filter(profiles::profile_id.eq(profile_id)))
+            .set((profiles::followers_count.eq(followers), profiles::following_count.eq(following)))
+            .execute(conn)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn snapshot_is_written_and_served_as_the_trend() {
+        let Some(db) = test_database().await else { return };
+        let pool = db.pool.as_ref().clone();
+        let mut conn = pool.get().await.unwrap();
+        let (active, inactive) = (unique_id("profile"), unique_id("profile"));
+        let today = chrono::Utc::now().date_naive();
+        insert_profile(&mut conn, &active, &unique_id("user")).await;
+        insert_profile(&mut conn, &inactive, &unique_id("user")).await;
+        set_counts(&mut conn, &active, 7, 2).await;
+        diesel::update(profiles::table.filter(profiles::profile_id.eq(&inactive)))
+            .set(profiles::updated_at.eq(NaiveDate::from_ymd_opt(2001, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        
+        snapshot_follower_counts(&mut conn, today).await.unwrap();
+        // A later pass the same day overwrites the row
+        set_counts(&mut conn, &active, 9, 3).await;
+        snapshot_follower_counts(&mut conn, today).await.unwrap();
+        
+        let inactive_rows: i64 = profile_follower_snapshots::table
+            .filter(profile_follower_snapshots::profile_id.eq(&inactive))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(inactive_rows, 0);
+        
+        let query = FollowerTrendQuery { from: Some(today), to: Some(today) };
+        let response = get_follower_trend(State(pool), Path(active), Query(query)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(
+            body["snapshots"],
+            serde_json::json!([{ "date": today, "followers_count": 9, "following_count": 3 }])
+        );
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashSet;
+
+use once_cell::sync::OnceCell;
+use tracing::{debug, warn};
+
+use crate::metrics::IGNORED_EVENTS;
+
+use super::listener::BlockchainEvent;
+
+// Addresses whose events are dropped before indexing (spam or test accounts)
+static IGNORED_ADDRESSES: OnceCell<HashSet<String>> = OnceCell::new();
+
+fn normalize(address: &str) -> String {
+    address.trim().to_lowercase()
+}
+
+/// Set the addresses whose events are skipped by the handlers
+pub fn set_ignored_addresses(addresses: &[String]) {
+    let addresses = addresses
+        .iter()
+        .map(|address| normalize(address))
+        .filter(|address| !address.is_empty())
+        .collect();
+    IGNORED_ADDRESSES.set(addresses).unwrap_or_else(|_| {
+        warn!("Ignored addresses already set, ignoring new value");
+    });
+}
+
+/// Whether events from this address should be skipped
+pub fn is_ignored_address(address: &str) -> bool {
+    IGNORED_ADDRESSES
+        .get()
+        .map_or(false, |addresses| !addresses.is_empty() && addresses.contains(&normalize(address)))
+}
+
+/// Skip the event if its sender or any of `addresses` is ignored, counting it for `handler`
+pub fn skip_ignored(handler: &str, event: &BlockchainEvent, addresses: &[&str]) -> bool {
+    let ignored = std::iter::once(event.sender.as_str())
+        .chain(addresses.iter().copied())
+        .find(|address| is_ignored_address(address));
+
+    match ignored {
+        Some(address) => {
+            debug!("Skipping {} from ignored address {}", event.event_type, address);
+            IGNORED_EVENTS.with_label_values(&[handler]).inc();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Address every test treats as ignored; the list can only be set once per process
+#[cfg(test)]
+pub(crate) const TEST_IGNORED_ADDRESS: &str = "0xIGNORED";
+
+/// Make `TEST_IGNORED_ADDRESS` the ignored list
+#[cfg(test)]
+pub(crate) fn ignore_test_address() {
+    static SET: std::sync::Once = std::sync::Once::new();
+    SET.call_once(|| set_ignored_addresses(&[TEST_IGNORED_ADDRESS.to_string()]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignored_addresses_match_regardless_of_case_and_whitespace() {
+        ignore_test_address();
+        
+        assert!(is_ignored_address("0xignored"));
+        assert!(is_ignored_address(" 0xIgnored "));
+        assert!(!is_ignored_address("0xother"));
+    }
+}
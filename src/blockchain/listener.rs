@@ -4,17 +4,34 @@
 use std::sync::Arc;
 use anyhow::Result;
 use futures::StreamExt;
+use tokio::sync::mpsc::error::{SendTimeoutError, TrySendError};
 use tokio::sync::{mpsc, Mutex};
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info, warn};
 
 use mys_sdk::{
-    rpc_types::EventFilter,
-    MysClientBuilder,
+    rpc_types::{Checkpoint, CheckpointId, EventFilter, MysEvent},
+    MysClient, MysClientBuilder,
 };
+use mys_types::event::EventID;
 
-use crate::config::Config;
-use crate::db::Database;
+use crate::config::{BlockchainConfig, Config};
+use crate::db::{self, Database};
+use crate::events::intra_transaction_rank;
+use crate::events::event_data::{check_event_data_size, sanitize_event_data, truncated_preview, MalformedEventData};
+use crate::metrics::{HANDLER_CHANNEL_FULL, HANDLER_UNDELIVERED, MALFORMED_EVENTS};
+
+use super::catch_up::CatchUpStatus;
+use super::dead_letter::record_dead_letter;
+
+/// Worker id recorded on dead letters rejected by the listener before reaching a handler
+const LISTENER_WORKER_ID: &str = "listener";
+
+/// Bytes of an oversized payload kept on its dead letter
+const OVERSIZED_PREVIEW_BYTES: usize = 4096;
+
+/// How long a quiet subscription waits for more events of the same transaction
+const TRANSACTION_FLUSH_DELAY: Duration = Duration::from_millis(100);
 
 /// Type for events received from the blockchain
 #[derive(Debug)]
@@ -25,38 +42,102 @@ pub struct BlockchainEvent {
     pub event_id: String,
     /// Event type
     pub event_type: String,
+    /// Address of the package that emitted the event
+    pub package_address: String,
+    /// Move module that emitted the event (e.g. `profile`, `platform`)
+    pub module_name: String,
+    /// Move struct name of the event (e.g. `ProfileCreatedEvent`)
+    pub struct_name: String,
+    /// Address of the transaction sender
+    pub sender: String,
     /// Event data as JSON
     pub data: serde_json::Value,
     /// Timestamp from the blockchain
     pub timestamp_ms: u64,
 }
 
+/// A registered handler's channel
+#[derive(Clone)]
+struct HandlerChannel {
+    /// Worker id of the handler, used for metrics and its dead letters
+    worker_id: String,
+    sender: mpsc::Sender<BlockchainEvent>,
+}
+
 /// Listener that connects to the blockchain and processes events
 pub struct BlockchainEventListener {
     /// Configuration
     config: Config,
+    /// Database connection pool, used to find where to resume from
+    db: Arc<Database>,
     /// Event handler channels
-    event_senders: Mutex<Vec<mpsc::Sender<BlockchainEvent>>>,
+    event_senders: Mutex<Vec<HandlerChannel>>,
+    /// Whether everything up to the chain tip has been indexed
+    catch_up: CatchUpStatus,
 }
 
 impl BlockchainEventListener {
     /// Create a new blockchain event listener
-    pub fn new(config: Config, _db: Arc<Database>) -> Self {
-        // Note: db parameter kept for API compatibility but not used
+    pub fn new(config: Config, db: Arc<Database>) -> Self {
         Self {
             config,
+            db,
             event_senders: Mutex::new(Vec::new()),
+            catch_up: CatchUpStatus::new(),
         }
     }
+    
+    /// Report catch-up through `catch_up`, e.g. one shared with the API
+    pub fn with_catch_up(mut self, catch_up: CatchUpStatus) -> Self {
+        self.catch_up = catch_up;
+        self
+    }
 
     /// Register a new event handler
-    pub async fn register_event_handler(&self, sender: mpsc::Sender<BlockchainEvent>) {
+    ///
+    /// Events the handler can't take are dead-lettered under `worker_id`; see `process_event`.
+    pub async fn register_event_handler(&self, worker_id: impl Into<String>, sender: mpsc::Sender<BlockchainEvent>) {
         let mut senders = self.event_senders.lock().await;
-        senders.push(sender);
+        senders.push(HandlerChannel { worker_id: worker_id.into(), sender });
     }
 
     /// Process a blockchain event and forward it to all registered handlers
-    async fn process_event(&self, event: BlockchainEvent) {
+    ///
+    /// Events from packages that aren't monitored are dropped first, so nothing is dead-lettered
+    /// for them. Events whose data is malformed or larger than `MAX_EVENT_DATA_BYTES` are then
+    /// dead-lettered here and never reach the handlers.
+    ///
+    /// Every handler with room in its channel gets the event straight away, so one slow handler
+    /// doesn't hold the event back from the others. Handlers whose channel is full are then
+    /// waited on together for up to `HANDLER_SEND_TIMEOUT_MS`. An event a handler still hasn't
+    /// accepted by then, or whose handler has stopped, is dead-lettered under that handler's
+    /// worker id rather than dropped.
+    async fn process_event(&self, mut event: BlockchainEvent) {
+        match screen_event(&mut event, self.config.blockchain.max_event_data_bytes) {
+            Screened::Unmonitored => {
+                debug!("Ignoring event {} from unmonitored package {}", event.event_id, event.package_address);
+                return;
+            }
+            Screened::Ready(0) => {}
+            Screened::Ready(cleaned) => warn!("Stripped NUL characters from {} string(s) in event {}", cleaned, event.event_id),
+            Screened::Malformed(malformed) => {
+                error!("Dead-lettering event {} ({}): {}", event.event_id, event.event_type, malformed);
+                MALFORMED_EVENTS.with_label_values(&[malformed.reason()]).inc();
+                if let MalformedEventData::TooLarge { bytes, .. } = malformed {
+                    // Storing the whole payload on the dead letter would defeat the limit
+                    event.data = serde_json::json!({
+                        "truncated": truncated_preview(&event.data, OVERSIZED_PREVIEW_BYTES),
+                        "bytes": bytes
+                    });
+                } else if !event.data.is_object() && !event.data.is_null() {
+                    // Keep the payload as text so the dead letter itself can be stored
+                    event.data = serde_json::json!({ "raw": event.data.to_string().replace('\0', "") });
+                }
+                record_dead_letter(&self.db, LISTENER_WORKER_ID, &event, &anyhow::Error::new(malformed)).await;
+                return;
+            }
+        }
+        
         // SUPER IMPORTANT: Log every single event type that comes through the system
         // This will help us identify if events are being received at all
         tracing::info!("🔍 GLOBAL EVENT TRACKER: Received event type: {}", event.event_type);
@@ -81,11 +162,16 @@ impl BlockchainEventListener {
             }
         }
         
-        let senders = self.event_senders.lock().await;
-        for sender in senders.iter() {
-            if let Err(e) = sender.send(event.clone()).await {
-                error!("Failed to send event to handler: {}", e);
-            }
+        // Clone the senders so a handler that's slow to take the event doesn't hold the lock
+        let handlers = self.event_senders.lock().await.clone();
+        let timeout = Duration::from_millis(self.config.blockchain.handler_send_timeout_ms);
+        let undelivered = fan_out(&handlers, &event, timeout).await;
+        
+        for (worker_id, reason) in &undelivered {
+            error!("Handler {} did not accept event {} ({}), dead-lettering it", worker_id, event.event_id, reason);
+            HANDLER_UNDELIVERED.with_label_values(&[worker_id.as_str(), *reason]).inc();
+            let failure = anyhow::anyhow!("Handler channel {}: event not delivered", reason);
+            record_dead_letter(&self.db, worker_id, &event, &failure).await;
         }
     }
 
@@ -108,36 +194,49 @@ impl BlockchainEventListener {
         }
         
         // Create event filter for all events
-        // This will capture all events - we'll filter by package and module in our handlers
+        // This will capture all events - process_event drops the ones from other packages
         let event_filter = EventFilter::All([]);
         
         // Subscribe to events
         let mut event_stream = client.event_api().subscribe_event(event_filter).await?;
         info!("Successfully subscribed to blockchain events");
         
-        // Process events as they arrive
-        while let Some(event_result) = event_stream.next().await {
-            match event_result {
-                Ok(event) => {
+        // A subscription delivers events as they are emitted, so it starts at the tip
+        self.catch_up.update(true);
+        
+        // Events of one transaction arrive back to back. They're held until the next transaction
+        // starts, or the stream goes quiet, and then applied creations first.
+        let mut transaction: Vec<MysEvent> = Vec::new();
+        loop {
+            let next = if transaction.is_empty() {
+                event_stream.next().await
+            } else {
+                match tokio::time::timeout(TRANSACTION_FLUSH_DELAY, event_stream.next()).await {
+                    Ok(next) => next,
+                    Err(_) => {
+                        self.flush_transaction(&mut transaction).await;
+                        continue;
+                    }
+                }
+            };
+            
+            match next {
+                Some(Ok(event)) => {
                     debug!("Received event: {:?}", event);
                     
-                    // Get timestamp with fallback
-                    let timestamp_ms = event.timestamp_ms.unwrap_or_else(|| {
-                        std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_millis() as u64
-                    });
+                    if transaction.first().is_some_and(|first| first.id.tx_digest != event.id.tx_digest) {
+                        self.flush_transaction(&mut transaction).await;
+                    }
                     
                     // Log the raw event for debugging
                     tracing::debug!("Raw blockchain event: {:?}", event);
                     
                     // Get the parsed JSON data
-                    let parsed_data = event.parsed_json.clone();
+                    let parsed_data = &event.parsed_json;
                     
                     // Log the complete raw event structure for detailed debugging
                     tracing::info!("Complete raw blockchain event JSON: {}", serde_json::to_string_pretty(&event).unwrap_or_default());
-                    tracing::info!("Parsed JSON data: {}", serde_json::to_string_pretty(&parsed_data).unwrap_or_default());
+                    tracing::info!("Parsed JSON data: {}", serde_json::to_string_pretty(parsed_data).unwrap_or_default());
                     
                     // Log all events that might be relevant
                     if event.type_.to_string().contains("::profile::") || 
@@ -188,31 +287,37 @@ impl BlockchainEventListener {
                         }
                     }
                     
-                    // Generate event ID
-                    let event_id = format!("{}:{}", event.id.tx_digest, event.id.event_seq); 
-                    
-                    // Convert to blockchain event
-                    let blockchain_event = BlockchainEvent {
-                        tx_digest: event.id.tx_digest.to_string(),
-                        event_id,
-                        event_type: event.type_.to_string(),
-                        data: parsed_data,
-                        timestamp_ms,
-                    };
-                    
-                    // Process the event
-                    self.process_event(blockchain_event).await;
+                    transaction.push(event);
                 }
-                Err(e) => {
+                Some(Err(e)) => {
                     error!("Error receiving event: {}", e);
                 }
+                None => break,
             }
         }
+        self.flush_transaction(&mut transaction).await;
         
         warn!("Event stream ended unexpectedly");
+        self.catch_up.update(false);
         Ok(())
     }
     
+    /// Apply the buffered events of one subscribed transaction, creations first
+    async fn flush_transaction(&self, transaction: &mut Vec<MysEvent>) {
+        order_within_transactions(transaction);
+        for event in transaction.drain(..) {
+            // Get timestamp with fallback
+            let timestamp_ms = event.timestamp_ms.unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64
+            });
+            let parsed_data = event.parsed_json.clone();
+            self.process_event(to_blockchain_event(&event, parsed_data, timestamp_ms)).await;
+        }
+    }
+    
     /// Start the blockchain event listener using polling
     pub async fn start_polling_listener(&self) -> Result<()> {
         info!("Starting blockchain event listener using polling");
@@ -231,40 +336,73 @@ impl BlockchainEventListener {
         }
         
         // Create event filter for all events
-        // This will capture all events - we'll filter by package and module in our handlers
+        // This will capture all events - process_event drops the ones from other packages
         let event_filter = EventFilter::All([]);
         
         // Create polling interval
         let mut interval = interval(Duration::from_millis(self.config.blockchain.poll_interval_ms));
         
-        // Track the last seen event timestamp
-        let mut last_seen_timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)?
-            .as_millis() as u64;
+        // Page forward from the newest final event at startup; anything older is left to the
+        // startup replay, and events inside the lag window are picked up once they are final
+        let lag = self.config.blockchain.index_lag_checkpoints;
+        let startup_cutoff_ms = if lag > 0 {
+            Some(lag_cutoff_ms(&client, &self.config.blockchain, lag).await?)
+        } else {
+            None
+        };
+        let mut cursor = self.startup_cursor(&client, &event_filter, startup_cutoff_ms).await?;
         
         // Poll for events
         loop {
             interval.tick().await;
             
+            // Events newer than the checkpoint `lag` behind the tip are left for a later poll
+            let cutoff_ms = if lag > 0 {
+                match lag_cutoff_ms(&client, &self.config.blockchain, lag).await {
+                    Ok(cutoff_ms) => Some(cutoff_ms),
+                    Err(e) => {
+                        warn!("Could not find the checkpoint {} behind the tip, skipping this poll: {}", lag, e);
+                        self.catch_up.update(false);
+                        continue;
+                    }
+                }
+            } else {
+                None
+            };
+            
             match client.event_api()
                 .query_events(
                     event_filter.clone(),
-                    None,
+                    cursor,
                     Some(self.config.blockchain.batch_size),
-                    true, // descending order to get newest first
+                    false, // ascending from the cursor, so nothing between polls is skipped
                 ).await 
             {
-                Ok(events) => {
-                    // Process events in reverse order (oldest to newest)
-                    for event in events.data.into_iter().rev() {
-                        // Get the timestamp
-                        let event_timestamp = event.timestamp_ms.unwrap_or(0);
-                        
-                        // Skip events we've already seen
-                        if event_timestamp <= last_seen_timestamp {
-                            continue;
+                Ok(page) => {
+                    // Too recent to be final; the first such event and every later one wait for
+                    // the next poll, and the cursor stays on the last event indexed before them
+                    let mut ready = ready_prefix_len(page.data.iter().map(|event| event.timestamp_ms), cutoff_ms);
+                    let deferred = ready < page.data.len();
+                    if deferred {
+                        debug!("Deferring {} event(s) until {} checkpoints have passed", page.data.len() - ready, lag);
+                    } else if page.has_next_page {
+                        // The page may end partway through a transaction; fetch that one whole next time
+                        let split = trailing_group_len(&page.data, |event| event.id.tx_digest);
+                        if split < ready {
+                            ready -= split;
                         }
-                        
+                    }
+                    
+                    let caught_up = poll_caught_up(deferred, page.has_next_page);
+                    
+                    let mut events = page.data;
+                    events.truncate(ready);
+                    if let Some(last) = events.last() {
+                        cursor = Some(last.id);
+                    }
+                    order_within_transactions(&mut events);
+                    
+                    for event in events {
                         debug!("Processing event: {:?}", event);
                         
                         // Get timestamp with fallback
@@ -275,9 +413,6 @@ impl BlockchainEventListener {
                                 .as_millis() as u64
                         });
                         
-                        // Update the last seen timestamp
-                        last_seen_timestamp = timestamp_ms;
-                        
                         // Log the raw event for debugging
                         tracing::debug!("Raw blockchain event: {:?}", event);
                         
@@ -290,31 +425,154 @@ impl BlockchainEventListener {
                             tracing::info!("!!! CRITICAL DEBUG: BlockProfileEvent DATA: {}", serde_json::to_string_pretty(&parsed_data).unwrap_or_default());
                         }
                         
-                        // Generate the event ID in format <digest>:<event_seq>
-                        let event_id = format!("{}:{}", event.id.tx_digest, event.id.event_seq);
-                        
                         // Convert to blockchain event
-                        let blockchain_event = BlockchainEvent {
-                            tx_digest: event.id.tx_digest.to_string(),
-                            event_id,
-                            event_type: event.type_.to_string(),
-                            data: parsed_data,
-                            timestamp_ms,
-                        };
+                        let blockchain_event = to_blockchain_event(&event, parsed_data, timestamp_ms);
                         
                         // Process the event
                         self.process_event(blockchain_event).await;
                     }
+                    
+                    self.catch_up.update(caught_up);
                 }
                 Err(e) => {
                     error!("Error querying events: {}", e);
+                    self.catch_up.update(false);
                 }
             }
         }
     }
     
+    /// Id of the newest event at or before `cutoff_ms` (the newest event without a cutoff)
+    ///
+    /// Polling resumes after it, so events newer than the cutoff are still ahead of the cursor.
+    /// `None` means no event is final yet and polling starts from the first one.
+    async fn startup_cursor(
+        &self,
+        client: &MysClient,
+        event_filter: &EventFilter,
+        cutoff_ms: Option<u64>,
+    ) -> Result<Option<EventID>> {
+        let mut page_cursor = None;
+        loop {
+            let page = client.event_api()
+                .query_events(event_filter.clone(), page_cursor, Some(self.config.blockchain.batch_size), true)
+                .await?;
+            
+            if let Some(index) = first_final_index(page.data.iter().map(|event| event.timestamp_ms), cutoff_ms) {
+                return Ok(Some(page.data[index].id));
+            }
+            if !page.has_next_page || page.next_cursor.is_none() {
+                return Ok(None);
+            }
+            page_cursor = page.next_cursor;
+        }
+    }
+    
+    /// Re-send the events of the last `STARTUP_REPLAY_CHECKPOINTS` checkpoints up to the
+    /// one holding the oldest event any worker recorded as processed.
+    ///
+    /// Progress is recorded before a handler has necessarily committed, so a crash can leave
+    /// the tail of the stream unapplied. Replaying that window on boot heals it, which relies
+    /// on the handlers treating a repeated event as a no-op.
+    ///
+    /// On a database with no progress yet, a non-zero `START_CHECKPOINT` replays from that
+    /// checkpoint up to the current tip instead.
+    pub async fn replay_recent_checkpoints(&self, client: &MysClient) -> Result<()> {
+        let window = self.config.blockchain.startup_replay_checkpoints;
+        let start_checkpoint = self.config.blockchain.start_checkpoint;
+        if window == 0 && start_checkpoint == 0 {
+            return Ok(());
+        }
+        
+        // Workers record the timestamp of the last event they handled
+        let progress = db::load_indexer_progress(&self.db).await?;
+        let resume_timestamp_ms = progress
+            .iter()
+            .map(|worker| worker.last_checkpoint_processed)
+            .filter(|timestamp| *timestamp > 0)
+            .min();
+        
+        let (first_checkpoint, last_checkpoint) = match resume_timestamp_ms {
+            Some(_) if window == 0 => return Ok(()),
+            Some(timestamp) => {
+                let last_checkpoint = checkpoint_at_or_before(client, &self.config.blockchain, timestamp as u64).await?;
+                let first_checkpoint = replay_window_start(last_checkpoint, window);
+                info!(
+                    "Replaying checkpoints {}..={} ({} requested) before resuming",
+                    first_checkpoint, last_checkpoint, window
+                );
+                (first_checkpoint, last_checkpoint)
+            }
+            None if start_checkpoint > 0 => {
+                let tip = client.read_api().get_latest_checkpoint_sequence_number().await?;
+                if start_checkpoint > tip {
+                    warn!("START_CHECKPOINT {} is past the chain tip {}, skipping startup replay", start_checkpoint, tip);
+                    return Ok(());
+                }
+                info!("No indexer progress recorded yet, indexing checkpoints {}..={} from START_CHECKPOINT", start_checkpoint, tip);
+                (start_checkpoint, tip)
+            }
+            None => {
+                info!("No indexer progress recorded yet, skipping startup replay");
+                return Ok(());
+            }
+        };
+        
+        let mut replayed = 0usize;
+        for sequence_number in first_checkpoint..=last_checkpoint {
+            let checkpoint = fetch_checkpoint(client, &self.config.blockchain, sequence_number).await?;
+            
+            for digest in checkpoint.transactions {
+                let mut events = client.event_api().get_events(digest).await?;
+                order_within_transactions(&mut events);
+                for event in events {
+                    let timestamp_ms = event.timestamp_ms.unwrap_or(checkpoint.timestamp_ms);
+                    let parsed_data = event.parsed_json.clone();
+                    self.process_event(to_blockchain_event(&event, parsed_data, timestamp_ms)).await;
+                    replayed += 1;
+                }
+            }
+        }
+        
+        info!("Startup replay finished: {} events from {} checkpoints", replayed, last_checkpoint + 1 - first_checkpoint);
+        Ok(())
+    }
+    
+    /// Checkpoint holding each of `timestamps_ms`, the event timestamps workers persist
+    pub async fn checkpoints_at(&self, timestamps_ms: &[u64]) -> Result<Vec<u64>> {
+        let client = MysClientBuilder::default()
+            .build(&self.config.blockchain.rpc_url)
+            .await?;
+        let mut checkpoints = Vec::with_capacity(timestamps_ms.len());
+        for timestamp_ms in timestamps_ms {
+            checkpoints.push(checkpoint_at_or_before(&client, &self.config.blockchain, *timestamp_ms).await?);
+        }
+        Ok(checkpoints)
+    }
+    
     /// Start the blockchain event listener using the preferred method
     pub async fn start(&self) -> Result<()> {
+        if self.config.blockchain.startup_replay_checkpoints > 0 || self.config.blockchain.start_checkpoint > 0 {
+            let replay = async {
+                let client = MysClientBuilder::default()
+                    .build(&self.config.blockchain.rpc_url)
+                    .await?;
+                self.replay_recent_checkpoints(&client).await
+            };
+            if let Err(e) = replay.await {
+                error!("Startup checkpoint replay failed: {}", e);
+            }
+        }
+        
+        // Subscribed events can't be held back, so a confirmation lag needs the polling listener
+        if self.config.blockchain.index_lag_checkpoints > 0 {
+            info!(
+                "Indexing {} checkpoints behind the tip, using the polling listener",
+                self.config.blockchain.index_lag_checkpoints
+            );
+            return self.start_polling_listener().await;
+        }
+        
         // Try WebSocket first, fall back to polling if that fails
         match self.start_ws_listener().await {
             Ok(_) => Ok(()),
@@ -326,6 +584,222 @@ impl BlockchainEventListener {
     }
 }
 
+/// First checkpoint of a `window`-checkpoint replay ending at `last_checkpoint`
+fn replay_window_start(last_checkpoint: u64, window: u64) -> u64 {
+    (last_checkpoint + 1).saturating_sub(window)
+}
+
+/// Number of leading events in an ascending page that can be indexed now
+///
+/// With a `cutoff_ms`, an event past the cutoff checkpoint's timestamp, or with no timestamp
+/// at all, is not final yet; it and everything after it are held back so the cursor never
+/// moves past them.
+fn ready_prefix_len(timestamps: impl Iterator<Item = Option<u64>>, cutoff_ms: Option<u64>) -> usize {
+    match cutoff_ms {
+        Some(cutoff_ms) => timestamps
+            .take_while(|timestamp| timestamp.is_some_and(|timestamp| timestamp <= cutoff_ms))
+            .count(),
+        None => timestamps.count(),
+    }
+}
+
+/// Position of the first final event in a descending page
+///
+/// The counterpart of `ready_prefix_len` for finding where to start: without a cutoff the
+/// newest event is final, otherwise the first one at or before `cutoff_ms`.
+fn first_final_index(mut timestamps: impl Iterator<Item = Option<u64>>, cutoff_ms: Option<u64>) -> Option<usize> {
+    match cutoff_ms {
+        Some(cutoff_ms) => timestamps.position(|timestamp| timestamp.is_some_and(|timestamp| timestamp <= cutoff_ms)),
+        None => timestamps.next().map(|_| 0),
+    }
+}
+
+/// Order each transaction's events creations first, keeping emission order otherwise
+///
+/// `events` must hold each transaction's events together, as pages and subscriptions do.
+fn order_within_transactions(events: &mut [MysEvent]) {
+    sort_within_groups(
+        events,
+        |event| event.id.tx_digest,
+        |event| intra_transaction_rank(event.type_.name.as_str()),
+    );
+}
+
+/// Stable-sort each run of consecutive items sharing a `group` key by `rank`
+fn sort_within_groups<T, K: PartialEq>(items: &mut [T], group: impl Fn(&T) -> K, rank: impl Fn(&T) -> u8) {
+    let mut start = 0;
+    while start < items.len() {
+        let key = group(&items[start]);
+        let len = items[start..].iter().take_while(|item| group(item) == key).count();
+        items[start..start + len].sort_by_key(&rank);
+        start += len;
+    }
+}
+
+/// Length of the run of items at the end of `items` sharing the last item's `group` key
+fn trailing_group_len<T, K: PartialEq>(items: &[T], group: impl Fn(&T) -> K) -> usize {
+    match items.last() {
+        Some(last) => {
+            let key = group(last);
+            items.iter().rev().take_while(|item| group(item) == key).count()
+        }
+        None => 0,
+    }
+}
+
+/// Offer `event` to every handler, returning the worker id and reason for each that didn't take it
+///
+/// Handlers with room get the event straight away; those whose channel is full are waited on
+/// together for up to `timeout`.
+async fn fan_out(handlers: &[HandlerChannel], event: &BlockchainEvent, timeout: Duration) -> Vec<(String, &'static str)> {
+    let mut undelivered = Vec::new();
+    let mut waiting = Vec::new();
+    for handler in handlers {
+        match handler.sender.try_send(event.clone()) {
+            Ok(()) => {}
+            Err(TrySendError::Full(pending)) => {
+                HANDLER_CHANNEL_FULL.with_label_values(&[handler.worker_id.as_str()]).inc();
+                waiting.push((handler, pending));
+            }
+            Err(TrySendError::Closed(_)) => undelivered.push((handler.worker_id.clone(), "closed")),
+        }
+    }
+    
+    let waits = waiting.into_iter().map(|(handler, pending)| async move {
+        (handler, handler.sender.send_timeout(pending, timeout).await)
+    });
+    for (handler, result) in futures::future::join_all(waits).await {
+        match result {
+            Ok(()) => debug!("Handler {} accepted event {} after waiting", handler.worker_id, event.event_id),
+            Err(SendTimeoutError::Timeout(_)) => undelivered.push((handler.worker_id.clone(), "timeout")),
+            Err(SendTimeoutError::Closed(_)) => undelivered.push((handler.worker_id.clone(), "closed")),
+        }
+    }
+    undelivered
+}
+
+/// Outcome of the checks an event goes through before it's fanned out
+#[derive(Debug, PartialEq, Eq)]
+enum Screened {
+    /// The event comes from a package that isn't monitored
+    Unmonitored,
+    /// The event can be forwarded; holds the number of strings that had NULs stripped
+    Ready(usize),
+    /// The event must be dead-lettered
+    Malformed(MalformedEventData),
+}
+
+/// Filter by package, then sanitize and size-check the event data in place
+///
+/// The package filter comes first so events nobody indexes are never dead-lettered.
+fn screen_event(event: &mut BlockchainEvent, max_event_data_bytes: usize) -> Screened {
+    if !crate::is_monitored_package(&event.package_address) {
+        return Screened::Unmonitored;
+    }
+    
+    let checked = sanitize_event_data(&mut event.data).and_then(|cleaned| {
+        check_event_data_size(&event.data, max_event_data_bytes)?;
+        Ok(cleaned)
+    });
+    match checked {
+        Ok(cleaned) => Screened::Ready(cleaned),
+        Err(malformed) => Screened::Malformed(malformed),
+    }
+}
+
+/// Whether a poll left nothing indexable behind
+///
+/// Caught up means every event up to the cutoff checkpoint (the tip, without a lag) has been
+/// handed to the handlers: either the poll stopped at an event past that checkpoint, or the
+/// node had no further page. Event timestamps play no part beyond locating the cutoff.
+fn poll_caught_up(deferred: bool, has_next_page: bool) -> bool {
+    deferred || !has_next_page
+}
+
+/// Convert an event received from the node into the form passed to handlers
+fn to_blockchain_event(event: &MysEvent, data: serde_json::Value, timestamp_ms: u64) -> BlockchainEvent {
+    BlockchainEvent {
+        tx_digest: event.id.tx_digest.to_string(),
+        // Event ID in format <digest>:<event_seq>
+        event_id: format!("{}:{}", event.id.tx_digest, event.id.event_seq),
+        event_type: event.type_.to_string(),
+        package_address: event.type_.address.to_hex_literal(),
+        module_name: event.type_.module.to_string(),
+        struct_name: event.type_.name.to_string(),
+        sender: event.sender.to_string(),
+        data,
+        timestamp_ms,
+    }
+}
+
+/// Fetch a checkpoint, bounding each attempt by `CHECKPOINT_FETCH_TIMEOUT_SECS` and retrying
+/// up to `CHECKPOINT_FETCH_RETRIES` times with exponential backoff
+async fn fetch_checkpoint(client: &MysClient, config: &BlockchainConfig, sequence_number: u64) -> Result<Checkpoint> {
+    let timeout = Duration::from_secs(config.checkpoint_fetch_timeout_secs.max(1));
+    let fetch = || async move {
+        client
+            .read_api()
+            .get_checkpoint(CheckpointId::SequenceNumber(sequence_number))
+            .await
+            .map_err(anyhow::Error::from)
+    };
+    retry_with_timeout(fetch, config.checkpoint_fetch_retries, timeout, Duration::from_millis(500))
+        .await
+        .map_err(|e| e.context(format!("Failed to fetch checkpoint {}", sequence_number)))
+}
+
+/// Run `fetch` until it succeeds, bounding each attempt by `timeout` and retrying up to
+/// `retries` times; the wait between attempts doubles from `backoff`
+async fn retry_with_timeout<T, F, Fut>(mut fetch: F, retries: u32, timeout: Duration, backoff: Duration) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    let mut attempt = 0u32;
+    
+    loop {
+        let error = match tokio::time::timeout(timeout, fetch()).await {
+            Ok(Ok(value)) => return Ok(value),
+            Ok(Err(e)) => e,
+            Err(_) => anyhow::anyhow!("timed out after {:?}", timeout),
+        };
+        
+        if attempt >= retries {
+            return Err(error.context(format!("Gave up after {} attempts", attempt + 1)));
+        }
+        
+        let delay = backoff.saturating_mul(1 << attempt.min(6));
+        warn!("Attempt {} failed: {}; retrying in {:?}", attempt + 1, error, delay);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+/// Timestamp of the checkpoint `lag` behind the latest one; later events aren't indexed yet
+async fn lag_cutoff_ms(client: &MysClient, config: &BlockchainConfig, lag: u64) -> Result<u64> {
+    let tip = client.read_api().get_latest_checkpoint_sequence_number().await?;
+    let checkpoint = fetch_checkpoint(client, config, tip.saturating_sub(lag)).await?;
+    Ok(checkpoint.timestamp_ms)
+}
+
+/// Find the latest checkpoint whose timestamp is not after `timestamp_ms`
+async fn checkpoint_at_or_before(client: &MysClient, config: &BlockchainConfig, timestamp_ms: u64) -> Result<u64> {
+    let mut low = 0u64;
+    let mut high = client.read_api().get_latest_checkpoint_sequence_number().await?;
+    
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        let checkpoint = fetch_checkpoint(client, config, mid).await?;
+        if checkpoint.timestamp_ms <= timestamp_ms {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    
+    Ok(low)
+}
+
 /// Allow cloning BlockchainEvent
 impl Clone for BlockchainEvent {
     fn clone(&self) -> Self {
@@ -333,8 +807,291 @@ impl Clone for BlockchainEvent {
             tx_digest: self.tx_digest.clone(),
             event_id: self.event_id.clone(),
             event_type: self.event_type.clone(),
+            package_address: self.package_address.clone(),
+            module_name: self.module_name.clone(),
+            struct_name: self.struct_name.clone(),
+            sender: self.sender.clone(),
             data: self.data.clone(),
             timestamp_ms: self.timestamp_ms,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn event(package_address: &str, data: serde_json::Value) -> BlockchainEvent {
+        BlockchainEvent {
+            tx_digest: "digest".to_string(),
+            event_id: "digest:0".to_string(),
+            event_type: format!("{}::profile::ProfileCreatedEvent", package_address),
+            package_address: package_address.to_string(),
+            module_name: "profile".to_string(),
+            struct_name: "ProfileCreatedEvent".to_string(),
+            sender: "0x1".to_string(),
+            data,
+            timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn rpc_events_carry_their_parsed_type_and_sender() {
+        let rpc_event: MysEvent = serde_json::from_value(serde_json::json!({
+            "id": { "txDigest": "11111111111111111111111111111111", "eventSeq": "3" },
+            "packageId": "0x2",
+            "transactionModule": "profile",
+            "sender": "0x00000000000000000000000000000000000000000000000000000000000000ab",
+            "type": "0x2::profile::ProfileCreatedEvent",
+            "parsedJson": { "profile_id": "0x5" },
+            "bcsEncoding": "base64",
+            "bcs": "",
+            "timestampMs": "1700000000000"
+        }))
+        .unwrap();
+        
+        let event = to_blockchain_event(&rpc_event, rpc_event.parsed_json.clone(), 1_700_000_000_000);
+        
+        assert_eq!(event.tx_digest, "11111111111111111111111111111111");
+        assert_eq!(event.event_id, "11111111111111111111111111111111:3");
+        assert_eq!(event.package_address, "0x2");
+        assert_eq!(event.module_name, "profile");
+        assert_eq!(event.struct_name, "ProfileCreatedEvent");
+        assert_eq!(event.sender, "0x00000000000000000000000000000000000000000000000000000000000000ab");
+        assert_eq!(event.data["profile_id"], "0x5");
+        assert_eq!(event.timestamp_ms, 1_700_000_000_000);
+    }
+
+    fn handler(worker_id: &str, capacity: usize) -> (HandlerChannel, mpsc::Receiver<BlockchainEvent>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (HandlerChannel { worker_id: worker_id.to_string(), sender }, receiver)
+    }
+
+    #[tokio::test]
+    async fn a_stuck_handler_times_out_without_holding_back_the_others() {
+        let (fast, mut fast_rx) = handler("fast", 1);
+        let (stuck, _stuck_rx) = handler("stuck", 1);
+        stuck.sender.try_send(event("0x1", serde_json::json!({}))).unwrap();
+        
+        let undelivered = fan_out(&[stuck, fast], &event("0x1", serde_json::json!({})), Duration::from_millis(20)).await;
+        
+        assert_eq!(undelivered, vec![("stuck".to_string(), "timeout")]);
+        assert!(fast_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn a_slow_handler_gets_the_event_once_it_makes_room() {
+        let (slow, mut slow_rx) = handler("slow", 1);
+        slow.sender.try_send(event("0x1", serde_json::json!({}))).unwrap();
+        let consumer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let first = slow_rx.recv().await;
+            let second = slow_rx.recv().await;
+            (first.is_some(), second.is_some())
+        });
+        
+        let undelivered = fan_out(&[slow], &event("0x1", serde_json::json!({})), Duration::from_secs(5)).await;
+        
+        assert!(undelivered.is_empty());
+        assert_eq!(consumer.await.unwrap(), (true, true));
+    }
+
+    #[tokio::test]
+    async fn a_stopped_handler_is_reported_closed() {
+        let (stopped, stopped_rx) = handler("stopped", 1);
+        drop(stopped_rx);
+        
+        let undelivered = fan_out(&[stopped], &event("0x1", serde_json::json!({})), Duration::from_millis(20)).await;
+        
+        assert_eq!(undelivered, vec![("stopped".to_string(), "closed")]);
+    }
+
+    #[test]
+    fn oversized_events_from_other_packages_are_ignored_not_dead_lettered() {
+        let mut unmonitored = event("0x2", serde_json::json!({ "bio": "x".repeat(100) }));
+        assert_eq!(screen_event(&mut unmonitored, 10), Screened::Unmonitored);
+        
+        let mut not_an_object = event("0x2", serde_json::json!([1, 2]));
+        assert_eq!(screen_event(&mut not_an_object, 10), Screened::Unmonitored);
+    }
+
+    #[test]
+    fn oversized_events_from_the_monitored_package_are_rejected() {
+        let mut oversized = event(crate::DEFAULT_MYSOCIAL_PACKAGE_ADDRESS, serde_json::json!({ "bio": "x".repeat(100) }));
+        assert!(matches!(
+            screen_event(&mut oversized, 10),
+            Screened::Malformed(MalformedEventData::TooLarge { limit: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn monitored_events_are_sanitized() {
+        let mut with_nul = event(crate::DEFAULT_MYSOCIAL_PACKAGE_ADDRESS, serde_json::json!({ "bio": "a\u{0}b" }));
+        assert_eq!(screen_event(&mut with_nul, 0), Screened::Ready(1));
+        assert_eq!(with_nul.data["bio"], "ab");
+    }
+
+    fn rank(event: &(&str, &str)) -> u8 {
+        intra_transaction_rank(event.1)
+    }
+
+    #[test]
+    fn a_create_delivered_second_is_applied_first() {
+        let mut events = vec![
+            ("tx1", "UsernameRegisteredEvent"),
+            ("tx1", "ProfileCreatedEvent"),
+        ];
+        sort_within_groups(&mut events, |event| event.0, rank);
+        assert_eq!(events, vec![("tx1", "ProfileCreatedEvent"), ("tx1", "UsernameRegisteredEvent")]);
+    }
+
+    #[test]
+    fn transactions_keep_their_order_and_only_sort_internally() {
+        let mut events = vec![
+            ("tx1", "FollowEvent"),
+            ("tx2", "UsernameRegisteredEvent"),
+            ("tx2", "FollowEvent"),
+            ("tx2", "ProfileCreatedEvent"),
+            ("tx3", "PlatformCreatedEvent"),
+        ];
+        sort_within_groups(&mut events, |event| event.0, rank);
+        assert_eq!(events, vec![
+            ("tx1", "FollowEvent"),
+            ("tx2", "ProfileCreatedEvent"),
+            ("tx2", "UsernameRegisteredEvent"),
+            ("tx2", "FollowEvent"),
+            ("tx3", "PlatformCreatedEvent"),
+        ]);
+    }
+
+    #[test]
+    fn trailing_group_covers_the_last_transaction() {
+        let events = [("tx1", "a"), ("tx2", "b"), ("tx2", "c")];
+        assert_eq!(trailing_group_len(&events, |event| event.0), 2);
+        let none: [(&str, &str); 0] = [];
+        assert_eq!(trailing_group_len(&none, |event| event.0), 0);
+    }
+
+    #[test]
+    fn a_full_page_with_more_behind_it_is_not_caught_up() {
+        assert!(!poll_caught_up(false, true));
+    }
+
+    #[test]
+    fn reaching_the_cutoff_checkpoint_or_the_tip_is_caught_up() {
+        assert!(poll_caught_up(true, true));
+        assert!(poll_caught_up(false, false));
+    }
+
+    #[test]
+    fn polls_flip_the_catch_up_flag() {
+        let status = CatchUpStatus::new();
+        assert!(!status.is_caught_up());
+        
+        assert!(status.update(poll_caught_up(false, false)));
+        assert!(status.is_caught_up());
+        assert!(!status.update(poll_caught_up(true, true)), "staying caught up is not a transition");
+        
+        assert!(status.update(poll_caught_up(false, true)));
+        assert!(!status.is_caught_up());
+    }
+
+    #[test]
+    fn everything_is_ready_without_a_cutoff() {
+        let timestamps = [Some(10), None, Some(30)];
+        assert_eq!(ready_prefix_len(timestamps.into_iter(), None), 3);
+    }
+
+    #[test]
+    fn events_past_the_cutoff_hold_back_the_rest_of_the_page() {
+        // The event at 25 is final but comes after one that isn't, so it waits too
+        let timestamps = [Some(10), Some(20), Some(30), Some(25)];
+        assert_eq!(ready_prefix_len(timestamps.into_iter(), Some(20)), 2);
+    }
+
+    #[test]
+    fn an_event_without_a_timestamp_is_not_final() {
+        let timestamps = [Some(10), None, Some(15)];
+        assert_eq!(ready_prefix_len(timestamps.into_iter(), Some(20)), 1);
+    }
+
+    #[test]
+    fn a_replay_window_of_two_covers_the_resume_checkpoint_and_the_one_before() {
+        assert_eq!((replay_window_start(10, 2)..=10).collect::<Vec<_>>(), vec![9, 10]);
+        assert_eq!(replay_window_start(10, 1), 10);
+        // A window longer than the chain starts at genesis
+        assert_eq!(replay_window_start(1, 5), 0);
+    }
+
+    #[tokio::test]
+    async fn a_flaky_fetch_succeeds_within_its_retries() {
+        let attempts = &AtomicU32::new(0);
+        let fetch = || async move {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(anyhow::anyhow!("connection reset")),
+                n => Ok(n),
+            }
+        };
+        
+        let result = retry_with_timeout(fetch, 2, Duration::from_secs(1), Duration::from_millis(1)).await;
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn a_fetch_gives_up_once_its_retries_run_out() {
+        let attempts = &AtomicU32::new(0);
+        let fetch = || async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(anyhow::anyhow!("connection reset"))
+        };
+        
+        assert!(retry_with_timeout(fetch, 1, Duration::from_secs(1), Duration::from_millis(1)).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn a_hung_fetch_times_out_and_is_retried() {
+        let attempts = &AtomicU32::new(0);
+        let fetch = || async move {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                std::future::pending::<()>().await;
+            }
+            Ok("checkpoint")
+        };
+        
+        let result = retry_with_timeout(fetch, 1, Duration::from_millis(20), Duration::from_millis(1)).await;
+        assert_eq!(result.unwrap(), "checkpoint");
+    }
+
+    #[test]
+    fn polling_starts_at_the_newest_event_without_a_lag() {
+        assert_eq!(first_final_index([Some(30), Some(20)].into_iter(), None), Some(0));
+        assert_eq!(first_final_index(std::iter::empty(), None), None);
+    }
+
+    #[test]
+    fn an_in_window_event_present_at_startup_is_indexed_later() {
+        // Newest first, as the startup query returns them; 30 is inside the lag window
+        let at_startup = [Some(30), Some(20), Some(10)];
+        let start = first_final_index(at_startup.into_iter(), Some(20)).unwrap();
+        assert_eq!(start, 1, "the cursor starts behind the in-window event");
+        
+        // Polling pages forward from the cursor, so the event at 30 is the next one seen and
+        // is indexed once the cutoff has moved past it
+        let after_cursor = [Some(30)];
+        assert_eq!(ready_prefix_len(after_cursor.into_iter(), Some(20)), 0);
+        assert_eq!(ready_prefix_len(after_cursor.into_iter(), Some(30)), 1);
+    }
+
+    #[test]
+    fn no_final_event_at_startup_starts_from_the_first_event() {
+        assert_eq!(first_final_index([Some(30), None].into_iter(), Some(20)), None);
+    }
+
+    #[test]
+    fn nothing_is_ready_when_the_first_event_is_too_recent() {
+        let timestamps = [Some(21), Some(22)];
+        assert_eq!(ready_prefix_len(timestamps.into_iter(), Some(20)), 0);
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use anyhow::Result;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::sql_types::{BigInt, Nullable, Text, Timestamp};
+use diesel::{OptionalExtension, QueryableByName};
+use diesel_async::RunQueryDsl;
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info, warn};
+
+use crate::db::Database;
+
+/// A platform whose stored counts disagreed with its memberships
+#[derive(Debug, QueryableByName)]
+pub struct CountCorrection {
+    #[diesel(sql_type = Text)]
+    pub platform_id: String,
+    #[diesel(sql_type = BigInt)]
+    pub old_total: i64,
+    #[diesel(sql_type = BigInt)]
+    pub new_total: i64,
+    #[diesel(sql_type = BigInt)]
+    pub old_active: i64,
+    #[diesel(sql_type = BigInt)]
+    pub new_active: i64,
+}
+
+/// Recompute `total_users_count` and `active_users_count` for platforms touched since `since`
+/// (all platforms when `None`), writing absolute values and returning the rows that changed
+pub async fn reconcile_platform_counts(
+    conn: &mut diesel_async::AsyncPgConnection,
+    since: Option<NaiveDateTime>,
+) -> Result<Vec<CountCorrection>, diesel::result::Error> {
+    // The FROM snapshot of platforms is taken before the update, so it still holds the old counts
+    diesel::sql_query(
+        "WITH touched AS (
+             SELECT platform_id FROM platforms WHERE $1 IS NULL OR updated_at >= $1
+             UNION SELECT platform_id FROM platform_events WHERE created_at >= $1
+             UNION SELECT platform_id FROM platform_memberships WHERE joined_at >= $1
+             UNION SELECT platform_id FROM platform_daily_active_profiles WHERE date >= DATE($1)
+         ),
+         counts AS (
+             SELECT t.platform_id,
+                    (SELECT COUNT(*) FROM platform_memberships m
+                     WHERE m.platform_id = t.platform_id) AS total,
+                    (SELECT COUNT(DISTINCT a.profile_id) FROM platform_daily_active_profiles a
+                     WHERE a.platform_id = t.platform_id AND a.date > CURRENT_DATE - 30) AS active
+             FROM touched t
+         )
+         UPDATE platforms p
+         SET total_users_count = c.total, active_users_count = c.active
+         FROM counts c, (SELECT id, total_users_count, active_users_count FROM platforms) old
+         WHERE c.platform_id = p.platform_id
+           AND old.id = p.id
+           AND (p.total_users_count <> c.total OR p.active_users_count <> c.active)
+         RETURNING p.platform_id,
+                   old.total_users_count AS old_total, c.total AS new_total,
+                   old.active_users_count AS old_active, c.active AS new_active"
+    )
+    .bind::<Nullable<Timestamp>, _>(since)
+    .load::<CountCorrection>(conn)
+    .await
+}
+
+#[derive(QueryableByName)]
+struct LatestEventTime {
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    latest: Option<NaiveDateTime>,
+}
+
+/// Time of the newest membership change or platform activity indexed so far
+///
+/// Events are indexed in chain order, so anything indexed later is at least this new even when
+/// the indexer lags the chain.
+pub async fn latest_event_time(conn: &mut diesel_async::AsyncPgConnection) -> Result<Option<NaiveDateTime>, diesel::result::Error> {
+    diesel::sql_query(
+        "SELECT GREATEST(
+             (SELECT MAX(created_at) FROM platform_events),
+             (SELECT MAX(joined_at) FROM platform_memberships),
+             (SELECT MAX(date)::timestamp FROM platform_daily_active_profiles)
+         ) AS latest"
+    )
+    .get_result::<LatestEventTime>(conn)
+    .await
+    .optional()
+    .map(|row| row.and_then(|row| row.latest))
+}
+
+/// Where a pass starts: every platform on the first pass of each day, so the 30-day active
+/// count moves on for idle platforms too, otherwise events at or after `watermark`
+fn pass_since(
+    watermark: Option<NaiveDateTime>,
+    last_full_pass: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Option<NaiveDateTime> {
+    if last_full_pass == Some(today) {
+        watermark
+    } else {
+        None
+    }
+}
+
+/// Periodically reconcile platform member counts against `platform_memberships`
+///
+/// The first pass each day covers every platform; later passes only platforms with events at
+/// least as new as the newest one the previous pass saw. Event time is used rather than the
+/// wall clock so events indexed late are still picked up.
+pub fn spawn_membership_reconciler(db: Arc<Database>, every: Duration) -> tokio::task::JoinHandle<()> {
+    crate::tasks::spawn_named("membership-reconciler", async move {
+        let mut ticker = interval(every);
+        let mut watermark: Option<NaiveDateTime> = None;
+        let mut last_full_pass: Option<NaiveDate> = None;
+        loop {
+            ticker.tick().await;
+            let today = chrono::Utc::now().date_naive();
+            let since = pass_since(watermark, last_full_pass, today);
+            
+            let mut conn = match db.get_connection().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    error!("Membership reconciliation skipped, no database connection: {}", e);
+                    continue;
+                }
+            };
+            
+            // Taken before the pass, so events indexed during it are covered by the next one
+            let next_watermark = match latest_event_time(&mut conn).await {
+                Ok(latest) => latest,
+                Err(e) => {
+                    error!("Membership reconciliation skipped, can't read the latest event time: {}", e);
+                    continue;
+                }
+            };
+            
+            match reconcile_platform_counts(&mut conn, since).await {
+                Ok(corrections) => {
+                    for c in &corrections {
+                        warn!(
+                            "Corrected member counts for platform {}: total {} -> {}, active {} -> {}",
+                            c.platform_id, c.old_total, c.new_total, c.old_active, c.new_active
+                        );
+                    }
+                    if corrections.is_empty() {
+                        debug!("Membership reconciliation found no drift");
+                    } else {
+                        info!("Membership reconciliation corrected {} platforms", corrections.len());
+                    }
+                    watermark = next_watermark;
+                    if since.is_none() {
+                        last_full_pass = Some(today);
+                    }
+                }
+                Err(e) => error!("Membership reconciliation failed: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::prelude::*;
+    use crate::db::test_support::{insert_platform, test_database, unique_id};
+    use crate::schema::{platform_memberships, platforms};
+
+    fn at(date: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} 12:00:00", date), "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn first_pass_of_each_day_covers_every_platform() {
+        let today = NaiveDate::from_ymd_opt(2025, 5, 2).unwrap();
+        let yesterday = NaiveDate::from_ymd_opt(2025, 5, 1).unwrap();
+        let watermark = Some(at("2025-05-01"));
+        
+        assert_eq!(pass_since(None, None, today), None);
+        assert_eq!(pass_since(watermark, Some(yesterday), today), None);
+        assert_eq!(pass_since(watermark, Some(today), today), watermark);
+    }
+
+    async fn counts(conn: &mut diesel_async::AsyncPgConnection, platform_id: &str) -> (i64, i64) {
+        platforms::table
+            .filter(platforms::platform_id.eq(platform_id))
+            .select((platforms::total_users_count, platforms::active_users_count))
+            .first(conn)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn wrong_counts_are_corrected() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let platform_id = unique_id("platform");
+        insert_platform(&mut conn, &platform_id).await;
+        diesel::insert_into(platform_memberships::table)
+            .values((
+                platform_memberships::platform_id.eq(&platform_id),
+                platform_memberships::profile_id.eq(unique_id("profile")),
+                platform_memberships::joined_at.eq(at("2025-05-01")),
+            ))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        diesel::update(platforms::table.filter(platforms::platform_id.eq(&platform_id)))
+            .set(platforms::total_users_count.eq(42))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        
+        let corrections = reconcile_platform_counts(&mut conn, None).await.unwrap();
+        
+        let correction = corrections.iter().find(|c| c.platform_id == platform_id).unwrap();
+        assert_eq!((correction.old_total, correction.new_total), (42, 1));
+        assert_eq!(counts(&mut conn, &platform_id).await, (1, 0));
+    }
+
+    #[tokio::test]
+    async fn lagged_join_after_the_watermark_is_picked_up() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let platform_id = unique_id("platform");
+        insert_platform(&mut conn, &platform_id).await;
+        // Indexed now, but the join happened on chain long before the wall clock
+        diesel::update(platforms::table.filter(platforms::platform_id.eq(&platform_id)))
+            .set(platforms::updated_at.eq(at("2020-01-01")))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        diesel::insert_into(platform_memberships::table)
+            .values((
+                platform_memberships::platform_id.eq(&platform_id),
+                platform_memberships::profile_id.eq(unique_id("profile")),
+                platform_memberships::joined_at.eq(at("2020-01-02")),
+            ))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        
+        reconcile_platform_counts(&mut conn, Some(at("2020-01-02"))).await.unwrap();
+        
+        assert_eq!(counts(&mut conn, &platform_id).await.0, 1);
+    }
+}
@@ -6,9 +6,24 @@ mod listener;
 mod social_graph_handler;
 mod platform_handler;
 mod block_list_handler;
+mod content_handler;
+mod dead_letter;
+mod membership_reconciler;
+mod follower_snapshots;
+mod daily_statistics;
+mod ignored;
+mod catch_up;
+pub mod store;
+pub mod publisher;
 
 pub use events::ProfileEventListener;
-pub use listener::BlockchainEventListener;
+pub use listener::{BlockchainEvent, BlockchainEventListener};
 pub use social_graph_handler::SocialGraphEventHandler;
 pub use platform_handler::PlatformEventHandler;
-pub use block_list_handler::BlockListEventHandler;
\ No newline at end of file
+pub use block_list_handler::BlockListEventHandler;
+pub use content_handler::ContentEventHandler;
+pub use membership_reconciler::{reconcile_platform_counts, spawn_membership_reconciler, CountCorrection};
+pub use follower_snapshots::{snapshot_follower_counts, spawn_follower_snapshotter};
+pub use daily_statistics::{recompute_daily_statistics, RecomputedStatistics};
+pub use catch_up::CatchUpStatus;
+pub use ignored::{is_ignored_address, set_ignored_addresses};
@@ -1,10 +1,12 @@
 // Copyright (c) MySocial Team
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use anyhow::{anyhow, Result};
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
+use futures::stream::{FuturesUnordered, StreamExt};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 // Serde json utilities
@@ -14,8 +16,74 @@ use crate::events::platform_events::PlatformEventType;
 use crate::models::platform::*;
 use crate::schema;
 
+use super::dead_letter::record_dead_letter;
+use super::publisher::{publish_applied, EventPublisher, NoopPublisher};
+use super::ignored::skip_ignored;
 use super::listener::BlockchainEvent;
 
+/// Resolve the numeric `platforms.id` for a platform, used as the child tables' `platform_ref`
+async fn resolve_platform_ref(
+    conn: &mut diesel_async::AsyncPgConnection,
+    platform_id: &str,
+) -> Result<Option<i32>, diesel::result::Error> {
+    schema::platforms::table
+        .filter(schema::platforms::platform_id.eq(platform_id))
+        .select(schema::platforms::id)
+        .first::<i32>(conn)
+        .await
+        .optional()
+}
+
+/// Record that a profile was active on a platform at `at`
+///
+/// Repeated activity on the same day is a no-op, so daily active counts stay distinct. Also
+/// moves the profile's `last_activity_at` forward.
+pub(crate) async fn record_platform_activity(
+    conn: &mut diesel_async::AsyncPgConnection,
+    platform_id: &str,
+    profile_id: &str,
+    at: chrono::NaiveDateTime,
+) -> Result<(), diesel::result::Error> {
+    diesel::insert_into(schema::platform_daily_active_profiles::table)
+        .values((
+            schema::platform_daily_active_profiles::platform_id.eq(platform_id),
+            schema::platform_daily_active_profiles::date.eq(at.date()),
+            schema::platform_daily_active_profiles::profile_id.eq(profile_id),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .await?;
+    crate::db::touch_profile_activity(conn, profile_id, at).await?;
+    Ok(())
+}
+
+/// Point child rows recorded before their platform existed at the platform's numeric id
+async fn backfill_platform_refs(
+    conn: &mut diesel_async::AsyncPgConnection,
+    platform_id: &str,
+    platform_ref: i32,
+) -> Result<(), diesel::result::Error> {
+    diesel::update(schema::platform_memberships::table
+        .filter(schema::platform_memberships::platform_id.eq(platform_id))
+        .filter(schema::platform_memberships::platform_ref.is_null()))
+        .set(schema::platform_memberships::platform_ref.eq(platform_ref))
+        .execute(conn)
+        .await?;
+    diesel::update(schema::platform_moderators::table
+        .filter(schema::platform_moderators::platform_id.eq(platform_id))
+        .filter(schema::platform_moderators::platform_ref.is_null()))
+        .set(schema::platform_moderators::platform_ref.eq(platform_ref))
+        .execute(conn)
+        .await?;
+    diesel::update(schema::platform_blocked_profiles::table
+        .filter(schema::platform_blocked_profiles::platform_id.eq(platform_id))
+        .filter(schema::platform_blocked_profiles::platform_ref.is_null()))
+        .set(schema::platform_blocked_profiles::platform_ref.eq(platform_ref))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
 // Helper functions for extracting fields from blockchain events
 fn extract_string_field(data: &serde_json::Value, field_name: &str) -> String {
     // Try direct access
@@ -79,60 +147,44 @@ fn extract_string_array(data: &serde_json::Value, field_name: &str) -> Vec<Strin
     Vec::new()
 }
 
-fn extract_number_field(data: &serde_json::Value, field_name: &str) -> Option<u8> {
-    // Try direct access
-    if let Some(value) = data.get(field_name) {
-        if let Some(n) = value.as_u64() {
-            return Some(n as u8);
-        }
-    }
-    
-    // Try nested fields
-    if field_name.contains('.') {
-        let parts: Vec<&str> = field_name.split('.').collect();
-        let mut current = data;
-        
-        for part in parts {
-            if let Some(next) = current.get(part) {
-                current = next;
-            } else {
-                return None;
-            }
-        }
-        
-        if let Some(n) = current.as_u64() {
-            return Some(n as u8);
-        }
-    }
-    
-    // Try as string
-    let str_val = extract_string_field(data, field_name);
-    if !str_val.is_empty() {
-        if let Ok(n) = str_val.parse::<u8>() {
-            return Some(n);
-        }
-    }
-    
-    None
-}
-
 /// Handler for platform-related blockchain events
 pub struct PlatformEventHandler {
     /// Database connection
     db: Arc<Database>,
     /// Event receiver channel
     rx: mpsc::Receiver<BlockchainEvent>,
+    /// Worker ID for recording failed events
+    worker_id: String,
+    /// Publisher for events this handler has applied
+    publisher: Arc<dyn EventPublisher>,
+    /// Most events processed at once; events for the same platform still run one at a time
+    concurrency: usize,
 }
 
 impl PlatformEventHandler {
     /// Create a new platform event handler
-    pub fn new(db: Arc<Database>, rx: mpsc::Receiver<BlockchainEvent>, _worker_id: String) -> Self {
+    pub fn new(db: Arc<Database>, rx: mpsc::Receiver<BlockchainEvent>, worker_id: String) -> Self {
         Self {
             db,
             rx,
+            worker_id,
+            publisher: Arc::new(NoopPublisher),
+            concurrency: 1,
         }
     }
     
+    /// Process up to `concurrency` events for different platforms at once (at least 1)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+    
+    /// Publish the platform events this handler applies
+    pub fn with_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = publisher;
+        self
+    }
+    
     /// Get a database connection from the pool
     async fn get_connection(&self) -> Result<DbConnection> {
         self.db.get_connection()
@@ -188,14 +240,14 @@ impl PlatformEventHandler {
                     let platform_update = UpdatePlatform {
                         name: Some(event.name.clone()),
                         tagline: Some(event.tagline.clone()),
-                        description: event.description.clone(), // Use the description from the event
-                        logo: event.logo.clone(), // Use the logo from the event
-                        terms_of_service: Some(event.terms_of_service.clone()),
-                        privacy_policy: Some(event.privacy_policy.clone()),
+                        description: event.description.clone().map(Some), // Use the description from the event
+                        logo: event.logo.clone().map(Some), // Use the logo from the event
+                        terms_of_service: Some(Some(event.terms_of_service.clone())),
+                        privacy_policy: Some(Some(event.privacy_policy.clone())),
                         platform_names: Some(serde_json::to_value(&event.platforms).unwrap_or_default()),
                         links: Some(serde_json::to_value(&event.links).unwrap_or_default()),
                         status: Some(event.status.status as i16),
-                        release_date: Some(event.release_date.clone()),
+                        release_date: Some(Some(event.release_date.clone())),
                         shutdown_date: None,
                         updated_at: Some(chrono::DateTime::from_timestamp(now.as_secs() as i64, 0)
                             .unwrap_or_else(|| chrono::Utc::now())
@@ -246,6 +298,10 @@ impl PlatformEventHandler {
                         .await?;
                     
                     // Add developer as a moderator
+                    let platform_ref = resolve_platform_ref(&mut conn, &event.platform_id).await?;
+                    if let Some(platform_ref) = platform_ref {
+                        backfill_platform_refs(&mut conn, &event.platform_id, platform_ref).await?;
+                    }
                     let new_moderator = NewPlatformModerator {
                         platform_id: event.platform_id.clone(),
                         moderator_address: event.developer.clone(),
@@ -253,6 +309,7 @@ impl PlatformEventHandler {
                         created_at: chrono::DateTime::from_timestamp(now.as_secs() as i64, 0)
                             .unwrap_or_else(|| chrono::Utc::now())
                             .naive_utc(),
+                        platform_ref,
                     };
                     
                     // Insert developer as moderator
@@ -322,25 +379,8 @@ impl PlatformEventHandler {
                 
                 if platform_exists {
                     // Update existing platform
-                    let platform_update = UpdatePlatform {
-                        name: Some(event.name.clone()),
-                        tagline: Some(event.tagline.clone()),
-                        description: Some(event.description.clone()),
-                        logo: None, // Not in updated event
-                        terms_of_service: Some(event.terms_of_service.clone()),
-                        privacy_policy: Some(event.privacy_policy.clone()),
-                        platform_names: Some(serde_json::to_value(&event.platforms).unwrap_or_default()),
-                        links: Some(serde_json::to_value(&event.links).unwrap_or_default()),
-                        status: Some(event.status.status as i16),
-                        release_date: Some(event.release_date.clone()),
-                        shutdown_date: event.shutdown_date.clone().map(Some).unwrap_or(None),
-                        updated_at: Some(chrono::DateTime::from_timestamp(event.updated_at as i64, 0)
-                            .unwrap_or_else(|| chrono::Utc::now())
-                            .naive_utc()),
-                        is_approved: None, // Don't change approval status on regular update
-                        approval_changed_at: None, // Don't change approval timestamp
-                        approved_by: None, // Don't change approver
-                    };
+                    // Only the fields the event carries are changed
+                    let platform_update = event.into_update();
                     
                     diesel::update(schema::platforms::table)
                         .filter(schema::platforms::platform_id.eq(&event.platform_id))
@@ -355,26 +395,25 @@ impl PlatformEventHandler {
                     
                     // Create platform with limited information from update event
                     // (we don't have developer info in the update event)
+                    let update = event.into_update();
                     let new_platform = NewPlatform {
                         platform_id: event.platform_id.clone(),
-                        name: event.name.clone(),
-                        tagline: event.tagline.clone(),
-                        description: Some(event.description.clone()),
-                        logo: None,
+                        name: update.name.unwrap_or_default(),
+                        tagline: update.tagline.unwrap_or_default(),
+                        description: update.description.flatten(),
+                        logo: update.logo.flatten(),
                         developer_address: "unknown".to_string(), // We don't have this info
-                        terms_of_service: Some(event.terms_of_service.clone()),
-                        privacy_policy: Some(event.privacy_policy.clone()),
-                        platform_names: Some(serde_json::to_value(&event.platforms).unwrap_or_default()),
-                        links: Some(serde_json::to_value(&event.links).unwrap_or_default()),
-                        status: event.status.status as i16,
-                        release_date: Some(event.release_date.clone()),
-                        shutdown_date: event.shutdown_date.clone().map(Some).unwrap_or(None),
+                        terms_of_service: update.terms_of_service.flatten(),
+                        privacy_policy: update.privacy_policy.flatten(),
+                        platform_names: update.platform_names,
+                        links: update.links,
+                        status: update.status.unwrap_or(0),
+                        release_date: update.release_date.flatten(),
+                        shutdown_date: update.shutdown_date.flatten(),
                         created_at: chrono::DateTime::from_timestamp(now.as_secs() as i64, 0)
                             .unwrap_or_else(|| chrono::Utc::now())
                             .naive_utc(),
-                        updated_at: chrono::DateTime::from_timestamp(event.updated_at as i64, 0)
-                            .unwrap_or_else(|| chrono::Utc::now())
-                            .naive_utc(),
+                        updated_at: update.updated_at.unwrap_or_else(|| chrono::Utc::now().naive_utc()),
                         is_approved: false, // New platforms are not approved by default
                         approval_changed_at: None, // No approval change yet
                         approved_by: None, // No approver yet
@@ -477,6 +516,7 @@ impl PlatformEventHandler {
                 }
                 
                 // Add moderator to platform
+                let platform_ref = resolve_platform_ref(&mut conn, &event.platform_id).await?;
                 let new_moderator = NewPlatformModerator {
                     platform_id: event.platform_id.clone(),
                     moderator_address: event.moderator_address.clone(),
@@ -484,6 +524,7 @@ impl PlatformEventHandler {
                     created_at: chrono::DateTime::from_timestamp(now.as_secs() as i64, 0)
                         .unwrap_or_else(|| chrono::Utc::now())
                         .naive_utc(),
+                    platform_ref,
                 };
                 
                 // Insert moderator with conflict handling
@@ -596,53 +637,21 @@ impl PlatformEventHandler {
                     .execute(&mut conn)
                     .await?;
                 
-                // Check if this platform-profile relationship already exists
-                let existing_relationship = schema::platform_blocked_profiles::table
-                    .filter(schema::platform_blocked_profiles::platform_id.eq(&event.platform_id))
-                    .filter(schema::platform_blocked_profiles::profile_id.eq(&event.profile_id))
-                    .first::<PlatformBlockedProfile>(&mut conn)
-                    .await;
-                
-                match existing_relationship {
-                    Ok(_) => {
-                        // Delete the existing record - we'll insert a new one to reset the timestamps
-                        diesel::delete(schema::platform_blocked_profiles::table)
-                            .filter(schema::platform_blocked_profiles::platform_id.eq(&event.platform_id))
-                            .filter(schema::platform_blocked_profiles::profile_id.eq(&event.profile_id))
-                            .execute(&mut conn)
-                            .await?;
-                        
-                        info!("Deleted existing block relationship to refresh timestamp");
-                        
-                        // Create new blocked profile relationship below
-                    },
-                    Err(diesel::result::Error::NotFound) => {
-                        // No existing relationship - we'll create a new one
-                    },
-                    Err(e) => {
-                        error!("Error checking for existing block relationship: {}", e);
-                        return Err(e);
-                    }
-                }
-                
-                // Create new blocked profile relationship
-                let new_blocked_profile = (
-                    schema::platform_blocked_profiles::platform_id.eq(event.platform_id.clone()),
-                    schema::platform_blocked_profiles::profile_id.eq(event.profile_id.clone()),
-                    schema::platform_blocked_profiles::blocked_by.eq(event.blocked_by.clone()),
-                    schema::platform_blocked_profiles::created_at.eq(
-                        chrono::DateTime::from_timestamp(now.as_secs() as i64, 0)
-                            .unwrap_or_else(|| chrono::Utc::now())
-                            .naive_utc()
-                    )
-                );
+                // Create the block, or refresh the timestamp of an existing one
+                let platform_ref = resolve_platform_ref(&mut conn, &event.platform_id).await?;
+                let new_blocked_profile = NewPlatformBlockedProfile {
+                    platform_id: event.platform_id.clone(),
+                    profile_id: event.profile_id.clone(),
+                    blocked_by: event.blocked_by.clone(),
+                    created_at: chrono::DateTime::from_timestamp(now.as_secs() as i64, 0)
+                        .unwrap_or_else(|| chrono::Utc::now())
+                        .naive_utc(),
+                    platform_ref,
+                };
                 
-                diesel::insert_into(schema::platform_blocked_profiles::table)
-                    .values(new_blocked_profile)
-                    .execute(&mut conn)
-                    .await?;
+                crate::db::upsert_platform_block(&mut conn, &new_blocked_profile).await?;
                 
-                info!("Created new blocked profile relationship: {} on platform {}", event.profile_id, event.platform_id);
+                info!("Recorded blocked profile relationship: {} on platform {}", event.profile_id, event.platform_id);
                 
                 Result::<_, diesel::result::Error>::Ok(())
             }))
@@ -776,14 +785,22 @@ impl PlatformEventHandler {
                         approved_by: Some(event.approved_by.clone()),
                     };
                     
-                    diesel::update(schema::platforms::table)
+                    // Only apply changes at least as new as the stored one, so a late older event can't win
+                    let updated = diesel::update(schema::platforms::table)
                         .filter(schema::platforms::platform_id.eq(&event.platform_id))
+                        .filter(schema::platforms::approval_changed_at.is_null()
+                            .or(schema::platforms::approval_changed_at.le(approval_changed_at)))
                         .set(&platform_update)
                         .execute(&mut conn)
                         .await?;
                     
-                    info!("Updated platform approval status: platform_id={}, is_approved={}", 
-                        event.platform_id, event.is_approved);
+                    if updated > 0 {
+                        info!("Updated platform approval status: platform_id={}, is_approved={}", 
+                            event.platform_id, event.is_approved);
+                    } else {
+                        warn!("Ignoring stale approval change for platform {}: changed_at {} is older than the stored change",
+                            event.platform_id, approval_changed_at);
+                    }
                 } else {
                     warn!("Platform not found for approval change: {}", event.platform_id);
                 }
@@ -832,18 +849,41 @@ impl PlatformEventHandler {
                     .await?;
                 
                 // Check if the platform is approved - only approved platforms can be joined
-                let platform_is_approved = schema::platforms::table
+                let (platform_is_approved, platform_status, shutdown_date) = schema::platforms::table
                     .filter(schema::platforms::platform_id.eq(&event.platform_id))
-                    .select(schema::platforms::is_approved)
-                    .first::<bool>(&mut conn)
+                    .select((
+                        schema::platforms::is_approved,
+                        schema::platforms::status,
+                        schema::platforms::shutdown_date,
+                    ))
+                    .first::<(bool, i16, Option<String>)>(&mut conn)
                     .await
-                    .unwrap_or(false);
+                    .unwrap_or((false, PLATFORM_STATUS_DEVELOPMENT, None));
                 
                 if !platform_is_approved {
                     warn!("Ignoring join event for non-approved platform: {}", event.platform_id);
                     return Ok(());
                 }
                 
+                // Platforms that are winding down no longer accept new members
+                if !platform_accepts_members(platform_status) {
+                    warn!("Ignoring join event for platform {} in status {}", 
+                        event.platform_id, PlatformWithDetails::status_to_text(platform_status));
+                    return Ok(());
+                }
+                
+                let join_time = chrono::DateTime::from_timestamp(event.timestamp as i64, 0)
+                    .unwrap_or_else(|| chrono::Utc::now())
+                    .naive_utc();
+                
+                if let Some(shutdown_at) = shutdown_date.as_deref().and_then(parse_platform_date) {
+                    if join_time >= shutdown_at {
+                        warn!("Ignoring join event for platform {} past its shutdown date {}", 
+                            event.platform_id, shutdown_at);
+                        return Ok(());
+                    }
+                }
+                
                 // Check if the profile is blocked by the platform
                 let profile_is_blocked = schema::platform_blocked_profiles::table
                     .filter(schema::platform_blocked_profiles::platform_id.eq(&event.platform_id))
@@ -858,6 +898,9 @@ impl PlatformEventHandler {
                     return Ok(());
                 }
                 
+                // Joining counts as activity on the platform for that day
+                record_platform_activity(&mut conn, &event.platform_id, &event.profile_id, join_time).await?;
+                
                 // Check if membership already exists
                 let membership_exists = schema::platform_memberships::table
                     .filter(schema::platform_memberships::platform_id.eq(&event.platform_id))
@@ -869,12 +912,12 @@ impl PlatformEventHandler {
                 
                 if !membership_exists {
                     // Create new membership
+                    let platform_ref = resolve_platform_ref(&mut conn, &event.platform_id).await?;
                     let new_membership = NewPlatformMembership {
                         platform_id: event.platform_id.clone(),
                         profile_id: event.profile_id.clone(),
-                        joined_at: chrono::DateTime::from_timestamp(event.timestamp as i64, 0)
-                            .unwrap_or_else(|| chrono::Utc::now())
-                            .naive_utc(),
+                        joined_at: join_time,
+                        platform_ref,
                     };
                     
                     // Insert membership
@@ -901,10 +944,7 @@ impl PlatformEventHandler {
                     );
                     
                     // Insert into profile events table
-                    diesel::insert_into(schema::profile_events::table)
-                        .values(&profile_event)
-                        .execute(&mut conn)
-                        .await?;
+                    crate::db::insert_profile_event(&mut conn, &profile_event).await?;
                     
                     info!("Created profile event for platform join: {} -> {}", event.profile_id, event.platform_id);
                 }
@@ -987,10 +1027,7 @@ impl PlatformEventHandler {
                     );
                     
                     // Insert into profile events table
-                    diesel::insert_into(schema::profile_events::table)
-                        .values(&profile_event)
-                        .execute(&mut conn)
-                        .await?;
+                    crate::db::insert_profile_event(&mut conn, &profile_event).await?;
                     
                     info!("Created profile event for platform leave: {} -> {}", event.profile_id, event.platform_id);
                 }
@@ -1023,6 +1060,10 @@ impl PlatformEventHandler {
         if let Some(event_type) = crate::events::platform_events::PlatformEventType::from_str(&event.event_type) {
             info!("Identified platform event type: {:?}", event_type);
             
+            if skip_ignored("platform", &event, &[]) {
+                return Ok(());
+            }
+            
             match event_type {
                 PlatformEventType::PlatformCreated => {
                     info!("Processing PlatformCreated event");
@@ -1032,6 +1073,9 @@ impl PlatformEventHandler {
                     // First try normal deserialization
                     match serde_json::from_value::<PlatformCreatedEvent>(event.data.clone()) {
                         Ok(platform_event) => {
+                            if skip_ignored("platform", &event, &[&platform_event.developer]) {
+                                return Ok(());
+                            }
                             self.process_platform_created_event(&platform_event, Some(&event)).await?;
                         },
                         Err(e) => {
@@ -1065,9 +1109,9 @@ impl PlatformEventHandler {
                                 privacy_policy: extract_string_field(&event.data, "privacy_policy"),
                                 platforms: extract_string_array(&event.data, "platforms"),
                                 links: extract_string_array(&event.data, "links"),
-                                status: PlatformStatus { 
-                                    status: extract_number_field(&event.data, "status.status").unwrap_or(0) 
-                                },
+                                status: event.data.get("status")
+                                    .and_then(|status| serde_json::from_value::<PlatformStatus>(status.clone()).ok())
+                                    .unwrap_or(PlatformStatus { status: 0 }),
                                 release_date: extract_string_field(&event.data, "release_date"),
                             };
                             
@@ -1137,19 +1181,349 @@ impl PlatformEventHandler {
         Ok(())
     }
     
-    /// Start listening for platform events
-    pub async fn start(&mut self) -> Result<()> {
-        info!("Starting platform event handler");
+    /// Process one event, publishing it on success and dead-lettering it on failure, and hand
+    /// back its platform key
+    async fn process_keyed(&self, key: String, event: BlockchainEvent) -> String {
+        debug!("Received event: {:?}", event.event_type);
         
-        while let Some(event) = self.rx.recv().await {
-            debug!("Received event: {:?}", event.event_type);
-            
-            if let Err(e) = self.process_event(event).await {
+        let applied_event = event.clone();
+        match self.process_event(event).await {
+            Ok(()) => publish_applied(self.publisher.as_ref(), crate::PLATFORM_MODULE_NAME, &applied_event).await,
+            Err(e) => {
                 error!("Error processing event: {}", e);
+                record_dead_letter(&self.db, &self.worker_id, &applied_event, &e).await;
             }
         }
+        key
+    }
+    
+    /// Start listening for platform events
+    ///
+    /// Up to `concurrency` events are held at once. An event whose platform already has one
+    /// in flight waits behind it, so each platform's events are applied in arrival order.
+    pub async fn start(&mut self) -> Result<()> {
+        info!("Starting platform event handler (concurrency {})", self.concurrency);
+        
+        // Take the receiver so in-flight events can borrow the handler while we keep receiving
+        let mut rx = std::mem::replace(&mut self.rx, mpsc::channel(1).1);
+        let handler = &*self;
+        
+        // Events without a platform id share the empty key and so stay in order too
+        process_in_key_order(
+            &mut rx,
+            handler.concurrency,
+            |event| extract_string_field(&event.data, "platform_id"),
+            |key, event| handler.process_keyed(key, event),
+        )
+        .await;
         
         warn!("Platform event handler channel closed");
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Run `process` on events from `rx` until it closes, at most `concurrency` at a time
+///
+/// Events with the same `key_of` are processed one after another in arrival order; `process`
+/// hands back the key of the event it finished. Queued events count toward `concurrency`.
+async fn process_in_key_order<E, Fut>(
+    rx: &mut mpsc::Receiver<E>,
+    concurrency: usize,
+    key_of: impl Fn(&E) -> String,
+    process: impl Fn(String, E) -> Fut,
+) where
+    Fut: std::future::Future<Output = String>,
+{
+    let mut in_flight = FuturesUnordered::new();
+    // An entry exists while the key has an event in flight; it holds the ones queued behind it
+    let mut waiting: HashMap<String, VecDeque<E>> = HashMap::new();
+    let mut buffered = 0;
+    let mut channel_open = true;
+    
+    loop {
+        tokio::select! {
+            event = rx.recv(), if channel_open && buffered < concurrency => {
+                let Some(event) = event else {
+                    channel_open = false;
+                    continue;
+                };
+                buffered += 1;
+                
+                let key = key_of(&event);
+                match waiting.get_mut(&key) {
+                    Some(queue) => queue.push_back(event),
+                    None => {
+                        waiting.insert(key.clone(), VecDeque::new());
+                        in_flight.push(process(key, event));
+                    }
+                }
+            }
+            Some(key) = in_flight.next(), if !in_flight.is_empty() => {
+                buffered -= 1;
+                match waiting.get_mut(&key).and_then(|queue| queue.pop_front()) {
+                    Some(next) => in_flight.push(process(key, next)),
+                    None => {
+                        waiting.remove(&key);
+                    }
+                }
+            }
+            else => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{insert_platform, test_database, unique_id};
+
+    /// Process `(key, name)` events, logging when each starts and ends; `slow` ones take longer
+    async fn run_in_key_order(events: &[(&str, &str)], slow: &[&str]) -> Vec<String> {
+        let log = std::sync::Mutex::new(Vec::new());
+        let (tx, mut rx) = mpsc::channel(events.len());
+        for (key, name) in events {
+            tx.send((key.to_string(), name.to_string())).await.unwrap();
+        }
+        drop(tx);
+        
+        process_in_key_order(&mut rx, 4, |(key, _)| key.clone(), |key, (_, name)| {
+            let log = &log;
+            async move {
+                log.lock().unwrap().push(format!("start {}", name));
+                let millis = if slow.contains(&name.as_str()) { 100 } else { 10 };
+                tokio::time::sleep(std::time::Duration::from_millis(millis)).await;
+                log.lock().unwrap().push(format!("end {}", name));
+                key
+            }
+        })
+        .await;
+        log.into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn platforms_run_concurrently_but_each_platform_stays_in_order() {
+        let log = run_in_key_order(&[("0xa", "a1"), ("0xb", "b1"), ("0xa", "a2")], &["a1"]).await;
+        let at = |entry: &str| log.iter().position(|logged| logged == entry).unwrap();
+        
+        // b1 starts and finishes while a1 is still running; a2 waits for a1
+        assert!(at("start a1") < at("end b1"));
+        assert!(at("end b1") < at("end a1"));
+        assert!(at("end a1") < at("start a2"));
+        assert_eq!(log.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn new_membership_references_the_platform_row() {
+        let Some(db) = test_database().await else { return };
+        let handler = PlatformEventHandler::new(db.clone(), mpsc::channel(1).1, "platform-test".to_string());
+        let platform_id = unique_id("platform");
+        let profile_id = unique_id("profile");
+        let platform_ref = {
+            let mut conn = db.get_connection().await.unwrap();
+            let id = insert_platform(&mut conn, &platform_id).await;
+            diesel::update(schema::platforms::table.find(id))
+                .set(schema::platforms::is_approved.eq(true))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            id
+        };
+        let join = UserJoinedPlatformEvent {
+            profile_id: profile_id.clone(),
+            platform_id: platform_id.clone(),
+            user: format!("{}owner", profile_id),
+            timestamp: chrono::Utc::now().timestamp() as u64,
+        };
+        
+        handler.process_user_joined_platform_event(&join, None).await.unwrap();
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let stored: Option<i32> = schema::platform_memberships::table
+            .filter(schema::platform_memberships::platform_id.eq(&platform_id))
+            .filter(schema::platform_memberships::profile_id.eq(&profile_id))
+            .select(schema::platform_memberships::platform_ref)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(stored, Some(platform_ref));
+    }
+
+    /// Join an approved platform in `status` with `shutdown_date`, returning the memberships stored
+    async fn join_winding_down_platform(status: i16, shutdown_date: Option<&str>) -> Option<i64> {
+        let db = test_database().await?;
+        let handler = PlatformEventHandler::new(db.clone(), mpsc::channel(1).1, "platform-test".to_string());
+        let platform_id = unique_id("platform");
+        let profile_id = unique_id("profile");
+        {
+            let mut conn = db.get_connection().await.unwrap();
+            let id = insert_platform(&mut conn, &platform_id).await;
+            diesel::update(schema::platforms::table.find(id))
+                .set((
+                    schema::platforms::is_approved.eq(true),
+                    schema::platforms::status.eq(status),
+                    schema::platforms::shutdown_date.eq(shutdown_date),
+                ))
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+        let join = UserJoinedPlatformEvent {
+            profile_id: profile_id.clone(),
+            platform_id: platform_id.clone(),
+            user: format!("{}owner", profile_id),
+            timestamp: chrono::Utc::now().timestamp() as u64,
+        };
+        
+        handler.process_user_joined_platform_event(&join, None).await.unwrap();
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let memberships = schema::platform_memberships::table
+            .filter(schema::platform_memberships::platform_id.eq(&platform_id))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await
+            .unwrap();
+        Some(memberships)
+    }
+
+    #[tokio::test]
+    async fn join_to_a_shut_down_platform_is_ignored() {
+        let Some(memberships) = join_winding_down_platform(PLATFORM_STATUS_SHUTDOWN, None).await else { return };
+        assert_eq!(memberships, 0);
+    }
+
+    #[tokio::test]
+    async fn join_after_the_shutdown_date_is_ignored() {
+        let Some(memberships) = join_winding_down_platform(PLATFORM_STATUS_LIVE, Some("2020-01-01")).await else { return };
+        assert_eq!(memberships, 0);
+    }
+
+    #[tokio::test]
+    async fn join_before_the_shutdown_date_is_recorded() {
+        let Some(memberships) = join_winding_down_platform(PLATFORM_STATUS_LIVE, Some("2999-01-01")).await else { return };
+        assert_eq!(memberships, 1);
+    }
+
+    #[tokio::test]
+    async fn blocks_from_either_write_path_are_listed_by_the_api() {
+        use axum::{extract::{Path, State}, response::IntoResponse};
+        use crate::api::handlers::platforms::get_platform_blocked_profiles;
+        use crate::api::params::ListParams;
+        use crate::models::platform::NewPlatformBlockedProfile;
+        
+        let Some(db) = test_database().await else { return };
+        let handler = PlatformEventHandler::new(db.clone(), mpsc::channel(1).1, "platform-test".to_string());
+        let platform_id = unique_id("platform");
+        let (from_handler, from_model) = (unique_id("profile"), unique_id("profile"));
+        let platform_ref = {
+            let mut conn = db.get_connection().await.unwrap();
+            insert_platform(&mut conn, &platform_id).await
+        };
+        let block = PlatformBlockedProfileEvent {
+            platform_id: platform_id.clone(),
+            profile_id: from_handler.clone(),
+            blocked_by: "0xmoderator".to_string(),
+        };
+        
+        // A repeated block refreshes the row rather than adding one
+        handler.process_profile_blocked_event(&block, None).await.unwrap();
+        handler.process_profile_blocked_event(&block, None).await.unwrap();
+        {
+            let mut conn = db.get_connection().await.unwrap();
+            crate::db::upsert_platform_block(&mut conn, &NewPlatformBlockedProfile {
+                platform_id: platform_id.clone(),
+                profile_id: from_model.clone(),
+                blocked_by: "0xmoderator".to_string(),
+                created_at: chrono::Utc::now().naive_utc(),
+                platform_ref: Some(platform_ref),
+            })
+            .await
+            .unwrap();
+        }
+        
+        let params = ListParams::parse(None, None, None, None, None, 100).unwrap();
+        let response = get_platform_blocked_profiles(State(db.pool.as_ref().clone()), Path(platform_id), params)
+            .await
+            .into_response();
+        let body: serde_json::Value =
+            serde_json::from_slice(&axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        let mut listed: Vec<&str> = body["blocked_profiles"].as_array().unwrap().iter().map(|b| b["profile_id"].as_str().unwrap()).collect();
+        listed.sort();
+        let mut expected = [from_handler.as_str(), from_model.as_str()];
+        expected.sort();
+        assert_eq!(listed, expected);
+    }
+
+    #[tokio::test]
+    async fn update_without_a_logo_keeps_the_stored_logo() {
+        let Some(db) = test_database().await else { return };
+        let handler = PlatformEventHandler::new(db.clone(), mpsc::channel(1).1, "platform-test".to_string());
+        let platform_id = unique_id("platform");
+        let mut conn = db.get_connection().await.unwrap();
+        let id = insert_platform(&mut conn, &platform_id).await;
+        diesel::update(schema::platforms::table.find(id))
+            .set((
+                schema::platforms::logo.eq("ipfs://logo"),
+                schema::platforms::description.eq("Old description"),
+            ))
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let update: PlatformUpdatedEvent = serde_json::from_value(serde_json::json!({
+            "platform_id": platform_id,
+            "tagline": "New tagline",
+            "description": "",
+        }))
+        .unwrap();
+        
+        handler.process_platform_updated_event(&update, None).await.unwrap();
+        
+        let stored: (String, Option<String>, Option<String>) = schema::platforms::table
+            .find(id)
+            .select((schema::platforms::tagline, schema::platforms::logo, schema::platforms::description))
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(stored, ("New tagline".to_string(), Some("ipfs://logo".to_string()), None));
+    }
+
+    #[tokio::test]
+    async fn older_approval_change_does_not_overwrite_a_newer_one() {
+        let Some(db) = test_database().await else { return };
+        let handler = PlatformEventHandler::new(db.clone(), mpsc::channel(1).1, "platform-test".to_string());
+        let platform_id = unique_id("platform");
+        let id = {
+            let mut conn = db.get_connection().await.unwrap();
+            insert_platform(&mut conn, &platform_id).await
+        };
+        let change = |is_approved: bool, approved_by: &str, changed_at: u64| PlatformApprovalChangedEvent {
+            platform_id: platform_id.clone(),
+            is_approved,
+            approved_by: approved_by.to_string(),
+            changed_at,
+        };
+        
+        handler.process_platform_approval_changed_event(&change(true, "0xnewer", 1_700_000_100), None).await.unwrap();
+        handler.process_platform_approval_changed_event(&change(false, "0xolder", 1_700_000_000), None).await.unwrap();
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let stored: (bool, Option<String>, Option<chrono::NaiveDateTime>) = schema::platforms::table
+            .find(id)
+            .select((schema::platforms::is_approved, schema::platforms::approved_by, schema::platforms::approval_changed_at))
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(stored, (true, Some("0xnewer".to_string()), chrono::DateTime::from_timestamp(1_700_000_100, 0).map(|t| t.naive_utc())));
+        
+        // The stale event is still kept in the history
+        let recorded = schema::platform_events::table
+            .filter(schema::platform_events::platform_id.eq(&platform_id))
+            .filter(schema::platform_events::event_type.eq(PlatformEventType::PlatformApprovalChanged.to_str()))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(recorded, 2);
+    }
+}
+
@@ -0,0 +1,302 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::config::EventBusConfig;
+use crate::metrics::{EVENTS_PUBLISHED, EVENT_PUBLISH_FAILURES};
+use crate::tasks::spawn_named;
+
+use super::listener::BlockchainEvent;
+
+/// Publishes indexed events to an external event bus
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Publish a single event
+    async fn publish(&self, event: &BlockchainEvent) -> Result<()>;
+}
+
+/// Publisher used when no event bus is configured
+pub struct NoopPublisher;
+
+#[async_trait]
+impl EventPublisher for NoopPublisher {
+    async fn publish(&self, _event: &BlockchainEvent) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Time allowed to open a NATS connection and receive the server's greeting
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Messages queued for the NATS connection before `publish` starts failing
+const PUBLISH_QUEUE_CAPACITY: usize = 1024;
+
+/// Minimal NATS publisher speaking the plain-text client protocol
+///
+/// `publish` only queues the message; a background task owns the connection, writes queued
+/// messages, answers the server's PINGs and reconnects when the connection drops.
+pub struct NatsPublisher {
+    /// Subject events are published to
+    subject: String,
+    /// Messages waiting for the connection task
+    queue: mpsc::Sender<Vec<u8>>,
+}
+
+impl NatsPublisher {
+    /// Create a publisher for a `nats://host:port` URL and start its connection task
+    pub fn new(url: &str, subject: String) -> Self {
+        let address = url.trim_start_matches("nats://").trim_end_matches('/').to_string();
+        let (queue, messages) = mpsc::channel(PUBLISH_QUEUE_CAPACITY);
+        spawn_named("nats-publisher", run_nats_connection(address, messages));
+        Self { subject, queue }
+    }
+}
+
+/// Open a connection and complete the CONNECT handshake within `CONNECT_TIMEOUT`
+async fn nats_connect(address: &str) -> Result<BufReader<TcpStream>> {
+    let handshake = async {
+        let stream = TcpStream::connect(address).await?;
+        let mut reader = BufReader::new(stream);
+        
+        // The server greets with an INFO line before accepting commands
+        let mut info_line = String::new();
+        reader.read_line(&mut info_line).await?;
+        if !info_line.starts_with("INFO") {
+            return Err(anyhow!("Unexpected NATS greeting: {}", info_line.trim()));
+        }
+        
+        reader
+            .get_mut()
+            .write_all(b"CONNECT {\"verbose\":false,\"pedantic\":false,\"name\":\"mys-social-indexer\"}\r\n")
+            .await?;
+        Ok(reader)
+    };
+    
+    let reader = tokio::time::timeout(CONNECT_TIMEOUT, handshake)
+        .await
+        .map_err(|_| anyhow!("Timed out connecting to NATS at {} after {:?}", address, CONNECT_TIMEOUT))??;
+    info!("Connected to NATS event bus at {}", address);
+    Ok(reader)
+}
+
+/// Own the NATS connection until every `NatsPublisher` handle is gone
+///
+/// A message that can't be written is counted in `EVENT_PUBLISH_FAILURES` and dropped; the
+/// connection is then re-opened for the next one.
+async fn run_nats_connection(address: String, mut messages: mpsc::Receiver<Vec<u8>>) {
+    let mut connection: Option<BufReader<TcpStream>> = None;
+    // read_until keeps a partly read line across cancellations, unlike read_line
+    let mut line = Vec::new();
+    
+    loop {
+        let Some(conn) = connection.as_mut() else {
+            // Connect lazily, when there is something to send
+            let Some(message) = messages.recv().await else {
+                return;
+            };
+            match nats_connect(&address).await {
+                Ok(mut conn) => {
+                    if let Err(e) = conn.get_mut().write_all(&message).await {
+                        EVENT_PUBLISH_FAILURES.inc();
+                        warn!("NATS publish failed: {}", e);
+                        continue;
+                    }
+                    connection = Some(conn);
+                }
+                Err(e) => {
+                    EVENT_PUBLISH_FAILURES.inc();
+                    warn!("NATS connection failed, dropping message: {}", e);
+                }
+            }
+            continue;
+        };
+        
+        tokio::select! {
+            message = messages.recv() => {
+                let Some(message) = message else {
+                    return;
+                };
+                if let Err(e) = conn.get_mut().write_all(&message).await {
+                    EVENT_PUBLISH_FAILURES.inc();
+                    warn!("NATS publish failed, reconnecting: {}", e);
+                    connection = None;
+                }
+            }
+            read = conn.read_until(b'\n', &mut line) => {
+                let reply = match read {
+                    Ok(0) | Err(_) => {
+                        warn!("NATS connection to {} closed, reconnecting on the next event", address);
+                        connection = None;
+                        None
+                    }
+                    Ok(_) => server_reply(&String::from_utf8_lossy(&line)),
+                };
+                if let (Some(reply), Some(conn)) = (reply, connection.as_mut()) {
+                    if let Err(e) = conn.get_mut().write_all(reply).await {
+                        warn!("Failed to answer NATS server, reconnecting: {}", e);
+                        connection = None;
+                    }
+                }
+                line.clear();
+            }
+        }
+    }
+}
+
+/// What to send back for a line from the NATS server, if anything
+///
+/// The server disconnects clients that leave its PINGs unanswered.
+fn server_reply(line: &str) -> Option<&'static [u8]> {
+    let line = line.trim_end();
+    if line == "PING" {
+        return Some(b"PONG\r\n");
+    }
+    if let Some(error) = line.strip_prefix("-ERR") {
+        warn!("NATS server error:{}", error);
+    }
+    None
+}
+
+/// JSON payload published for an event, with its checkpoint context
+pub fn event_payload(event: &BlockchainEvent) -> serde_json::Value {
+    serde_json::json!({
+        "event_id": event.event_id,
+        "tx_digest": event.tx_digest,
+        "event_type": event.event_type,
+        "package_address": event.package_address,
+        "module_name": event.module_name,
+        "struct_name": event.struct_name,
+        "sender": event.sender,
+        "timestamp_ms": event.timestamp_ms,
+        "data": event.data,
+    })
+}
+
+#[async_trait]
+impl EventPublisher for NatsPublisher {
+    async fn publish(&self, event: &BlockchainEvent) -> Result<()> {
+        let payload = serde_json::to_vec(&event_payload(event))?;
+        let mut message = format!("PUB {} {}\r\n", self.subject, payload.len()).into_bytes();
+        message.extend_from_slice(&payload);
+        message.extend_from_slice(b"\r\n");
+        
+        // Never wait on the connection; a backed-up queue fails this event instead
+        self.queue
+            .try_send(message)
+            .map_err(|e| anyhow!("Failed to queue event {} for NATS: {}", event.event_id, e))
+    }
+}
+
+/// Build the publisher configured by `EVENT_BUS_URL`, or a no-op publisher
+pub fn publisher_from_config(config: &EventBusConfig) -> Arc<dyn EventPublisher> {
+    match &config.url {
+        Some(url) if url.starts_with("nats://") => {
+            info!("Publishing processed events to {} on subject {}", url, config.subject);
+            Arc::new(NatsPublisher::new(url, config.subject.clone()))
+        }
+        Some(url) => {
+            warn!("Unsupported EVENT_BUS_URL scheme, event publishing disabled: {}", url);
+            Arc::new(NoopPublisher)
+        }
+        None => Arc::new(NoopPublisher),
+    }
+}
+
+/// Publish an event, metering the outcome without failing ingestion
+pub async fn publish_metered(publisher: &dyn EventPublisher, event: &BlockchainEvent) {
+    match publisher.publish(event).await {
+        Ok(()) => EVENTS_PUBLISHED.inc(),
+        Err(e) => {
+            EVENT_PUBLISH_FAILURES.inc();
+            warn!("Failed to publish event {}: {}", event.event_id, e);
+        }
+    }
+}
+
+/// Publish an event a handler has just applied, if the handler owns `module`
+///
+/// Every handler receives every event, so only the handler for the module that emitted an
+/// event publishes it, and only once its own writes have committed.
+pub async fn publish_applied(publisher: &dyn EventPublisher, module: &str, event: &BlockchainEvent) {
+    if event.module_name == module {
+        publish_metered(publisher, event).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    /// Records the ids of the events it was asked to publish
+    #[derive(Default)]
+    struct MockPublisher {
+        published: StdMutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MockPublisher {
+        async fn publish(&self, event: &BlockchainEvent) -> Result<()> {
+            self.published.lock().unwrap().push(event.event_id.clone());
+            Ok(())
+        }
+    }
+
+    fn event(event_id: &str, module_name: &str) -> BlockchainEvent {
+        BlockchainEvent {
+            tx_digest: "digest".to_string(),
+            event_id: event_id.to_string(),
+            event_type: format!("0x1::{}::SomeEvent", module_name),
+            package_address: "0x1".to_string(),
+            module_name: module_name.to_string(),
+            struct_name: "SomeEvent".to_string(),
+            sender: "0x2".to_string(),
+            data: serde_json::json!({}),
+            timestamp_ms: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn each_applied_event_is_published_once_by_its_owner() {
+        let publisher = MockPublisher::default();
+        let events = [event("a:0", "profile"), event("b:0", "platform"), event("c:0", "profile")];
+        
+        // Both handlers see every event, as they do behind the listener
+        for event in &events {
+            publish_applied(&publisher, "profile", event).await;
+            publish_applied(&publisher, "platform", event).await;
+        }
+        
+        assert_eq!(*publisher.published.lock().unwrap(), vec!["a:0", "b:0", "c:0"]);
+    }
+
+    #[tokio::test]
+    async fn noop_publisher_accepts_every_event() {
+        for event in [event("a:0", "profile"), event("b:0", "social_graph")] {
+            assert!(NoopPublisher.publish(&event).await.is_ok());
+        }
+    }
+
+    #[test]
+    fn server_pings_are_answered() {
+        assert_eq!(server_reply("PING\r\n"), Some(&b"PONG\r\n"[..]));
+        assert_eq!(server_reply("+OK\r\n"), None);
+        assert_eq!(server_reply("-ERR 'Authorization Violation'\r\n"), None);
+    }
+
+    #[test]
+    fn payload_carries_the_event_context() {
+        let payload = event_payload(&event("a:0", "profile"));
+        assert_eq!(payload["event_id"], "a:0");
+        assert_eq!(payload["module_name"], "profile");
+        assert_eq!(payload["timestamp_ms"], 1);
+    }
+}
@@ -1,36 +1,59 @@
 // Copyright (c) MySocial Team
 // SPDX-License-Identifier: Apache-2.0
 
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use anyhow::{anyhow, Result};
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn, trace};
 
-use crate::db::{Database, DbConnection};
+use crate::db::{resolve_profile_numeric_id, Database, DbConnection, ProfileKey};
 use crate::events::{FollowEvent, UnfollowEvent};
 use crate::schema;
 
+use super::dead_letter::record_dead_letter;
+use super::publisher::{publish_applied, EventPublisher, NoopPublisher};
+use super::ignored::skip_ignored;
 use super::listener::BlockchainEvent;
 
+/// Times a follow checks for its profiles before it's indexed without them
+const PROFILE_WAIT_ATTEMPTS: u32 = 5;
+
+/// Delay before the second check, doubled for each one after
+const PROFILE_WAIT_DELAY: Duration = Duration::from_millis(100);
+
 /// Handlers for social graph related events
 pub struct SocialGraphEventHandler {
     /// Database connection
     db: Arc<Database>,
     /// Event receiver channel
     rx: mpsc::Receiver<BlockchainEvent>,
+    /// Worker ID for recording failed events
+    worker_id: String,
+    /// Publisher for events this handler has applied
+    publisher: Arc<dyn EventPublisher>,
 }
 
 impl SocialGraphEventHandler {
     /// Create a new social graph event handler
-    pub fn new(db: Arc<Database>, rx: mpsc::Receiver<BlockchainEvent>, _worker_id: String) -> Self {
+    pub fn new(db: Arc<Database>, rx: mpsc::Receiver<BlockchainEvent>, worker_id: String) -> Self {
         Self {
             db,
             rx,
+            worker_id,
+            publisher: Arc::new(NoopPublisher),
         }
     }
     
+    /// Publish the social graph events this handler applies
+    pub fn with_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = publisher;
+        self
+    }
+    
     /// Get a database connection from the pool
     async fn get_connection(&self) -> Result<DbConnection> {
         self.db.get_connection()
@@ -38,16 +61,39 @@ impl SocialGraphEventHandler {
             .map_err(|e| anyhow!("Failed to get database connection: {}", e))
     }
     
+    /// Wait for both profiles of a follow to be indexed, returning whether they were
+    ///
+    /// A follow can share a transaction with the `ProfileCreatedEvent` it depends on, which the
+    /// profile listener applies from its own channel. The follow checks again with a backoff
+    /// rather than being recorded without its relationship straight away.
+    async fn wait_for_profiles(&self, event: &FollowEvent) -> Result<bool> {
+        retry_until(PROFILE_WAIT_ATTEMPTS, PROFILE_WAIT_DELAY, || async move {
+            let mut conn = self.get_connection().await?;
+            for profile_id in [&event.follower, &event.following] {
+                if resolve_profile_numeric_id(&mut conn, ProfileKey::ObjectId(profile_id)).await?.is_none() {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        })
+        .await
+    }
+    
     /// Process a follow event - creates relationship and updates follow counts
     async fn process_follow_event(&self, event: &FollowEvent, blockchain_event: Option<&BlockchainEvent>) -> Result<()> {
         debug!("Processing follow event details");
         
+        if !self.wait_for_profiles(event).await? {
+            warn!("Profiles for follow {} -> {} still missing, recording it without a relationship", event.follower, event.following);
+        }
+        
         let mut conn = self.get_connection().await?;
         
         // We always record the event in social_graph_events table, regardless of relationship status
         // Start a transaction for atomicity
-        conn.build_transaction()
-            .run(|mut conn| Box::pin(async move {
+        // SERIALIZABLE so concurrent follows/unfollows of the same profile can't each count
+        // without the other's relationship row; conflicting runs are retried
+        crate::db::run_serializable(&mut conn, |mut conn| Box::pin(async move {
                 // Create a social graph event record for history/auditing - we ALWAYS create this
                 // even if the relationship can't be created yet
                 let now = std::time::SystemTime::now()
@@ -76,27 +122,17 @@ impl SocialGraphEventHandler {
                 
                 // Now check if both profiles exist before creating a relationship
                 debug!("Verifying profiles exist by profile_id");
-                let follower_profile_exists = schema::profiles::table
-                    .filter(schema::profiles::profile_id.eq(&event.follower))
-                    .count()
-                    .get_result::<i64>(&mut conn)
-                    .await
-                    .unwrap_or(0) > 0;
+                let follower_profile = resolve_profile_numeric_id(&mut conn, ProfileKey::ObjectId(&event.follower)).await?;
                 
-                if !follower_profile_exists {
+                if follower_profile.is_none() {
                     info!("Follower profile not found: {}", event.follower);
                     // Still return Ok() since we've recorded the event
                     return Ok(());
                 }
                 
-                let following_profile_exists = schema::profiles::table
-                    .filter(schema::profiles::profile_id.eq(&event.following))
-                    .count()
-                    .get_result::<i64>(&mut conn)
-                    .await
-                    .unwrap_or(0) > 0;
+                let following_profile = resolve_profile_numeric_id(&mut conn, ProfileKey::ObjectId(&event.following)).await?;
                 
-                if !following_profile_exists {
+                if following_profile.is_none() {
                     info!("Following profile not found: {}", event.following);
                     // Still return Ok() since we've recorded the event
                     return Ok(());
@@ -124,26 +160,17 @@ impl SocialGraphEventHandler {
                     }
                 };
                 
-                // First, look up the owner_address for both follower and following profile IDs
-                let follower_owner = schema::profiles::table
-                    .filter(schema::profiles::profile_id.eq(&relationship.follower_address))
-                    .select(schema::profiles::owner_address)
-                    .first::<String>(&mut conn)
-                    .await;
-                
-                let following_owner = schema::profiles::table
-                    .filter(schema::profiles::profile_id.eq(&relationship.following_address))
-                    .select(schema::profiles::owner_address)
-                    .first::<String>(&mut conn)
-                    .await;
+                // Both profiles must exist before the relationship is touched
+                let follower_profile = resolve_profile_numeric_id(&mut conn, ProfileKey::ObjectId(&relationship.follower_address)).await?;
+                let following_profile = resolve_profile_numeric_id(&mut conn, ProfileKey::ObjectId(&relationship.following_address)).await?;
                 
                 // Log for debugging at trace level only
                 debug!("Verified profile ID mapping for follow event");
                 
-                // Continue only if we found both owner addresses
-                if let (Ok(_follower_owner), Ok(_following_owner)) = (&follower_owner, &following_owner) {
+                // Continue only if we found both profiles
+                if follower_profile.is_some() && following_profile.is_some() {
                     // Insert relationship - using standard Diesel query DSL to ensure proper escaping
-                    diesel::insert_into(schema::social_graph_relationships::table)
+                    let inserted = diesel::insert_into(schema::social_graph_relationships::table)
                         .values(&relationship)
                         .on_conflict((
                             schema::social_graph_relationships::follower_address, 
@@ -153,6 +180,12 @@ impl SocialGraphEventHandler {
                         .execute(&mut conn)
                         .await?;
                     
+                    // A concurrent or replayed follow already counted this relationship
+                    if inserted == 0 {
+                        debug!("Follow relationship already exists - ignoring");
+                        return Ok(());
+                    }
+                    
                     // Force recalculate the counts for the affected profiles based on actual relationships
                     diesel::sql_query(
                         "UPDATE profiles 
@@ -166,6 +199,9 @@ impl SocialGraphEventHandler {
                     .execute(&mut conn)
                     .await?;
                     
+                    // Following or unfollowing someone counts as activity for the follower
+                    crate::db::touch_profile_activity(&mut conn, &relationship.follower_address, chrono::Utc::now().naive_utc()).await?;
+                    
                     diesel::sql_query(
                         "UPDATE profiles 
                          SET followers_count = (
@@ -201,8 +237,9 @@ impl SocialGraphEventHandler {
         
         // We always record the event in social_graph_events table, regardless of relationship status
         // Start a transaction for atomicity
-        conn.build_transaction()
-            .run(|mut conn| Box::pin(async move {
+        // SERIALIZABLE so concurrent follows/unfollows of the same profile can't each count
+        // without the other's relationship row; conflicting runs are retried
+        crate::db::run_serializable(&mut conn, |mut conn| Box::pin(async move {
                 // Create a social graph event record for history/auditing - we ALWAYS create this
                 // even if there's no relationship to delete
                 let now = std::time::SystemTime::now()
@@ -258,24 +295,15 @@ impl SocialGraphEventHandler {
                     (rel.0, rel.1)
                 };
                 
-                // First, look up the owner_address for both follower and following profile IDs
-                let follower_owner = schema::profiles::table
-                    .filter(schema::profiles::profile_id.eq(&follower_address))
-                    .select(schema::profiles::owner_address)
-                    .first::<String>(&mut conn)
-                    .await;
-                
-                let following_owner = schema::profiles::table
-                    .filter(schema::profiles::profile_id.eq(&following_address))
-                    .select(schema::profiles::owner_address)
-                    .first::<String>(&mut conn)
-                    .await;
+                // Both profiles must exist before the relationship is touched
+                let follower_profile = resolve_profile_numeric_id(&mut conn, ProfileKey::ObjectId(&follower_address)).await?;
+                let following_profile = resolve_profile_numeric_id(&mut conn, ProfileKey::ObjectId(&following_address)).await?;
                 
                 // Log for debugging at trace level only
                 debug!("Verified profile ID mapping for unfollow event");
                 
-                // Continue only if we found both owner addresses
-                if let (Ok(_follower_owner), Ok(_following_owner)) = (&follower_owner, &following_owner) {
+                // Continue only if we found both profiles
+                if follower_profile.is_some() && following_profile.is_some() {
                     // Delete the relationship using proper Diesel delete with DSL
                     let deleted = diesel::delete(
                         schema::social_graph_relationships::table
@@ -300,6 +328,9 @@ impl SocialGraphEventHandler {
                     .execute(&mut conn)
                     .await?;
                     
+                    // Following or unfollowing someone counts as activity for the follower
+                    crate::db::touch_profile_activity(&mut conn, &follower_address, chrono::Utc::now().naive_utc()).await?;
+                    
                     diesel::sql_query(
                         "UPDATE profiles 
                          SET followers_count = (
@@ -338,32 +369,40 @@ impl SocialGraphEventHandler {
         if event.event_type.contains("::social_graph::") || 
            event.event_type.contains("::FollowEvent") || 
            event.event_type.contains("::UnfollowEvent") {
+            if skip_ignored("social_graph", &event, &[]) {
+                return Ok(());
+            }
+            
             info!("Processing social graph event: {}", event.event_type);
             
             if event.event_type.ends_with("::FollowEvent") {
-                match crate::events::parse_event::<FollowEvent>(&event.data) {
-                    Ok(follow_event) => {
-                        info!("Processing follow: {} -> {}", &follow_event.follower, &follow_event.following);
-                        if let Err(e) = self.process_follow_event(&follow_event, Some(&event)).await {
-                            error!("Failed to process follow event: {}", e);
-                        }
-                    },
-                    Err(e) => {
-                        error!("Failed to parse follow event: {}", e);
-                    }
+                let mut follow_event = crate::events::parse_event::<FollowEvent>(&event.data)
+                    .map_err(|e| e.context("Failed to parse follow event"))?;
+                if skip_ignored("social_graph", &event, &[&follow_event.follower]) {
+                    return Ok(());
                 }
+                
+                // Carry the checkpoint time so the relationship keeps the on-chain follow time
+                if follow_event.timestamp_ms.is_none() {
+                    follow_event.timestamp_ms = Some(event.timestamp_ms);
+                }
+
+                info!("Processing follow: {} -> {}", &follow_event.follower, &follow_event.following);
+                // A failed follow is dead-lettered by `start` and not published
+                self.process_follow_event(&follow_event, Some(&event))
+                    .await
+                    .map_err(|e| e.context("Failed to process follow event"))?;
             } else if event.event_type.ends_with("::UnfollowEvent") {
-                match crate::events::parse_event::<UnfollowEvent>(&event.data) {
-                    Ok(unfollow_event) => {
-                        info!("Processing unfollow: {} -> {}", &unfollow_event.follower, &unfollow_event.unfollowed);
-                        if let Err(e) = self.process_unfollow_event(&unfollow_event, Some(&event)).await {
-                            error!("Failed to process unfollow event: {}", e);
-                        }
-                    },
-                    Err(e) => {
-                        error!("Failed to parse unfollow event: {}", e);
-                    }
+                let unfollow_event = crate::events::parse_event::<UnfollowEvent>(&event.data)
+                    .map_err(|e| e.context("Failed to parse unfollow event"))?;
+                if skip_ignored("social_graph", &event, &[&unfollow_event.follower]) {
+                    return Ok(());
                 }
+                
+                info!("Processing unfollow: {} -> {}", &unfollow_event.follower, &unfollow_event.unfollowed);
+                self.process_unfollow_event(&unfollow_event, Some(&event))
+                    .await
+                    .map_err(|e| e.context("Failed to process unfollow event"))?;
             }
         }
         
@@ -377,12 +416,204 @@ impl SocialGraphEventHandler {
         while let Some(event) = self.rx.recv().await {
             debug!("Received event: {:?}", event);
             
-            if let Err(e) = self.process_event(event).await {
-                error!("Error processing event: {}", e);
+            let applied_event = event.clone();
+            match self.process_event(event).await {
+                Ok(()) => publish_applied(self.publisher.as_ref(), crate::SOCIAL_GRAPH_MODULE_NAME, &applied_event).await,
+                Err(e) => {
+                    error!("Error processing event: {}", e);
+                    record_dead_letter(&self.db, &self.worker_id, &applied_event, &e).await;
+                }
             }
         }
         
         warn!("Social graph event handler channel closed");
         Ok(())
     }
-}
\ No newline at end of file
+}
+/// Run `check` until it returns true, at most `attempts` times, sleeping `delay` (doubling
+/// each time) in between. Returns the last result.
+async fn retry_until<F, Fut>(attempts: u32, delay: Duration, mut check: F) -> Result<bool>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    let mut delay = delay;
+    for attempt in 1..=attempts.max(1) {
+        if check().await? {
+            return Ok(true);
+        }
+        if attempt < attempts {
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn retry_stops_once_the_dependency_appears() {
+        let checks = &AtomicU32::new(0);
+        let found = retry_until(5, Duration::from_millis(1), || async move {
+            Ok(checks.fetch_add(1, Ordering::SeqCst) == 2)
+        })
+        .await
+        .unwrap();
+        
+        assert!(found);
+        assert_eq!(checks.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_gives_up_after_the_last_attempt() {
+        let checks = &AtomicU32::new(0);
+        let found = retry_until(3, Duration::from_millis(1), || async move {
+            checks.fetch_add(1, Ordering::SeqCst);
+            Ok(false)
+        })
+        .await
+        .unwrap();
+        
+        assert!(!found);
+        assert_eq!(checks.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_stops_on_an_error() {
+        let checks = &AtomicU32::new(0);
+        let result = retry_until(3, Duration::from_millis(1), || async move {
+            checks.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow!("database unavailable"))
+        })
+        .await;
+        
+        assert!(result.is_err());
+        assert_eq!(checks.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn repeated_follow_counts_once() {
+        use crate::db::test_support::{insert_profile, test_database, unique_id};
+        
+        let Some(db) = test_database().await else { return };
+        let handler = SocialGraphEventHandler::new(db.clone(), mpsc::channel(1).1, "social-graph-test".to_string());
+        let follower = unique_id("profile");
+        let following = unique_id("profile");
+        {
+            let mut conn = db.get_connection().await.unwrap();
+            insert_profile(&mut conn, &follower, &unique_id("user")).await;
+            insert_profile(&mut conn, &following, &unique_id("user")).await;
+        }
+        let follow: FollowEvent = serde_json::from_value(serde_json::json!({
+            "follower": follower,
+            "following": following,
+        }))
+        .unwrap();
+        
+        handler.process_follow_event(&follow, None).await.unwrap();
+        handler.process_follow_event(&follow, None).await.unwrap();
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let relationships: i64 = schema::social_graph_relationships::table
+            .filter(schema::social_graph_relationships::follower_address.eq(&follower))
+            .filter(schema::social_graph_relationships::following_address.eq(&following))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        let followers_count: i32 = schema::profiles::table
+            .filter(schema::profiles::profile_id.eq(&following))
+            .select(schema::profiles::followers_count)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(relationships, 1);
+        assert_eq!(followers_count, 1);
+    }
+
+    #[tokio::test]
+    async fn follow_relationship_keeps_the_event_timestamp() {
+        use crate::db::test_support::{insert_profile, test_database, unique_id};
+        
+        let Some(db) = test_database().await else { return };
+        let handler = SocialGraphEventHandler::new(db.clone(), mpsc::channel(1).1, "social-graph-test".to_string());
+        let follower = unique_id("profile");
+        let following = unique_id("profile");
+        {
+            let mut conn = db.get_connection().await.unwrap();
+            insert_profile(&mut conn, &follower, &unique_id("user")).await;
+            insert_profile(&mut conn, &following, &unique_id("user")).await;
+        }
+        let tx_digest = unique_id("tx");
+        let event = BlockchainEvent {
+            event_id: format!("{}:0", tx_digest),
+            tx_digest,
+            event_type: "0x1::social_graph::FollowEvent".to_string(),
+            package_address: "0x1".to_string(),
+            module_name: "social_graph".to_string(),
+            struct_name: "FollowEvent".to_string(),
+            sender: follower.clone(),
+            data: serde_json::json!({ "follower": follower, "following": following }),
+            timestamp_ms: 1_600_000_000_250,
+        };
+        
+        handler.process_event(event).await.unwrap();
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let created_at: chrono::NaiveDateTime = schema::social_graph_relationships::table
+            .filter(schema::social_graph_relationships::follower_address.eq(&follower))
+            .filter(schema::social_graph_relationships::following_address.eq(&following))
+            .select(schema::social_graph_relationships::created_at)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(created_at.and_utc().timestamp_millis(), 1_600_000_000_250);
+    }
+
+    /// Concurrent follows of one profile conflict under SERIALIZABLE; the retries in
+    /// `run_serializable` must still land every one of them in the count
+    #[tokio::test]
+    async fn concurrent_follows_are_all_counted() {
+        use crate::db::test_support::{insert_profile, test_database, unique_id};
+        
+        let Some(db) = test_database().await else { return };
+        let handler = SocialGraphEventHandler::new(db.clone(), mpsc::channel(1).1, "social-graph-test".to_string());
+        let following = unique_id("profile");
+        let followers: Vec<String> = (0..crate::db::SERIALIZABLE_ATTEMPTS).map(|_| unique_id("profile")).collect();
+        {
+            let mut conn = db.get_connection().await.unwrap();
+            insert_profile(&mut conn, &following, &unique_id("user")).await;
+            for follower in &followers {
+                insert_profile(&mut conn, follower, &unique_id("user")).await;
+            }
+        }
+        let follows: Vec<FollowEvent> = followers
+            .iter()
+            .map(|follower| {
+                serde_json::from_value(serde_json::json!({
+                    "follower": follower,
+                    "following": following,
+                }))
+                .unwrap()
+            })
+            .collect();
+        
+        let results = futures::future::join_all(follows.iter().map(|follow| handler.process_follow_event(follow, None))).await;
+        for result in results {
+            result.unwrap();
+        }
+        
+        let mut conn = db.get_connection().await.unwrap();
+        let followers_count: i32 = schema::profiles::table
+            .filter(schema::profiles::profile_id.eq(&following))
+            .select(schema::profiles::followers_count)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(followers_count as usize, followers.len());
+    }
+}
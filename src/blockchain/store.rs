@@ -0,0 +1,199 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::Result;
+use async_trait::async_trait;
+use diesel_async::RunQueryDsl;
+use tokio::sync::Mutex;
+
+use crate::db::Database;
+use crate::models::profile::{NewProfile, UpdateProfile};
+
+/// Database operations the profile event handler relies on
+///
+/// Handler logic goes through this instead of a connection so it can run against
+/// `MemoryProfileStore` without Postgres.
+#[async_trait]
+pub trait ProfileStore: Send + Sync {
+    /// Insert a profile or update the existing row with the same `profile_id`, returning its id
+    async fn upsert_profile(&self, profile: &NewProfile) -> Result<i32>;
+    
+    /// Apply `update` to the profile with `profile_id`, returning whether it exists
+    async fn update_profile(&self, profile_id: &str, update: &UpdateProfile) -> Result<bool>;
+    
+    /// Materialize follows recorded before `profile_id` existed, returning how many were added
+    async fn resolve_pending_relationships(&self, profile_id: &str) -> Result<usize>;
+}
+
+/// `ProfileStore` backed by Postgres
+pub struct DieselProfileStore {
+    db: Arc<Database>,
+}
+
+impl DieselProfileStore {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ProfileStore for DieselProfileStore {
+    async fn upsert_profile(&self, profile: &NewProfile) -> Result<i32> {
+        let mut conn = self.db.get_connection().await?;
+        crate::db::upsert_profile(&mut conn, profile).await
+    }
+    
+    async fn update_profile(&self, profile_id: &str, update: &UpdateProfile) -> Result<bool> {
+        use diesel::prelude::*;
+        use crate::schema::profiles;
+        
+        let mut conn = self.db.get_connection().await?;
+        let updated = diesel::update(profiles::table.filter(profiles::profile_id.eq(profile_id)))
+            .set(update)
+            .execute(&mut conn)
+            .await?;
+        Ok(updated > 0)
+    }
+    
+    async fn resolve_pending_relationships(&self, profile_id: &str) -> Result<usize> {
+        let mut conn = self.db.get_connection().await?;
+        let profile_id = profile_id.to_string();
+        
+        let resolved = conn.build_transaction()
+            .run(|mut conn| Box::pin(async move {
+                // The latest event per pair decides whether the follow still stands
+                let inserted = diesel::sql_query(
+                    "INSERT INTO social_graph_relationships (follower_address, following_address, created_at)
+                     SELECT e.follower_address, e.following_address, e.created_at
+                     FROM (
+                         SELECT DISTINCT ON (follower_address, following_address)
+                             follower_address, following_address, event_type, created_at
+                         FROM social_graph_events
+                         WHERE follower_address = $1 OR following_address = $1
+                         ORDER BY follower_address, following_address, created_at DESC, id DESC
+                     ) e
+                     WHERE e.event_type = 'follow'
+                       AND EXISTS (SELECT 1 FROM profiles WHERE profile_id = e.follower_address)
+                       AND EXISTS (SELECT 1 FROM profiles WHERE profile_id = e.following_address)
+                     ON CONFLICT (follower_address, following_address) DO NOTHING"
+                )
+                .bind::<diesel::sql_types::Text, _>(&profile_id)
+                .execute(&mut conn)
+                .await?;
+                
+                if inserted > 0 {
+                    // Recompute counts for the new profile and everyone it is now linked to
+                    diesel::sql_query(
+                        "UPDATE profiles p
+                         SET following_count = (
+                                 SELECT COUNT(*) FROM social_graph_relationships
+                                 WHERE follower_address = p.profile_id
+                             ),
+                             followers_count = (
+                                 SELECT COUNT(*) FROM social_graph_relationships
+                                 WHERE following_address = p.profile_id
+                             )
+                         WHERE p.profile_id = $1
+                            OR p.profile_id IN (
+                                SELECT following_address FROM social_graph_relationships WHERE follower_address = $1
+                            )
+                            OR p.profile_id IN (
+                                SELECT follower_address FROM social_graph_relationships WHERE following_address = $1
+                            )"
+                    )
+                    .bind::<diesel::sql_types::Text, _>(&profile_id)
+                    .execute(&mut conn)
+                    .await?;
+                }
+                
+                Result::<_, diesel::result::Error>::Ok(inserted)
+            }))
+            .await?;
+        
+        Ok(resolved)
+    }
+}
+
+/// In-memory `ProfileStore` for exercising handler logic without a database
+#[derive(Default)]
+pub struct MemoryProfileStore {
+    /// Profiles by `profile_id`, with the id assigned on first insert
+    pub profiles: Mutex<HashMap<String, (i32, NewProfile)>>,
+    /// Follows waiting for a profile, as (follower, following)
+    pub pending_follows: Mutex<Vec<(String, String)>>,
+    /// Follows materialized by `resolve_pending_relationships`
+    pub relationships: Mutex<Vec<(String, String)>>,
+}
+
+#[async_trait]
+impl ProfileStore for MemoryProfileStore {
+    async fn upsert_profile(&self, profile: &NewProfile) -> Result<i32> {
+        let mut profiles = self.profiles.lock().await;
+        let key = profile.profile_id.clone().unwrap_or_default();
+        let next_id = profiles.len() as i32 + 1;
+        let entry = profiles.entry(key).or_insert_with(|| (next_id, profile.clone()));
+        entry.1 = profile.clone();
+        Ok(entry.0)
+    }
+    
+    async fn update_profile(&self, profile_id: &str, update: &UpdateProfile) -> Result<bool> {
+        let mut profiles = self.profiles.lock().await;
+        let Some((_, profile)) = profiles.get_mut(profile_id) else {
+            return Ok(false);
+        };
+        
+        // Same as the changeset: fields left as None keep their stored value
+        macro_rules! apply {
+            ($($field:ident),*) => {
+                $(if let Some(value) = &update.$field {
+                    profile.$field = Some(value.clone());
+                })*
+            };
+        }
+        apply!(
+            display_name, bio, profile_photo, website, cover_photo, sensitive_data_updated_at,
+            birthdate, current_location, raised_location, phone, email, gender, political_view,
+            religion, education, primary_language, relationship_status, x_username,
+            mastodon_username, facebook_username, reddit_username, github_username,
+            block_list_address, source_event
+        );
+        if let Some(count) = update.followers_count {
+            profile.followers_count = count;
+        }
+        if let Some(count) = update.following_count {
+            profile.following_count = count;
+        }
+        if let Some(flag) = update.has_profile_photo {
+            profile.has_profile_photo = flag;
+        }
+        if let Some(flag) = update.has_cover_photo {
+            profile.has_cover_photo = flag;
+        }
+        Ok(true)
+    }
+    
+    async fn resolve_pending_relationships(&self, profile_id: &str) -> Result<usize> {
+        let profiles = self.profiles.lock().await;
+        let mut pending = self.pending_follows.lock().await;
+        let mut relationships = self.relationships.lock().await;
+        
+        // Same rule as the SQL: both ends must exist before a follow is materialized
+        let (ready, waiting): (Vec<_>, Vec<_>) = pending.drain(..).partition(|(follower, following)| {
+            (follower == profile_id || following == profile_id)
+                && profiles.contains_key(follower)
+                && profiles.contains_key(following)
+        });
+        *pending = waiting;
+        
+        let mut added = 0;
+        for follow in ready {
+            if !relationships.contains(&follow) {
+                relationships.push(follow);
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+}
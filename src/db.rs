@@ -6,29 +6,69 @@ use anyhow::{anyhow, Result};
 use diesel::prelude::*;
 use diesel::pg::PgConnection;
 use diesel_async::AsyncPgConnection;
-use diesel_async::pooled_connection::deadpool::{Object, Pool};
-use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+use diesel_async::pooled_connection::deadpool::{Object, Pool, PoolError};
+use diesel_async::pooled_connection::{AsyncDieselConnectionManager, ManagerConfig, RecyclingMethod};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 
 use crate::config::Config;
+use crate::models::indexer::IndexerProgress;
+use crate::models::platform::NewPlatformBlockedProfile;
+use crate::models::profile::NewProfile;
+use crate::models::profile_events::NewProfileEvent;
+use crate::schema::{indexer_progress, platform_blocked_profiles, profile_events, profiles};
 
-pub type DbPool = Pool<AsyncPgConnection>;
 pub type DbConnection = Object<AsyncPgConnection>;
 
+/// Connection pool that times every acquire in `DB_POOL_ACQUIRE_SECONDS`
+///
+/// API handlers and the indexer both get their connections through `get`, so the histogram
+/// covers every wait on the pool. Everything else is the underlying deadpool pool.
+#[derive(Clone)]
+pub struct DbPool(Pool<AsyncPgConnection>);
+
+impl DbPool {
+    /// Get a connection, recording how long the pool took to hand it out
+    pub async fn get(&self) -> Result<DbConnection, PoolError> {
+        let _timer = crate::metrics::DB_POOL_ACQUIRE_SECONDS.start_timer();
+        self.0.get().await
+    }
+}
+
+impl std::ops::Deref for DbPool {
+    type Target = Pool<AsyncPgConnection>;
+    
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 // Define migrations
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
 /// Database wrapper for connection pool access
 #[derive(Clone)]
 pub struct Database {
+    /// Primary pool, used for all writes
     pub pool: Arc<DbPool>,
+    /// Pool for API reads; the same pool as `pool` unless a read replica is configured
+    pub read_pool: Arc<DbPool>,
 }
 
 impl Database {
     /// Create a new database instance
     pub fn new(pool: DbPool) -> Self {
+        let pool = Arc::new(pool);
+        Self {
+            read_pool: pool.clone(),
+            pool,
+        }
+    }
+    
+    /// Create a database instance whose API reads go to a separate (replica) pool
+    pub fn with_read_pool(pool: DbPool, read_pool: DbPool) -> Self {
         Self {
             pool: Arc::new(pool),
+            read_pool: Arc::new(read_pool),
         }
     }
     
@@ -40,29 +80,501 @@ impl Database {
 }
 
 /// Sets up the database connection pool
+///
+/// Connections are verified with a `SELECT 1` whenever they are recycled back out of
+/// the pool, so connections killed by a Postgres restart or failover are evicted and
+/// replaced on the next acquire instead of failing every query.
+///
+/// Manual check: start the indexer, run
+/// `SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE datname = current_database() AND pid <> pg_backend_pid();`
+/// from psql, then hit `/health` - the request should succeed on a fresh connection.
+///
+/// When `DATABASE_READ_URL` is set a second pool of the same size is opened against it for
+/// API reads; otherwise reads share the primary pool.
 pub async fn setup_connection_pool(config: &Config) -> Result<Arc<Database>> {
-    let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(&config.database.url);
+    let pool = build_pool(&config.database.url, config.database.max_connections).await?;
+    
+    // Create and return the database
+    match &config.database.read_url {
+        Some(read_url) => {
+            tracing::info!("Routing API reads to DATABASE_READ_URL");
+            let read_pool = build_pool(read_url, config.database.max_connections).await?;
+            Ok(Arc::new(Database::with_read_pool(pool, read_pool)))
+        }
+        None => Ok(Arc::new(Database::new(pool))),
+    }
+}
+
+/// Build a pool for `url` and check that a connection can be made
+async fn build_pool(url: &str, max_connections: u32) -> Result<DbPool> {
+    // Validate pooled connections on checkout so dead ones get dropped
+    let mut manager_config = ManagerConfig::default();
+    manager_config.recycling_method = RecyclingMethod::Verified;
+    
+    let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new_with_config(
+        url,
+        manager_config,
+    );
     
     // Create the pool with basic configuration
     let pool = Pool::builder(manager)
-        .max_size(config.database.max_connections as usize)
+        .max_size(max_connections as usize)
         .build()?;
     
     // Test the connection
     let _conn = pool.get().await?;
     
-    // Create and return the database
-    Ok(Arc::new(Database::new(pool)))
+    Ok(DbPool(pool))
+}
+
+/// Load the last persisted progress for every worker
+pub async fn load_indexer_progress(db: &Database) -> Result<Vec<IndexerProgress>> {
+    use diesel_async::RunQueryDsl;
+    
+    let mut conn = db.get_connection().await?;
+    let progress = indexer_progress::table
+        .order_by(indexer_progress::id.asc())
+        .load::<IndexerProgress>(&mut conn)
+        .await?;
+    
+    Ok(progress)
+}
+
+/// Insert a profile or update the existing row for the same on-chain `profile_id`
+///
+/// `profile_id` is the only identifier that never changes for a profile: usernames can be
+/// changed on-chain and `id` is a local sequence, so keying the upsert on either of them
+/// either duplicates the profile or overwrites a different one. Every path that writes
+/// profiles from events goes through here.
+///
+/// Usernames are unique on-chain too, so another profile still holding this username here
+/// means an event was missed or applied out of order. That is returned as an error naming both
+/// profiles, so the event is dead-lettered instead of either profile being rewritten.
+pub async fn upsert_profile(conn: &mut AsyncPgConnection, new_profile: &NewProfile) -> Result<i32> {
+    use diesel_async::RunQueryDsl;
+    
+    let id = conn.build_transaction().run(|mut conn| Box::pin(async move {
+        let holder = profiles::table
+            .filter(profiles::username.eq(&new_profile.username))
+            .filter(profiles::profile_id.is_distinct_from(&new_profile.profile_id))
+            .select(profiles::profile_id)
+            .first::<Option<String>>(&mut conn)
+            .await
+            .optional()?;
+        if let Some(holder) = holder {
+            anyhow::bail!(
+                "username {} of profile {:?} is still held by profile {:?}",
+                new_profile.username, new_profile.profile_id, holder
+            );
+        }
+        
+        diesel::insert_into(profiles::table)
+            .values(new_profile)
+            .on_conflict(profiles::profile_id)
+            .do_update()
+            .set((
+                profiles::owner_address.eq(&new_profile.owner_address),
+                profiles::username.eq(&new_profile.username),
+                profiles::display_name.eq(&new_profile.display_name),
+                profiles::bio.eq(&new_profile.bio),
+                profiles::profile_photo.eq(&new_profile.profile_photo),
+                profiles::website.eq(&new_profile.website),
+                profiles::updated_at.eq(&new_profile.updated_at),
+                profiles::cover_photo.eq(&new_profile.cover_photo),
+                profiles::sensitive_data_updated_at.eq(&new_profile.sensitive_data_updated_at),
+                // Sensitive fields
+                profiles::birthdate.eq(&new_profile.birthdate),
+                profiles::current_location.eq(&new_profile.current_location),
+                profiles::raised_location.eq(&new_profile.raised_location),
+                profiles::phone.eq(&new_profile.phone),
+                profiles::email.eq(&new_profile.email),
+                profiles::gender.eq(&new_profile.gender),
+                profiles::political_view.eq(&new_profile.political_view),
+                profiles::religion.eq(&new_profile.religion),
+                profiles::education.eq(&new_profile.education),
+                profiles::primary_language.eq(&new_profile.primary_language),
+                profiles::relationship_status.eq(&new_profile.relationship_status),
+                profiles::x_username.eq(&new_profile.x_username),
+                profiles::mastodon_username.eq(&new_profile.mastodon_username),
+                profiles::facebook_username.eq(&new_profile.facebook_username),
+                profiles::reddit_username.eq(&new_profile.reddit_username),
+                profiles::github_username.eq(&new_profile.github_username),
+                profiles::source_event.eq(&new_profile.source_event),
+                profiles::has_profile_photo.eq(new_profile.has_profile_photo),
+                profiles::has_cover_photo.eq(new_profile.has_cover_photo),
+                // Only ever moves forward, like touch_profile_activity
+                profiles::last_activity_at.eq(diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::Timestamp>>(
+                    "GREATEST(profiles.last_activity_at, excluded.last_activity_at)",
+                )),
+            ))
+            .returning(profiles::id)
+            .get_result::<i32>(&mut conn)
+            .await
+            .map_err(anyhow::Error::from)
+    }))
+    .await?;
+    
+    Ok(id)
+}
+
+/// The ways a profile can be identified when resolving its local `id`
+#[derive(Debug, Clone, Copy)]
+pub enum ProfileKey<'a> {
+    /// The wallet address that owns the profile
+    Address(&'a str),
+    /// The profile's on-chain object id (`profile_id`)
+    ObjectId(&'a str),
+    /// The profile's current username
+    Username(&'a str),
+}
+
+/// Resolve a profile to its local numeric `id`
+///
+/// Returns `Ok(None)` when no profile matches, so callers only see an error for a real
+/// database failure.
+pub async fn resolve_profile_numeric_id(conn: &mut AsyncPgConnection, key: ProfileKey<'_>) -> QueryResult<Option<i32>> {
+    use diesel_async::RunQueryDsl;
+    
+    let query = profiles::table.select(profiles::id).into_boxed();
+    let query = match key {
+        ProfileKey::Address(address) => query.filter(profiles::owner_address.eq(address)),
+        ProfileKey::ObjectId(profile_id) => query.filter(profiles::profile_id.eq(profile_id)),
+        ProfileKey::Username(username) => query.filter(profiles::username.eq(username)),
+    };
+    query
+        .first::<i32>(conn)
+        .await
+        .optional()
+}
+
+/// Record a profile history row, skipping it if the same on-chain event was already recorded
+///
+/// Keyed on `(profile_id, event_type, event_id)`, so reprocessing an event leaves one row.
+/// Rows without an `event_id` can't be matched and are always inserted. Returns the number
+/// of rows written.
+pub async fn insert_profile_event(conn: &mut AsyncPgConnection, profile_event: &NewProfileEvent) -> QueryResult<usize> {
+    use diesel_async::RunQueryDsl;
+    
+    diesel::insert_into(profile_events::table)
+        .values(profile_event)
+        .on_conflict((profile_events::profile_id, profile_events::event_type, profile_events::event_id))
+        .do_nothing()
+        .execute(conn)
+        .await
+}
+
+/// Record that a platform blocks a profile, refreshing the block if one already exists
+///
+/// A row in `platform_blocked_profiles` is the block itself; unblocking deletes it. Returns the
+/// number of rows written.
+pub async fn upsert_platform_block(conn: &mut AsyncPgConnection, block: &NewPlatformBlockedProfile) -> QueryResult<usize> {
+    use diesel_async::RunQueryDsl;
+    
+    diesel::insert_into(platform_blocked_profiles::table)
+        .values(block)
+        .on_conflict((platform_blocked_profiles::platform_id, platform_blocked_profiles::profile_id))
+        .do_update()
+        .set((
+            platform_blocked_profiles::blocked_by.eq(&block.blocked_by),
+            platform_blocked_profiles::created_at.eq(block.created_at),
+            platform_blocked_profiles::platform_ref.eq(block.platform_ref),
+        ))
+        .execute(conn)
+        .await
+}
+
+/// Move a profile's `last_activity_at` forward to `at`
+///
+/// Never moves it back, so events processed out of order leave the latest activity in place.
+pub async fn touch_profile_activity(conn: &mut AsyncPgConnection, profile_id: &str, at: chrono::NaiveDateTime) -> QueryResult<usize> {
+    use diesel_async::RunQueryDsl;
+    
+    diesel::update(profiles::table
+        .filter(profiles::profile_id.eq(profile_id))
+        .filter(profiles::last_activity_at.is_null().or(profiles::last_activity_at.lt(at))))
+        .set(profiles::last_activity_at.eq(at))
+        .execute(conn)
+        .await
+}
+
+/// Attempts `run_serializable` makes before returning a serialization failure
+pub const SERIALIZABLE_ATTEMPTS: u32 = 5;
+
+/// Run `transaction` at SERIALIZABLE isolation, retrying it when Postgres aborts it with a
+/// serialization failure
+///
+/// For transactions that read a value and write something derived from it (follow counts),
+/// where two concurrent runs at READ COMMITTED can each miss the other's write. The closure is
+/// rerun from scratch on each retry, so it must not have side effects outside the transaction.
+pub async fn run_serializable<T, F>(conn: &mut AsyncPgConnection, mut transaction: F) -> QueryResult<T>
+where
+    F: for<'r> FnMut(&'r mut AsyncPgConnection) -> futures::future::BoxFuture<'r, QueryResult<T>> + Send,
+    T: Send,
+{
+    use diesel::result::{DatabaseErrorKind, Error};
+    
+    let mut attempt = 1;
+    loop {
+        let result = conn.build_transaction()
+            .serializable()
+            .run(|conn| transaction(conn))
+            .await;
+        match result {
+            Err(Error::DatabaseError(DatabaseErrorKind::SerializationFailure, info)) if attempt < SERIALIZABLE_ATTEMPTS => {
+                tracing::debug!("Serialization failure (attempt {}), retrying: {}", attempt, info.message());
+                tokio::time::sleep(std::time::Duration::from_millis(10 * attempt as u64)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
 }
 
 /// Run database migrations
 pub fn run_migrations(config: &Config) -> Result<()> {
+    run_migrations_at(&config.database.url)
+}
+
+/// Run database migrations against the database at `url`
+fn run_migrations_at(url: &str) -> Result<()> {
     // Use a regular blocking connection for migrations
-    let mut conn = PgConnection::establish(&config.database.url)?;
+    let mut conn = PgConnection::establish(url)?;
     
     // Run migrations
     conn.run_pending_migrations(MIGRATIONS)
         .map_err(|e| anyhow::anyhow!("Migration error: {}", e))?;
     
     Ok(())
-}
\ No newline at end of file
+}
+/// Helpers for tests that need Postgres
+///
+/// Point `TEST_DATABASE_URL` at a disposable database to run them; without it those tests
+/// return early. Rows are keyed on `unique_id`s so tests can share the database.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use once_cell::sync::OnceCell;
+    use crate::schema::platforms;
+
+    /// Pool for `TEST_DATABASE_URL` with migrations applied, or `None` when it isn't set
+    pub async fn test_database() -> Option<Arc<Database>> {
+        static MIGRATED: OnceCell<()> = OnceCell::new();
+        
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            eprintln!("TEST_DATABASE_URL not set, skipping database test");
+            return None;
+        };
+        let migration_url = url.clone();
+        tokio::task::spawn_blocking(move || MIGRATED.get_or_try_init(|| run_migrations_at(&migration_url)).map(|_| ()))
+            .await
+            .expect("migration task panicked")
+            .expect("test database migrations");
+        let pool = build_pool(&url, 8).await.expect("test database pool");
+        Some(Arc::new(Database::new(pool)))
+    }
+
+    /// An id no other test run uses, e.g. `0xprofile17…3`
+    pub fn unique_id(prefix: &str) -> String {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        format!("0x{}{}{}", prefix, nanos, COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Insert a bare platform with zero member counts and return its numeric id
+    pub async fn insert_platform(conn: &mut AsyncPgConnection, platform_id: &str) -> i32 {
+        use diesel_async::RunQueryDsl;
+        
+        diesel::insert_into(platforms::table)
+            .values((
+                platforms::platform_id.eq(platform_id),
+                platforms::name.eq(platform_id),
+                platforms::tagline.eq(""),
+                platforms::developer_address.eq("0xdeveloper"),
+                platforms::status.eq(0i16),
+                platforms::total_users_count.eq(0i64),
+                platforms::active_users_count.eq(0i64),
+            ))
+            .returning(platforms::id)
+            .get_result(conn)
+            .await
+            .expect("insert test platform")
+    }
+
+    /// Insert a bare profile and return its numeric id
+    pub async fn insert_profile(conn: &mut AsyncPgConnection, profile_id: &str, username: &str) -> i32 {
+        use diesel_async::RunQueryDsl;
+        
+        diesel::insert_into(profiles::table)
+            .values((
+                profiles::owner_address.eq(format!("{}owner", profile_id)),
+                profiles::username.eq(username),
+                profiles::profile_id.eq(profile_id),
+            ))
+            .returning(profiles::id)
+            .get_result(conn)
+            .await
+            .expect("insert test profile")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::test_support::{insert_profile, test_database, unique_id};
+
+    fn new_profile(profile_id: &str, username: &str) -> NewProfile {
+        serde_json::from_value(serde_json::json!({
+            "owner_address": format!("{}owner", profile_id),
+            "username": username,
+            "profile_id": profile_id,
+            "created_at": "2025-01-01T00:00:00",
+            "updated_at": "2025-01-01T00:00:00",
+        }))
+        .unwrap()
+    }
+
+    async fn username_of(conn: &mut AsyncPgConnection, id: i32) -> String {
+        use diesel_async::RunQueryDsl;
+        
+        profiles::table.find(id).select(profiles::username).first(conn).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn same_profile_id_twice_updates_one_row() {
+        use diesel_async::RunQueryDsl;
+        
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let profile_id = unique_id("profile");
+        let renamed = unique_id("user");
+        
+        let first = upsert_profile(&mut conn, &new_profile(&profile_id, &unique_id("user"))).await.unwrap();
+        let second = upsert_profile(&mut conn, &new_profile(&profile_id, &renamed)).await.unwrap();
+        
+        assert_eq!(first, second);
+        let rows: i64 = profiles::table
+            .filter(profiles::profile_id.eq(&profile_id))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(rows, 1);
+        assert_eq!(username_of(&mut conn, first).await, renamed);
+    }
+
+    #[tokio::test]
+    async fn username_held_by_another_profile_is_an_error() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let username = unique_id("user");
+        
+        let old = upsert_profile(&mut conn, &new_profile(&unique_id("profile"), &username)).await.unwrap();
+        let err = upsert_profile(&mut conn, &new_profile(&unique_id("profile"), &username)).await.unwrap_err();
+        
+        assert!(err.to_string().contains("is still held by"), "{}", err);
+        assert_eq!(username_of(&mut conn, old).await, username);
+        assert_eq!(resolve_profile_numeric_id(&mut conn, ProfileKey::Username(&username)).await.unwrap(), Some(old));
+    }
+
+    #[tokio::test]
+    async fn replayed_create_does_not_move_last_activity_back() {
+        use diesel_async::RunQueryDsl;
+        
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let profile_id = unique_id("profile");
+        let username = unique_id("user");
+        let latest = chrono::NaiveDate::from_ymd_opt(2025, 6, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        
+        let id = upsert_profile(&mut conn, &new_profile(&profile_id, &username)).await.unwrap();
+        touch_profile_activity(&mut conn, &profile_id, latest).await.unwrap();
+        
+        let mut older = new_profile(&profile_id, &username);
+        older.last_activity_at = Some(latest - chrono::Duration::days(30));
+        upsert_profile(&mut conn, &older).await.unwrap();
+        // A create without any activity leaves it alone as well
+        upsert_profile(&mut conn, &new_profile(&profile_id, &username)).await.unwrap();
+        
+        let last_activity_at: Option<chrono::NaiveDateTime> = profiles::table
+            .find(id)
+            .select(profiles::last_activity_at)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(last_activity_at, Some(latest));
+    }
+
+    #[tokio::test]
+    async fn profile_resolves_by_every_key() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let profile_id = unique_id("profile");
+        let username = unique_id("user");
+        let id = insert_profile(&mut conn, &profile_id, &username).await;
+        let owner = format!("{}owner", profile_id);
+        
+        for key in [ProfileKey::Address(&owner), ProfileKey::ObjectId(&profile_id), ProfileKey::Username(&username)] {
+            assert_eq!(resolve_profile_numeric_id(&mut conn, key).await.unwrap(), Some(id), "{:?}", key);
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_profile_resolves_to_none() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let missing = unique_id("missing");
+        
+        for key in [ProfileKey::Address(&missing), ProfileKey::ObjectId(&missing), ProfileKey::Username(&missing)] {
+            assert_eq!(resolve_profile_numeric_id(&mut conn, key).await.unwrap(), None, "{:?}", key);
+        }
+    }
+
+    #[tokio::test]
+    async fn keys_only_match_their_own_column() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let profile_id = unique_id("profile");
+        let username = unique_id("user");
+        insert_profile(&mut conn, &profile_id, &username).await;
+        
+        assert_eq!(resolve_profile_numeric_id(&mut conn, ProfileKey::Username(&profile_id)).await.unwrap(), None);
+        assert_eq!(resolve_profile_numeric_id(&mut conn, ProfileKey::ObjectId(&username)).await.unwrap(), None);
+        assert_eq!(resolve_profile_numeric_id(&mut conn, ProfileKey::Address(&profile_id)).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn reprocessed_profile_event_leaves_one_history_row() {
+        use diesel_async::RunQueryDsl;
+        
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.get_connection().await.unwrap();
+        let profile_id = unique_id("profile");
+        insert_profile(&mut conn, &profile_id, &unique_id("user")).await;
+        let history_event = |event_id: Option<&str>| NewProfileEvent::from_blockchain_event(
+            "ProfileBlockedEvent",
+            profile_id.clone(),
+            serde_json::json!({ "blocked": "0xother" }),
+            event_id.map(str::to_string),
+            Some(1_700_000_000),
+        );
+        
+        assert_eq!(insert_profile_event(&mut conn, &history_event(Some("tx:0"))).await.unwrap(), 1);
+        assert_eq!(insert_profile_event(&mut conn, &history_event(Some("tx:0"))).await.unwrap(), 0);
+        assert_eq!(insert_profile_event(&mut conn, &history_event(Some("tx:1"))).await.unwrap(), 1);
+        // Without an event id there is nothing to match on
+        assert_eq!(insert_profile_event(&mut conn, &history_event(None)).await.unwrap(), 1);
+        
+        let rows: i64 = profile_events::table
+            .filter(profile_events::profile_id.eq(&profile_id))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(rows, 3);
+    }
+}
+
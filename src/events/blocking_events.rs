@@ -8,14 +8,15 @@ use serde_json;
 use tracing::{info, error};
 use serde::{Deserialize, Serialize};
 
-use crate::db::DbConnection;
-use crate::schema::profile_events;
+use crate::db::{resolve_profile_numeric_id, DbConnection, ProfileKey};
 use crate::schema::profiles_blocked;
 use crate::models::blocking::profile_blocks::NewProfileBlock;
+use crate::models::platform::NewPlatformBlockedProfile;
 use crate::models::blocking::profile_blocks::UserBlockEvent;
 use crate::models::blocking::profile_blocks::UserUnblockEvent;
 use crate::models::profile_events::NewProfileEvent;
 use crate::events::profile_event_types::{BlockAddedEvent, BlockRemovedEvent};
+use crate::events::sentinel::is_sentinel;
 
 // Import platform event types
 use crate::events::{
@@ -35,6 +36,7 @@ pub struct BlockListCreatedEvent {
 pub async fn process_profile_block_event(
     conn: &mut DbConnection,
     event_data: &serde_json::Value,
+    event_id: Option<&str>,
 ) -> Result<()> {
     // Log the raw event data for debugging
     info!(
@@ -99,8 +101,7 @@ pub async fn process_profile_block_event(
     };
     
     // Check if we have valid data
-    if block_event.blocker.is_empty() || block_event.blocker == "unknown" ||
-       block_event.blocked.is_empty() || block_event.blocked == "unknown" {
+    if is_sentinel(&block_event.blocker) || is_sentinel(&block_event.blocked) {
         info!("Invalid block event data, skipping");
         return Ok(());
     }
@@ -142,14 +143,11 @@ pub async fn process_profile_block_event(
             // Create profile event for blocking
             let profile_event = NewProfileEvent::from_block_added(
                 &profile_block_event,
-                None // No event ID available
+                event_id.map(String::from)
             );
             
             // Insert into profile_events
-            let event_result = diesel::insert_into(profile_events::table)
-                .values(&profile_event)
-                .execute(conn)
-                .await;
+            let event_result = crate::db::insert_profile_event(conn, &profile_event).await;
                 
             match event_result {
                 Ok(_) => {
@@ -173,6 +171,7 @@ pub async fn process_profile_block_event(
 pub async fn process_profile_unblock_event(
     conn: &mut DbConnection,
     event_data: &serde_json::Value,
+    event_id: Option<&str>,
 ) -> Result<()> {
     // Log the raw event data for debugging
     info!(
@@ -261,14 +260,11 @@ pub async fn process_profile_unblock_event(
             // Create profile event for unblocking
             let profile_event = NewProfileEvent::from_block_removed(
                 &profile_unblock_event,
-                None // No event ID available
+                event_id.map(String::from)
             );
             
             // Insert into profile_events
-            let event_result = diesel::insert_into(profile_events::table)
-                .values(&profile_event)
-                .execute(conn)
-                .await;
+            let event_result = crate::db::insert_profile_event(conn, &profile_event).await;
                 
             match event_result {
                 Ok(_) => {
@@ -295,6 +291,7 @@ pub async fn process_profile_unblock_event(
 pub async fn process_platform_block_event(
     conn: &mut DbConnection,
     event_data: &serde_json::Value,
+    event_id: Option<&str>,
 ) -> Result<()> {
     // First log the raw event data to see what's coming from the blockchain
     info!(
@@ -366,15 +363,12 @@ pub async fn process_platform_block_event(
             "timestamp": block_timestamp,
             "is_platform_block": true
         }),
-        None, // No event ID available
+        event_id.map(String::from),
         Some(block_timestamp)
     );
     
     // Insert into profile_events
-    let result = diesel::insert_into(crate::schema::profile_events::table)
-        .values(&profile_event)
-        .execute(conn)
-        .await;
+    let result = crate::db::insert_profile_event(conn, &profile_event).await;
         
     match result {
         Ok(_) => {
@@ -393,6 +387,7 @@ pub async fn process_platform_block_event(
 pub async fn process_platform_unblock_event(
     conn: &mut DbConnection,
     event_data: &serde_json::Value,
+    event_id: Option<&str>,
 ) -> Result<()> {
     // First log the raw event data to see what's coming from the blockchain
     info!(
@@ -464,15 +459,12 @@ pub async fn process_platform_unblock_event(
             "timestamp": unblock_timestamp,
             "is_platform_block": true
         }),
-        None, // No event ID available
+        event_id.map(String::from),
         Some(unblock_timestamp)
     );
     
     // Insert into profile_events
-    let result = diesel::insert_into(crate::schema::profile_events::table)
-        .values(&profile_event)
-        .execute(conn)
-        .await;
+    let result = crate::db::insert_profile_event(conn, &profile_event).await;
         
     match result {
         Ok(_) => {
@@ -487,6 +479,137 @@ pub async fn process_platform_unblock_event(
     Ok(())
 } 
 
+/// Modules whose `UserBlockEvent`s block a profile on a platform rather than between profiles
+pub const PLATFORM_BLOCK_MODULES: &[&str] = &["platform"];
+
+/// Where a `UserBlockEvent`/`UserUnblockEvent` is recorded
+///
+/// The same event structs are emitted by several modules; `module_name` says which one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockScope {
+    /// One profile blocking another, stored in `profiles_blocked`
+    Profile,
+    /// A platform blocking a profile, stored in `platform_blocked_profiles`
+    Platform,
+}
+
+impl BlockScope {
+    /// Scope for a `module_name`; events without one predate the field and are profile blocks
+    pub fn from_module_name(module_name: Option<&str>) -> Self {
+        match module_name {
+            Some(module) if PLATFORM_BLOCK_MODULES.contains(&module) => BlockScope::Platform,
+            _ => BlockScope::Profile,
+        }
+    }
+    
+    /// Scope for raw event data, reading `module_name` from `fields` or the top level
+    pub fn from_event_data(event_data: &serde_json::Value) -> Self {
+        Self::from_module_name(event_field(event_data, "module_name"))
+    }
+}
+
+/// Record a `UserBlockEvent` in the table its `module_name` routes it to
+pub async fn process_user_block_event(
+    conn: &mut DbConnection,
+    event_data: &serde_json::Value,
+    sender: &str,
+    event_id: Option<&str>,
+) -> Result<()> {
+    match BlockScope::from_event_data(event_data) {
+        BlockScope::Platform => process_platform_user_block_event(conn, event_data, sender, event_id).await,
+        BlockScope::Profile => process_profile_block_event(conn, event_data, event_id).await,
+    }
+}
+
+/// Remove the block a `UserUnblockEvent` refers to, routed by `module_name` like blocks
+pub async fn process_user_unblock_event(
+    conn: &mut DbConnection,
+    event_data: &serde_json::Value,
+    sender: &str,
+    event_id: Option<&str>,
+) -> Result<()> {
+    match BlockScope::from_event_data(event_data) {
+        BlockScope::Platform => process_platform_user_unblock_event(conn, event_data, sender, event_id).await,
+        BlockScope::Profile => process_profile_unblock_event(conn, event_data, event_id).await,
+    }
+}
+
+/// Read a string field from the `fields` container, falling back to the top level
+fn event_field<'a>(event_data: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+    event_data
+        .get("fields")
+        .and_then(|fields| fields.get(name))
+        .or_else(|| event_data.get(name))
+        .and_then(|v| v.as_str())
+}
+
+/// Process a `UserBlockEvent` from a platform-scoped module: `blocker` is the platform, `blocked` the profile
+///
+/// `sender` is the transaction sender, recorded as `blocked_by`.
+pub async fn process_platform_user_block_event(
+    conn: &mut DbConnection,
+    event_data: &serde_json::Value,
+    sender: &str,
+    event_id: Option<&str>,
+) -> Result<()> {
+    let (Some(platform_id), Some(profile_id)) = (event_field(event_data, "blocker"), event_field(event_data, "blocked")) else {
+        info!("Platform-scoped block event is missing blocker or blocked, skipping");
+        return Ok(());
+    };
+    
+    let platform_ref = crate::schema::platforms::table
+        .filter(crate::schema::platforms::platform_id.eq(platform_id))
+        .select(crate::schema::platforms::id)
+        .first::<i32>(conn)
+        .await
+        .optional()?;
+    
+    let block = NewPlatformBlockedProfile {
+        platform_id: platform_id.to_string(),
+        profile_id: profile_id.to_string(),
+        blocked_by: sender.to_string(),
+        created_at: chrono::Utc::now().naive_utc(),
+        platform_ref,
+    };
+    crate::db::upsert_platform_block(conn, &block).await?;
+    info!("Platform {} blocked profile {}", platform_id, profile_id);
+    
+    // Record the block in the profile's history the same way as PlatformBlockedProfileEvent
+    let history = serde_json::json!({
+        "platform_id": platform_id,
+        "profile_id": profile_id,
+        "blocked_by": sender,
+    });
+    process_platform_block_event(conn, &history, event_id).await
+}
+
+/// Process a `UserUnblockEvent` from a platform-scoped module: `blocker` is the platform, `unblocked` the profile
+pub async fn process_platform_user_unblock_event(
+    conn: &mut DbConnection,
+    event_data: &serde_json::Value,
+    sender: &str,
+    event_id: Option<&str>,
+) -> Result<()> {
+    let (Some(platform_id), Some(profile_id)) = (event_field(event_data, "blocker"), event_field(event_data, "unblocked")) else {
+        info!("Platform-scoped unblock event is missing blocker or unblocked, skipping");
+        return Ok(());
+    };
+    
+    let removed = diesel::delete(crate::schema::platform_blocked_profiles::table)
+        .filter(crate::schema::platform_blocked_profiles::platform_id.eq(platform_id))
+        .filter(crate::schema::platform_blocked_profiles::profile_id.eq(profile_id))
+        .execute(conn)
+        .await?;
+    info!("Platform {} unblocked profile {} ({} rows removed)", platform_id, profile_id, removed);
+    
+    let history = serde_json::json!({
+        "platform_id": platform_id,
+        "profile_id": profile_id,
+        "unblocked_by": sender,
+    });
+    process_platform_unblock_event(conn, &history, event_id).await
+}
+
 /// Process a block list created event
 pub async fn process_block_list_created_event(
     conn: &mut DbConnection,
@@ -564,69 +687,207 @@ pub async fn process_block_list_created_event(
     use crate::schema::profiles;
     use crate::models::profile::UpdateProfile;
     
-    // First, log the current profile in the database
-    match diesel::dsl::select(
-        diesel::dsl::exists(
-            profiles::table
-                .filter(profiles::owner_address.eq(&block_list_event.owner))
-        )
-    )
-    .get_result::<bool>(conn)
-    .await {
-        Ok(exists) => {
-            info!("Profile with owner_address {} exists in database: {}", block_list_event.owner, exists);
-            
-            if !exists {
-                info!("Could not find profile with owner_address {}, cannot update", block_list_event.owner);
-                return Ok(());
-            }
+    let Some(profile) = resolve_profile_numeric_id(conn, ProfileKey::Address(&block_list_event.owner)).await? else {
+        info!("Could not find profile with owner_address {}, cannot update", block_list_event.owner);
+        return Ok(());
+    };
+    
+    info!("Updating profile with owner_address {}", block_list_event.owner);
+    
+    let update = UpdateProfile {
+        display_name: None,
+        bio: None,
+        profile_photo: None,
+        website: None,
+        cover_photo: None,
+        sensitive_data_updated_at: None,
+        followers_count: None,
+        following_count: None,
+        birthdate: None,
+        current_location: None,
+        raised_location: None,
+        phone: None,
+        email: None,
+        gender: None,
+        political_view: None,
+        religion: None,
+        education: None,
+        primary_language: None,
+        relationship_status: None,
+        x_username: None,
+        mastodon_username: None,
+        facebook_username: None,
+        reddit_username: None,
+        github_username: None,
+        block_list_address: Some(block_list_event.block_list_id.clone()),
+        has_profile_photo: None,
+        has_cover_photo: None,
+        source_event: None,
+    };
+    
+    // Skip profiles already pointing at this block list, so a replayed event writes nothing
+    let updated = diesel::update(profiles::table.find(profile))
+        .filter(profiles::block_list_address.is_distinct_from(&block_list_event.block_list_id))
+        .set(&update)
+        .execute(conn)
+        .await?;
+    
+    if updated == 0 {
+        info!(
+            "Block list {} already recorded for owner_address {}, nothing to update",
+            block_list_event.block_list_id, block_list_event.owner
+        );
+    } else {
+        info!(
+            "Updated profile with owner_address {} with block list address {}",
+            block_list_event.owner, block_list_event.block_list_id
+        );
+    }
+    
+    Ok(())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::{insert_profile, test_database, unique_id};
+    use crate::schema::profiles;
+
+    #[tokio::test]
+    async fn block_list_is_recorded_on_the_owners_profile() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.pool.get().await.unwrap();
+        let profile_id = unique_id("profile");
+        let id = insert_profile(&mut conn, &profile_id, &unique_id("user")).await;
+        let block_list_id = unique_id("blocklist");
+        let data = serde_json::json!({ "block_list_id": block_list_id, "owner": format!("{}owner", profile_id) });
+        
+        process_block_list_created_event(&mut conn, &data).await.unwrap();
+        
+        let recorded: Option<String> = profiles::table
+            .find(id)
+            .select(profiles::block_list_address)
+            .first(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(recorded, Some(block_list_id));
+    }
+
+    #[tokio::test]
+    async fn reprocessed_block_list_leaves_the_profile_untouched() {
+        use diesel::{dsl::sql, sql_types::BigInt};
+        
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.pool.get().await.unwrap();
+        let profile_id = unique_id("profile");
+        let id = insert_profile(&mut conn, &profile_id, &unique_id("user")).await;
+        let block_list_id = unique_id("blocklist");
+        let data = serde_json::json!({ "block_list_id": block_list_id, "owner": format!("{}owner", profile_id) });
+        // xmin changes whenever the row is rewritten, even with the same values
+        let row_version = sql::<BigInt>("xmin::text::bigint");
+        
+        process_block_list_created_event(&mut conn, &data).await.unwrap();
+        let before: i64 = profiles::table.find(id).select(row_version.clone()).first(&mut conn).await.unwrap();
+        process_block_list_created_event(&mut conn, &data).await.unwrap();
+        let after: i64 = profiles::table.find(id).select(row_version).first(&mut conn).await.unwrap();
+        
+        assert_eq!(before, after);
+        let holders: i64 = profiles::table
+            .filter(profiles::block_list_address.eq(&block_list_id))
+            .count()
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(holders, 1);
+    }
+
+    #[tokio::test]
+    async fn block_list_of_an_unknown_owner_is_skipped() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.pool.get().await.unwrap();
+        let data = serde_json::json!({ "block_list_id": unique_id("blocklist"), "owner": unique_id("missing") });
+        
+        process_block_list_created_event(&mut conn, &data).await.unwrap();
+    }
+
+    fn block_data(blocker: &str, blocked: &str, module_name: Option<&str>) -> serde_json::Value {
+        let mut fields = serde_json::json!({ "blocker": blocker, "blocked": blocked });
+        if let Some(module_name) = module_name {
+            fields["module_name"] = module_name.into();
+        }
+        serde_json::json!({ "fields": fields })
+    }
+
+    async fn profile_blocks(conn: &mut DbConnection, blocker: &str) -> i64 {
+        profiles_blocked::table
+            .filter(profiles_blocked::blocker_wallet_address.eq(blocker))
+            .count()
+            .get_result(conn)
+            .await
+            .unwrap()
+    }
+
+    async fn platform_blocks(conn: &mut DbConnection, platform_id: &str) -> i64 {
+        use crate::schema::platform_blocked_profiles;
+        
+        platform_blocked_profiles::table
+            .filter(platform_blocked_profiles::platform_id.eq(platform_id))
+            .count()
+            .get_result(conn)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn module_name_picks_the_block_scope() {
+        assert_eq!(BlockScope::from_event_data(&block_data("0xa", "0xb", Some("platform"))), BlockScope::Platform);
+        assert_eq!(BlockScope::from_event_data(&block_data("0xa", "0xb", Some("social_graph"))), BlockScope::Profile);
+        assert_eq!(BlockScope::from_event_data(&block_data("0xa", "0xb", Some("block_list"))), BlockScope::Profile);
+        assert_eq!(BlockScope::from_event_data(&block_data("0xa", "0xb", None)), BlockScope::Profile);
+        // A top-level module_name counts as well
+        let top_level = serde_json::json!({ "blocker": "0xa", "blocked": "0xb", "module_name": "platform" });
+        assert_eq!(BlockScope::from_event_data(&top_level), BlockScope::Platform);
+    }
+
+    #[tokio::test]
+    async fn each_module_name_writes_the_right_table() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.pool.get().await.unwrap();
+        
+        for module_name in [Some("social_graph"), Some("block_list"), None] {
+            let blocker = unique_id("blocker");
+            let data = block_data(&blocker, &unique_id("blocked"), module_name);
             
-            // If we found a profile by owner_address, update it
-            info!("Updating profile with owner_address {}", block_list_event.owner);
+            process_user_block_event(&mut conn, &data, "0xsender", Some(&unique_id("event"))).await.unwrap();
             
-            let update = UpdateProfile {
-                display_name: None,
-                bio: None,
-                profile_photo: None,
-                website: None,
-                cover_photo: None,
-                sensitive_data_updated_at: None,
-                followers_count: None,
-                following_count: None,
-                birthdate: None,
-                current_location: None,
-                raised_location: None,
-                phone: None,
-                email: None,
-                gender: None,
-                political_view: None,
-                religion: None,
-                education: None,
-                primary_language: None,
-                relationship_status: None,
-                x_username: None,
-                mastodon_username: None,
-                facebook_username: None,
-                reddit_username: None,
-                github_username: None,
-                block_list_address: Some(block_list_event.block_list_id.clone()),
-            };
+            assert_eq!(profile_blocks(&mut conn, &blocker).await, 1, "{:?}", module_name);
+            assert_eq!(platform_blocks(&mut conn, &blocker).await, 0, "{:?}", module_name);
+        }
+        
+        let platform_id = unique_id("platform");
+        let data = block_data(&platform_id, &unique_id("blocked"), Some("platform"));
+        process_user_block_event(&mut conn, &data, "0xsender", Some(&unique_id("event"))).await.unwrap();
+        
+        assert_eq!(platform_blocks(&mut conn, &platform_id).await, 1);
+        assert_eq!(profile_blocks(&mut conn, &platform_id).await, 0);
+    }
+
+    #[tokio::test]
+    async fn unblock_is_routed_like_the_block() {
+        let Some(db) = test_database().await else { return };
+        let mut conn = db.pool.get().await.unwrap();
+        
+        for module_name in [Some("social_graph"), Some("platform")] {
+            let (blocker, blocked) = (unique_id("blocker"), unique_id("blocked"));
+            let mut unblock = block_data(&blocker, &blocked, module_name);
+            let fields = unblock["fields"].as_object_mut().unwrap();
+            fields.remove("blocked");
+            fields.insert("unblocked".to_string(), blocked.clone().into());
             
-            diesel::update(profiles::table)
-                .filter(profiles::owner_address.eq(&block_list_event.owner))
-                .set(&update)
-                .execute(conn)
-                .await?;
+            process_user_block_event(&mut conn, &block_data(&blocker, &blocked, module_name), "0xsender", Some(&unique_id("event"))).await.unwrap();
+            process_user_unblock_event(&mut conn, &unblock, "0xsender", Some(&unique_id("event"))).await.unwrap();
             
-            info!(
-                "Updated profile with owner_address {} with block list address {}",
-                block_list_event.owner, block_list_event.block_list_id
-            );
-        },
-        Err(e) => {
-            info!("Error checking if profile exists: {}", e);
+            assert_eq!(profile_blocks(&mut conn, &blocker).await, 0, "{:?}", module_name);
+            assert_eq!(platform_blocks(&mut conn, &blocker).await, 0, "{:?}", module_name);
         }
     }
-    
-    Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,267 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+//! Schema-drift canary: parses samples of each event type and checks the fields the
+//! handlers depend on still come out populated.
+//!
+//! The bundled known-good samples catch parser regressions; samples of the newest events on
+//! the node catch the on-chain package changing its event shapes.
+
+use anyhow::Result;
+use mys_sdk::{rpc_types::EventFilter, MysClient, MysClientBuilder};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tracing::{error, info, warn};
+
+use crate::events::{
+    parse_event, FollowEvent, PlatformCreatedEvent, ProfileCreatedEvent, UnfollowEvent,
+    UserBlockEvent,
+};
+use crate::metrics::SCHEMA_DRIFT;
+
+/// Known-good event samples, one per event type
+pub const CANARY_FIXTURES: &str = include_str!("fixtures/canary_events.json");
+
+/// Most recent events scanned on the node when looking for live samples
+const LIVE_SAMPLE_SCAN: usize = 1000;
+
+/// Events fetched per page while scanning for live samples
+const LIVE_SAMPLE_PAGE: usize = 100;
+
+/// An event type and the fields that must survive parsing
+struct CanaryCheck {
+    event_type: &'static str,
+    required_fields: &'static [&'static str],
+    parse: fn(&Value) -> Result<Value>,
+}
+
+/// Parse through the regular event parser and re-serialize for inspection
+fn parse_as<T: DeserializeOwned + Serialize>(data: &Value) -> Result<Value> {
+    Ok(serde_json::to_value(parse_event::<T>(data)?)?)
+}
+
+const CHECKS: &[CanaryCheck] = &[
+    CanaryCheck {
+        event_type: "ProfileCreatedEvent",
+        required_fields: &["profile_id", "owner_address", "username"],
+        parse: parse_as::<ProfileCreatedEvent>,
+    },
+    CanaryCheck {
+        event_type: "FollowEvent",
+        required_fields: &["follower", "following"],
+        parse: parse_as::<FollowEvent>,
+    },
+    CanaryCheck {
+        event_type: "UnfollowEvent",
+        required_fields: &["follower", "unfollowed"],
+        parse: parse_as::<UnfollowEvent>,
+    },
+    CanaryCheck {
+        event_type: "PlatformCreatedEvent",
+        required_fields: &["platform_id", "name", "developer"],
+        parse: parse_as::<PlatformCreatedEvent>,
+    },
+    CanaryCheck {
+        event_type: "UserBlockEvent",
+        required_fields: &["blocker", "blocked"],
+        parse: parse_as::<UserBlockEvent>,
+    },
+];
+
+/// Whether a parsed field is effectively missing (absent, null, empty or a placeholder)
+fn is_missing(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => true,
+        Some(Value::String(s)) => super::sentinel::is_sentinel(s),
+        _ => false,
+    }
+}
+
+/// Required fields of `check` that come out missing when `sample` is parsed
+fn missing_fields(check: &CanaryCheck, sample: &Value) -> Vec<String> {
+    match (check.parse)(sample) {
+        Ok(parsed) => check.required_fields
+            .iter()
+            .filter(|field| is_missing(parsed.get(**field)))
+            .map(|field| field.to_string())
+            .collect(),
+        // A sample that no longer parses at all is drift on every required field
+        Err(_) => check.required_fields.iter().map(|f| f.to_string()).collect(),
+    }
+}
+
+/// Run the canary against a set of fixtures, returning `(event_type, missing_fields)` for each drifted type
+pub fn check_fixtures(fixtures: &Value) -> Vec<(String, Vec<String>)> {
+    let mut drifted = Vec::new();
+    
+    for check in CHECKS {
+        let missing: Vec<String> = match fixtures.get(check.event_type) {
+            Some(sample) => missing_fields(check, sample),
+            None => {
+                error!("Schema canary has no fixture for {}", check.event_type);
+                Vec::new()
+            }
+        };
+        
+        SCHEMA_DRIFT
+            .with_label_values(&[check.event_type])
+            .set(if missing.is_empty() { 0 } else { 1 });
+        
+        if !missing.is_empty() {
+            drifted.push((check.event_type.to_string(), missing));
+        }
+    }
+    
+    drifted
+}
+
+/// Run the canary against the bundled fixtures, returning true when no drift was detected
+pub fn run_schema_canary() -> bool {
+    let fixtures: Value = match serde_json::from_str(CANARY_FIXTURES) {
+        Ok(fixtures) => fixtures,
+        Err(e) => {
+            error!("Failed to load schema canary fixtures: {}", e);
+            return false;
+        }
+    };
+    
+    let drifted = check_fixtures(&fixtures);
+    
+    if drifted.is_empty() {
+        info!("Schema canary passed for {} event types", CHECKS.len());
+        return true;
+    }
+    
+    report_drift(&drifted);
+    false
+}
+
+fn report_drift(drifted: &[(String, Vec<String>)]) {
+    for (event_type, missing) in drifted {
+        error!(
+            "🚨 SCHEMA DRIFT: {} parsed without required fields {:?} - event parsing is out of sync with the on-chain package",
+            event_type, missing
+        );
+    }
+}
+
+/// The newest event of each checked type among the node's most recent events, keyed like the fixtures
+///
+/// Only events from monitored packages are taken. Types with no event in the last
+/// `LIVE_SAMPLE_SCAN` events are left out.
+pub async fn sample_live_events(client: &MysClient) -> Result<Value> {
+    let mut samples = serde_json::Map::new();
+    let mut cursor = None;
+    let mut scanned = 0;
+    
+    while scanned < LIVE_SAMPLE_SCAN && samples.len() < CHECKS.len() {
+        let page = client.event_api()
+            .query_events(EventFilter::All([]), cursor, Some(LIVE_SAMPLE_PAGE), true)
+            .await?;
+        scanned += page.data.len();
+        
+        for event in &page.data {
+            let event_type = event.type_.name.as_str();
+            if samples.contains_key(event_type)
+                || !CHECKS.iter().any(|check| check.event_type == event_type)
+                || !crate::is_monitored_package(&event.type_.address.to_hex_literal())
+            {
+                continue;
+            }
+            
+            // Same preparation the listener gives event data before handlers parse it
+            let mut data = event.parsed_json.clone();
+            if super::event_data::sanitize_event_data(&mut data).is_ok() {
+                samples.insert(event_type.to_string(), data);
+            }
+        }
+        
+        match page.next_cursor {
+            Some(next) if page.has_next_page => cursor = Some(next),
+            _ => break,
+        }
+    }
+    
+    Ok(Value::Object(samples))
+}
+
+/// Check the newest on-chain sample of each event type, returning `(event_type, missing_fields)` for each drifted type
+///
+/// Only the types present in `samples` are checked; the others keep their fixture result.
+pub fn check_live_samples(samples: &Value) -> Vec<(String, Vec<String>)> {
+    let mut drifted = Vec::new();
+    
+    for check in CHECKS {
+        let Some(sample) = samples.get(check.event_type) else {
+            continue;
+        };
+        let missing = missing_fields(check, sample);
+        if !missing.is_empty() {
+            SCHEMA_DRIFT.with_label_values(&[check.event_type]).set(1);
+            drifted.push((check.event_type.to_string(), missing));
+        }
+    }
+    
+    drifted
+}
+
+/// Run the canary against recent events on the node at `rpc_url`, returning true when no drift was detected
+///
+/// A node that can't be reached is logged and counted as a pass; the fixture canary still ran.
+pub async fn run_live_schema_canary(rpc_url: &str) -> bool {
+    let samples = match MysClientBuilder::default().build(rpc_url).await {
+        Ok(client) => sample_live_events(&client).await,
+        Err(e) => Err(e.into()),
+    };
+    let samples = match samples {
+        Ok(samples) => samples,
+        Err(e) => {
+            warn!("Schema canary could not sample live events: {}", e);
+            return true;
+        }
+    };
+    
+    let sampled = samples.as_object().map(|samples| samples.len()).unwrap_or(0);
+    let drifted = check_live_samples(&samples);
+    
+    if drifted.is_empty() {
+        info!("Schema canary passed for {} of {} event types sampled from the node", sampled, CHECKS.len());
+        return true;
+    }
+    
+    report_drift(&drifted);
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixtures() -> Value {
+        serde_json::from_str(CANARY_FIXTURES).unwrap()
+    }
+
+    #[test]
+    fn bundled_fixtures_pass() {
+        assert!(check_fixtures(&fixtures()).is_empty());
+    }
+
+    #[test]
+    fn live_sample_missing_a_field_is_drift() {
+        let mut follow = fixtures()["FollowEvent"].clone();
+        follow.as_object_mut().unwrap().remove("following");
+        let samples = serde_json::json!({ "FollowEvent": follow });
+        
+        let drifted = check_live_samples(&samples);
+        assert_eq!(drifted.len(), 1);
+        assert_eq!(drifted[0].0, "FollowEvent");
+        assert!(drifted[0].1.contains(&"following".to_string()));
+    }
+
+    #[test]
+    fn types_without_a_live_sample_are_not_checked() {
+        let samples = serde_json::json!({ "FollowEvent": fixtures()["FollowEvent"].clone() });
+        assert!(check_live_samples(&samples).is_empty());
+        assert!(check_live_samples(&serde_json::json!({})).is_empty());
+    }
+}
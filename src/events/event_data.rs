@@ -0,0 +1,158 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use serde_json::Value;
+
+/// Why an event's data can't be indexed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MalformedEventData {
+    /// Data is a string that doesn't hold valid JSON
+    InvalidJson(String),
+    /// Data is valid JSON but not an object, so it can't be a Move struct
+    NotAnObject(&'static str),
+    /// Serialized data is larger than `MAX_EVENT_DATA_BYTES`
+    TooLarge { bytes: usize, limit: usize },
+}
+
+impl MalformedEventData {
+    /// Short label for metrics
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::InvalidJson(_) => "invalid_json",
+            Self::NotAnObject(_) => "not_an_object",
+            Self::TooLarge { .. } => "too_large",
+        }
+    }
+}
+
+impl std::fmt::Display for MalformedEventData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidJson(e) => write!(f, "Malformed event data: invalid JSON ({})", e),
+            Self::NotAnObject(kind) => write!(f, "Malformed event data: expected a JSON object, got {}", kind),
+            Self::TooLarge { bytes, limit } => write!(f, "Event data is {} bytes, over the {} byte limit", bytes, limit),
+        }
+    }
+}
+
+impl std::error::Error for MalformedEventData {}
+
+/// Check and clean an event's data before it reaches the handlers
+///
+/// Data sent as a JSON string is parsed in place. NUL characters are stripped from every string
+/// and key, since Postgres rejects them in both text and JSONB columns and the insert would
+/// fail deep inside a handler instead. Returns the number of strings that were changed.
+pub fn sanitize_event_data(data: &mut Value) -> Result<usize, MalformedEventData> {
+    if let Value::String(text) = data {
+        *data = serde_json::from_str(text).map_err(|e| MalformedEventData::InvalidJson(e.to_string()))?;
+    }
+    
+    match data {
+        Value::Object(_) => Ok(strip_nul(data)),
+        Value::Null => Err(MalformedEventData::NotAnObject("null")),
+        Value::Bool(_) => Err(MalformedEventData::NotAnObject("a boolean")),
+        Value::Number(_) => Err(MalformedEventData::NotAnObject("a number")),
+        Value::String(_) => Err(MalformedEventData::NotAnObject("a string")),
+        Value::Array(_) => Err(MalformedEventData::NotAnObject("an array")),
+    }
+}
+
+/// Reject data whose serialized JSON is longer than `limit` bytes; a limit of 0 disables the check
+///
+/// Returns the serialized size.
+pub fn check_event_data_size(data: &Value, limit: usize) -> Result<usize, MalformedEventData> {
+    let bytes = serialized_len(data);
+    if limit > 0 && bytes > limit {
+        return Err(MalformedEventData::TooLarge { bytes, limit });
+    }
+    Ok(bytes)
+}
+
+/// The first `max_bytes` of `data` as JSON text, cut on a character boundary
+///
+/// Used in place of a payload too large to store whole.
+pub fn truncated_preview(data: &Value, max_bytes: usize) -> String {
+    let mut text = data.to_string();
+    if text.len() > max_bytes {
+        let mut end = max_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
+
+/// Length of `data` serialized as compact JSON, without building the string
+fn serialized_len(data: &Value) -> usize {
+    struct Counter(usize);
+    
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    
+    let mut counter = Counter(0);
+    // Writing a `Value` to an infallible writer can't fail
+    let _ = serde_json::to_writer(&mut counter, data);
+    counter.0
+}
+
+/// Remove NUL characters from every string and key in `value`, returning how many changed
+fn strip_nul(value: &mut Value) -> usize {
+    match value {
+        Value::String(text) if text.contains('\0') => {
+            text.retain(|c| c != '\0');
+            1
+        }
+        Value::Array(items) => items.iter_mut().map(strip_nul).sum(),
+        Value::Object(map) => {
+            let mut changed = 0;
+            if map.keys().any(|key| key.contains('\0')) {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, value)| (key.replace('\0', ""), value))
+                    .collect();
+                changed += 1;
+            }
+            changed + map.values_mut().map(strip_nul).sum::<usize>()
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn size_check_measures_compact_json() {
+        let data = json!({ "a": 1 });
+        assert_eq!(check_event_data_size(&data, 7), Ok(7));
+        assert_eq!(
+            check_event_data_size(&data, 6),
+            Err(MalformedEventData::TooLarge { bytes: 7, limit: 6 })
+        );
+    }
+
+    #[test]
+    fn zero_limit_disables_the_size_check() {
+        let data = json!({ "bio": "x".repeat(1000) });
+        assert!(check_event_data_size(&data, 0).is_ok());
+    }
+
+    #[test]
+    fn preview_is_cut_on_a_character_boundary() {
+        let data = json!("ééé");
+        // `"` is one byte and each `é` two, so byte 4 falls inside the second `é`
+        assert_eq!(truncated_preview(&data, 4), "\"é");
+        assert_eq!(truncated_preview(&data, 100), "\"ééé\"");
+    }
+}
@@ -6,6 +6,9 @@ pub mod profile_event_types;
 pub mod social_graph_events;
 pub mod platform_events;
 pub mod blocking_events;
+pub mod canary;
+pub mod sentinel;
+pub mod event_data;
 
 // Re-export all profile events
 pub use profile_events::{
@@ -15,6 +18,7 @@ pub use profile_events::{
     UsernameRegisteredEvent,
     ProfileFollowEvent,
     ProfileJoinedPlatformEvent,
+    ProfileVerifiedEvent,
 };
 
 // Re-export profile event types
@@ -75,6 +79,33 @@ pub struct ContentCreatedEvent {
     pub parent_id: Option<String>,
 }
 
+impl ContentCreatedEvent {
+    /// Convert to a content row created at `timestamp_ms`, the time of the checkpoint that
+    /// holds the event; counters start at zero
+    pub fn into_model(&self, timestamp_ms: u64) -> Result<crate::models::content::NewContent> {
+        if self.content_id.is_empty() || self.creator_id.is_empty() || self.platform_id.is_empty() {
+            return Err(anyhow!("ContentCreatedEvent is missing content_id, creator_id or platform_id"));
+        }
+        
+        let created_at = chrono::DateTime::from_timestamp_millis(timestamp_ms as i64)
+            .ok_or_else(|| anyhow!("Invalid checkpoint timestamp: {}", timestamp_ms))?
+            .naive_utc();
+        
+        Ok(crate::models::content::NewContent {
+            id: self.content_id.clone(),
+            creator_id: self.creator_id.clone(),
+            platform_id: self.platform_id.clone(),
+            parent_id: self.parent_id.clone(),
+            like_count: 0,
+            view_count: 0,
+            share_count: 0,
+            comment_count: 0,
+            has_ip_registered: false,
+            created_at,
+        })
+    }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ContentInteractionEvent {
     pub content_id: String,
@@ -82,6 +113,14 @@ pub struct ContentInteractionEvent {
     pub interaction_type: String,
 }
 
+/// Reversal of a `ContentInteractionEvent`, e.g. an unlike or unshare
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ContentInteractionRemovedEvent {
+    pub content_id: String,
+    pub profile_id: String,
+    pub interaction_type: String,
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct EntityBlockedEvent {
     pub blocker_id: String,
@@ -128,14 +167,6 @@ impl PlatformCreatedEvent {
     }
 }
 
-// For ContentCreatedEvent
-impl ContentCreatedEvent {
-    pub fn into_model(&self) -> Result<()> {
-        // Placeholder implementation
-        unimplemented!("ContentCreatedEvent::into_model() not implemented yet")
-    }
-}
-
 // For ContentInteractionEvent
 impl ContentInteractionEvent {
     pub fn into_model(&self) -> Result<()> {
@@ -198,7 +229,30 @@ pub const MODULE_PREFIX_MY_IP: &str = module_prefix!();
 pub const MODULE_PREFIX_FEE_DISTRIBUTION: &str = module_prefix!();
 pub const MODULE_PREFIX_SOCIAL_GRAPH: &str = module_prefix!();
 
+/// Rank used to order the events of one transaction before they are applied
+///
+/// A transaction can create an entity and act on it (e.g. `ProfileCreatedEvent` followed
+/// by `UsernameRegisteredEvent`), and the events are not guaranteed to arrive in that
+/// order. Creation events rank first so their dependents find the entity in place.
+pub fn intra_transaction_rank(event_type: &str) -> u8 {
+    if event_type.ends_with("CreatedEvent") {
+        0
+    } else {
+        1
+    }
+}
+
+/// Count which `parse_event` layer handled an event, labelled with the event struct's short name
+fn record_parse_layer<T>(layer: &str) {
+    let event_type = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+    crate::metrics::PARSE_FALLBACK_DEPTH
+        .with_label_values(&[event_type, layer])
+        .inc();
+}
+
 /// Parse an event from blockchain JSON
+///
+/// Each call is counted in `PARSE_FALLBACK_DEPTH` by the layer that parsed it.
 pub fn parse_event<T: DeserializeOwned>(json_value: &Value) -> Result<T> {
     // Print detailed event structure for debugging
     tracing::info!("Parsing event JSON: {}", serde_json::to_string_pretty(json_value).unwrap_or_default());
@@ -212,6 +266,16 @@ pub fn parse_event<T: DeserializeOwned>(json_value: &Value) -> Result<T> {
                            // PrivateDataUpdatedEvent removed
                            is_username_event;
                            
+    // A payload with two spellings of an aliased field would fail as a duplicate field
+    let has_field_aliases = event_type.contains("ProfileCreatedEvent") || event_type.contains("ProfileUpdatedEvent");
+    let deduplicated;
+    let json_value = if has_field_aliases {
+        deduplicated = profile_events::keep_one_spelling(json_value);
+        &deduplicated
+    } else {
+        json_value
+    };
+    
     // Extra logging for username events
     if is_username_event {
         tracing::info!("Parsing a username event: {}", event_type);
@@ -431,46 +495,7 @@ pub fn parse_event<T: DeserializeOwned>(json_value: &Value) -> Result<T> {
                     }
                 }
                 
-                // Special handling for URLs when we have the "has_X" flags but no URL values
-                if let Some(has_profile_pic) = extracted_fields.get("has_profile_picture") {
-                    if has_profile_pic.as_bool().unwrap_or(false) && !extracted_fields.contains_key("profile_photo") {
-                        // Try to find profile photo in any field that might have it
-                        for (k, v) in fields.iter() {
-                            if k.contains("profile") && k.contains("pic") || k.contains("photo") || k.contains("avatar") {
-                                tracing::info!("Found potential profile photo field '{}': {}", k, v);
-                                if let Some(obj) = v.as_object() {
-                                    if let Some(extracted) = extract_object_value(obj) {
-                                        extracted_fields.insert("profile_photo".to_string(), extracted);
-                                        break;
-                                    }
-                                } else if v.is_string() {
-                                    extracted_fields.insert("profile_photo".to_string(), v.clone());
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
-                
-                if let Some(has_cover) = extracted_fields.get("has_cover_photo") {
-                    if has_cover.as_bool().unwrap_or(false) && !extracted_fields.contains_key("cover_photo") {
-                        // Try to find cover photo in any field that might have it
-                        for (k, v) in fields.iter() {
-                            if k.contains("cover") {
-                                tracing::info!("Found potential cover photo field '{}': {}", k, v);
-                                if let Some(obj) = v.as_object() {
-                                    if let Some(extracted) = extract_object_value(obj) {
-                                        extracted_fields.insert("cover_photo".to_string(), extracted);
-                                        break;
-                                    }
-                                } else if v.is_string() {
-                                    extracted_fields.insert("cover_photo".to_string(), v.clone());
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                }
+                // The has_X flags are kept as-is; a flagged photo without a URL is stored as pending
                 
                 // Parse bio similarly to display_name if not already found
                 if !extracted_fields.contains_key("bio") && extracted_fields.contains_key("display_name") {
@@ -671,17 +696,17 @@ pub fn parse_event<T: DeserializeOwned>(json_value: &Value) -> Result<T> {
                     // Add default values for any required fields that are still missing
                     if !extracted_fields.contains_key("username") {
                         tracing::warn!("Username still not found, setting empty default");
-                        extracted_fields.insert("username".to_string(), Value::String("unknown".to_string()));
+                        extracted_fields.insert("username".to_string(), Value::String(sentinel::UNKNOWN_SENTINEL.to_string()));
                     }
                     
                     if !extracted_fields.contains_key("profile_id") {
                         tracing::warn!("profile_id still not found, setting empty default");
-                        extracted_fields.insert("profile_id".to_string(), Value::String("unknown".to_string()));
+                        extracted_fields.insert("profile_id".to_string(), Value::String(sentinel::UNKNOWN_SENTINEL.to_string()));
                     }
                     
                     if !extracted_fields.contains_key("owner_address") {
                         tracing::warn!("owner_address still not found, setting empty default");
-                        extracted_fields.insert("owner_address".to_string(), Value::String("unknown".to_string()));
+                        extracted_fields.insert("owner_address".to_string(), Value::String(sentinel::UNKNOWN_SENTINEL.to_string()));
                     }
                 } else if !extracted_fields.contains_key("username") {
                     // Try to extract username from any field that might contain it
@@ -692,7 +717,12 @@ pub fn parse_event<T: DeserializeOwned>(json_value: &Value) -> Result<T> {
                 }
                 
                 // Create a special event object with our extracted fields
-                let custom_event = json!(extracted_fields);
+                // The mappings above can extract both spellings of a field, e.g. created_at and registered_at
+                let custom_event = if has_field_aliases {
+                    profile_events::keep_one_spelling(&json!(extracted_fields))
+                } else {
+                    json!(extracted_fields)
+                };
                 tracing::info!("Created custom event object: {}", custom_event);
                 
                 // Show field extraction outcome for debug purposes
@@ -705,6 +735,7 @@ pub fn parse_event<T: DeserializeOwned>(json_value: &Value) -> Result<T> {
                 // Try to parse our custom event
                 if let Ok(event) = serde_json::from_value::<T>(custom_event.clone()) {
                     tracing::info!("Successfully parsed custom event");
+                    record_parse_layer::<T>("manual");
                     return Ok(event);
                 } else {
                     tracing::warn!("Failed to parse custom event");
@@ -716,6 +747,7 @@ pub fn parse_event<T: DeserializeOwned>(json_value: &Value) -> Result<T> {
     // Try direct deserialization first
     if let Ok(result) = serde_json::from_value::<T>(json_value.clone()) {
         tracing::info!("Direct deserialization successful");
+        record_parse_layer::<T>("direct");
         return Ok(result);
     }
     
@@ -785,5 +817,155 @@ pub fn parse_event<T: DeserializeOwned>(json_value: &Value) -> Result<T> {
         }
     }
 
+    record_parse_layer::<T>(if result.is_ok() { "nested" } else { "failed" });
     result.map_err(|e| anyhow!("Failed to parse event: {}", e))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Only parsed here, so its counters aren't touched by other tests running in parallel
+    #[derive(Debug, serde::Deserialize)]
+    struct FallbackProbeEvent {
+        id: u32,
+    }
+
+    fn parsed_at(layer: &str) -> u64 {
+        crate::metrics::PARSE_FALLBACK_DEPTH
+            .with_label_values(&["FallbackProbeEvent", layer])
+            .get()
+    }
+
+    #[test]
+    fn parse_layer_is_counted_for_direct_nested_and_failed_parses() {
+        let direct: FallbackProbeEvent = parse_event(&json!({ "id": 1 })).unwrap();
+        assert_eq!(direct.id, 1);
+        assert_eq!((parsed_at("direct"), parsed_at("nested")), (1, 0));
+
+        let nested: FallbackProbeEvent = parse_event(&json!({ "fields": { "id": 2 } })).unwrap();
+        assert_eq!(nested.id, 2);
+        assert_eq!((parsed_at("direct"), parsed_at("nested")), (1, 1));
+
+        assert!(parse_event::<FallbackProbeEvent>(&json!({ "other": 3 })).is_err());
+        assert_eq!(parsed_at("failed"), 1);
+    }
+
+    #[test]
+    fn payload_with_two_spellings_of_a_field_parses() {
+        let created: ProfileCreatedEvent = parse_event(&json!({
+            "profile_id": "0xprofile",
+            "owner_address": "0xowner",
+            "display_name": "Name",
+            "bio": "bio",
+            "description": "description",
+            "created_at": 5,
+            "registered_at": 6,
+        }))
+        .unwrap();
+        assert_eq!(created.bio.as_deref(), Some("bio"));
+        assert_eq!(created.created_at, 5);
+        
+        let updated: ProfileUpdatedEvent = parse_event(&json!({
+            "profile_id": "0xprofile",
+            "updated_at": "7",
+            "timestamp": 8,
+        }))
+        .unwrap();
+        assert_eq!(updated.updated_at, 7);
+    }
+
+    #[test]
+    fn flagged_photo_without_a_url_is_stored_as_pending() {
+        let direct = json!({
+            "profile_id": "0xprofile",
+            "owner_address": "0xowner",
+            "display_name": "Name",
+            "has_profile_picture": true,
+            "has_cover_photo": false,
+        });
+        let nested = json!({ "fields": direct.clone() });
+        
+        for payload in [direct, nested] {
+            let created: ProfileCreatedEvent = parse_event(&payload).unwrap();
+            let profile = created.into_model().unwrap();
+            assert_eq!(profile.profile_photo, None);
+            assert!(profile.has_profile_photo, "{}", payload);
+            assert!(!profile.has_cover_photo, "{}", payload);
+        }
+    }
+
+    #[test]
+    fn fields_container_with_two_spellings_of_a_field_parses() {
+        let created: ProfileCreatedEvent = parse_event(&json!({
+            "fields": {
+                "profile_id": "0xprofile",
+                "owner": "0xowner",
+                "username": "name",
+                "display_name": "Name",
+                "created_at": 5,
+                "registered_at": 6,
+            }
+        }))
+        .unwrap();
+        assert_eq!(created.profile_id, "0xprofile");
+        assert_eq!(created.created_at, 5);
+    }
+
+    #[test]
+    fn alias_spellings_deserialize_directly() {
+        let created: ProfileCreatedEvent = serde_json::from_value(json!({
+            "id": "0xprofile",
+            "owner": "0xowner",
+            "avatar_url": "https://photo",
+            "cover_url": "https://cover",
+            "description": "bio",
+            "registered_at": 6,
+        }))
+        .unwrap();
+        assert_eq!(created.profile_id, "0xprofile");
+        assert_eq!(created.owner_address, "0xowner");
+        assert_eq!(created.profile_photo.as_deref(), Some("https://photo"));
+        assert_eq!(created.cover_photo.as_deref(), Some("https://cover"));
+        assert_eq!(created.bio.as_deref(), Some("bio"));
+        assert_eq!(created.created_at, 6);
+    }
+
+    fn content_created(parent_id: Option<&str>) -> ContentCreatedEvent {
+        ContentCreatedEvent {
+            content_id: "0xcontent".to_string(),
+            creator_id: "0xcreator".to_string(),
+            platform_id: "0xplatform".to_string(),
+            parent_id: parent_id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn content_into_model_dates_the_row_at_the_checkpoint() {
+        let content = content_created(Some("0xparent")).into_model(1_700_000_000_123).unwrap();
+        
+        assert_eq!(content.id, "0xcontent");
+        assert_eq!(content.creator_id, "0xcreator");
+        assert_eq!(content.platform_id, "0xplatform");
+        assert_eq!(content.parent_id.as_deref(), Some("0xparent"));
+        assert_eq!(content.created_at.and_utc().timestamp_millis(), 1_700_000_000_123);
+    }
+
+    #[test]
+    fn content_into_model_starts_counters_at_zero() {
+        let content = content_created(None).into_model(0).unwrap();
+        
+        assert_eq!(
+            (content.like_count, content.view_count, content.share_count, content.comment_count),
+            (0, 0, 0, 0)
+        );
+        assert!(!content.has_ip_registered);
+        assert!(content.parent_id.is_none());
+    }
+
+    #[test]
+    fn content_into_model_rejects_missing_ids() {
+        let mut event = content_created(None);
+        event.platform_id.clear();
+        assert!(event.into_model(0).is_err());
+    }
+}
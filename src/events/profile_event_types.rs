@@ -20,6 +20,8 @@ pub enum ProfileEventType {
     PlatformJoined,
     // User leaves a platform
     PlatformLeft,
+    // Profile verification granted or revoked
+    ProfileVerified,
 }
 
 impl ProfileEventType {
@@ -34,6 +36,7 @@ impl ProfileEventType {
             s if s.contains("::BlockRemovedEvent") || s.contains("::UserUnblockEvent") => Some(Self::BlockRemoved),
             s if s.contains("::UserJoinedPlatformEvent") || s.contains("::PlatformJoinedEvent") => Some(Self::PlatformJoined),
             s if s.contains("::UserLeftPlatformEvent") || s.contains("::PlatformLeftEvent") => Some(Self::PlatformLeft),
+            s if s.contains("::ProfileVerifiedEvent") => Some(Self::ProfileVerified),
             _ => None,
         }
     }
@@ -49,6 +52,7 @@ impl ProfileEventType {
             Self::BlockRemoved => "BlockRemovedEvent",
             Self::PlatformJoined => "PlatformJoinedEvent",
             Self::PlatformLeft => "PlatformLeftEvent",
+            Self::ProfileVerified => "ProfileVerifiedEvent",
         }
     }
 }
@@ -135,6 +139,8 @@ pub struct BlockRemovedEvent {
 pub struct PlatformJoinedEvent {
     pub profile_id: String,
     pub platform_id: String,
+    /// Seconds since the epoch; 0 when the event didn't carry one
+    #[serde(default)]
     pub timestamp: u64,
 }
 
@@ -142,5 +148,7 @@ pub struct PlatformJoinedEvent {
 pub struct PlatformLeftEvent {
     pub profile_id: String,
     pub platform_id: String,
+    /// Seconds since the epoch; 0 when the event didn't carry one
+    #[serde(default)]
     pub timestamp: u64,
 }
\ No newline at end of file
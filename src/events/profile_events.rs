@@ -6,7 +6,7 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize, Deserializer};
 use std::str::FromStr;
 
-use crate::models::profile::NewProfile;
+use crate::models::profile::{NewProfile, UpdateProfile};
 
 /// Helper function to deserialize strings as numbers
 fn deserialize_number_from_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
@@ -65,6 +65,15 @@ where
     }
 }
 
+/// The event's username, or the placeholder `user_` + the first 8 characters of the owner address
+/// when it is missing or itself a parser placeholder
+fn username_or_placeholder(username: &Option<String>, owner_address: &str) -> String {
+    match username {
+        Some(name) if !super::sentinel::is_sentinel(name) => name.clone(),
+        _ => format!("user_{}", owner_address.chars().take(8).collect::<String>())
+    }
+}
+
 /// Helper function for default timestamp
 fn default_timestamp() -> u64 {
     std::time::SystemTime::now()
@@ -73,6 +82,42 @@ fn default_timestamp() -> u64 {
         .as_secs()
 }
 
+/// Spellings `ProfileCreatedEvent`/`ProfileUpdatedEvent` accept for a field, canonical name first
+const FIELD_SPELLINGS: &[&[&str]] = &[
+    &["profile_id", "id"],
+    &["owner_address", "owner"],
+    &["profile_photo", "profile_picture", "profile_photo_url", "avatar_url", "avatar"],
+    &["cover_photo", "cover_url", "cover_photo_url"],
+    &["has_profile_photo", "has_profile_picture"],
+    &["bio", "description"],
+    &["created_at", "registered_at"],
+    &["updated_at", "timestamp"],
+];
+
+/// Copy of a profile event payload with a single spelling of each aliased field
+///
+/// serde rejects a payload carrying two spellings of one field as a duplicate, so the first
+/// spelling in `FIELD_SPELLINGS` wins. A nested `fields` object is cleaned up too.
+pub fn keep_one_spelling(value: &serde_json::Value) -> serde_json::Value {
+    fn dedup(map: &mut serde_json::Map<String, serde_json::Value>) {
+        for spellings in FIELD_SPELLINGS {
+            let present: Vec<&str> = spellings.iter().copied().filter(|name| map.contains_key(*name)).collect();
+            for name in present.iter().skip(1) {
+                map.remove(*name);
+            }
+        }
+    }
+    
+    let mut value = value.clone();
+    if let serde_json::Value::Object(map) = &mut value {
+        dedup(map);
+        if let Some(serde_json::Value::Object(fields)) = map.get_mut("fields") {
+            dedup(fields);
+        }
+    }
+    value
+}
+
 /// Event emitted when a profile is created
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProfileCreatedEvent {
@@ -93,19 +138,27 @@ pub struct ProfileCreatedEvent {
     pub display_name: String,
     
     /// Profile photo URL - can come from multiple fields
-    #[serde(rename = "profile_photo", alias = "profile_picture", alias = "avatar_url", default)]
+    #[serde(rename = "profile_photo", alias = "profile_picture", alias = "profile_photo_url", alias = "avatar_url", alias = "avatar", default)]
     pub profile_photo: Option<String>,
     
     /// Cover photo URL
-    #[serde(rename = "cover_photo", alias = "cover_url", default)]
+    #[serde(rename = "cover_photo", alias = "cover_url", alias = "cover_photo_url", default)]
     pub cover_photo: Option<String>,
     
-    /// Bio - may be a string directly in the event
+    /// Whether the profile has a photo; the URL may arrive later
+    #[serde(alias = "has_profile_picture", default)]
+    pub has_profile_photo: Option<bool>,
+    
+    /// Whether the profile has a cover photo; the URL may arrive later
     #[serde(default)]
+    pub has_cover_photo: Option<bool>,
+    
+    /// Bio - may be a string directly in the event
+    #[serde(rename = "bio", alias = "description", default)]
     pub bio: Option<String>,
     
     /// Timestamp of profile creation
-    #[serde(rename = "created_at", default = "default_timestamp", deserialize_with = "deserialize_number_from_string")]
+    #[serde(rename = "created_at", alias = "registered_at", default = "default_timestamp", deserialize_with = "deserialize_number_from_string")]
     pub created_at: u64,
 }
 
@@ -117,10 +170,7 @@ impl ProfileCreatedEvent {
         let now = Utc::now().naive_utc();
         
         // Use username if available, otherwise generate a placeholder
-        let username = match &self.username {
-            Some(name) => name.clone(),
-            None => format!("user_{}", self.owner_address.chars().take(8).collect::<String>())
-        };
+        let username = username_or_placeholder(&self.username, &self.owner_address);
         
         // Log all fields for debugging
         tracing::info!("Converting ProfileCreatedEvent to database model:");
@@ -130,6 +180,7 @@ impl ProfileCreatedEvent {
         tracing::info!("  bio: {:?}", self.bio);
         tracing::info!("  profile_photo: {:?}", self.profile_photo);
         tracing::info!("  cover_photo: {:?}", self.cover_photo);
+        tracing::info!("  has_profile_photo: {:?}, has_cover_photo: {:?}", self.has_profile_photo, self.has_cover_photo);
         tracing::info!("  using current timestamp instead of blockchain epoch");
         
         // Always use the profile photo if it exists
@@ -172,6 +223,11 @@ impl ProfileCreatedEvent {
             github_username: None,
             // BlockList object address - will be set when the BlockListCreatedEvent is received
             block_list_address: None,
+            // Attached by the handler when STORE_SOURCE_EVENTS is on
+            source_event: None,
+            has_profile_photo: photo_flag(self.has_profile_photo, &self.profile_photo),
+            has_cover_photo: photo_flag(self.has_cover_photo, &self.cover_photo),
+            last_activity_at: Some(now),
         })
     }
 }
@@ -196,19 +252,27 @@ pub struct ProfileUpdatedEvent {
     pub owner_address: String,
     
     /// Profile photo URL
-    #[serde(rename = "profile_photo", alias = "profile_picture", alias = "avatar_url", default)]
+    #[serde(rename = "profile_photo", alias = "profile_picture", alias = "profile_photo_url", alias = "avatar_url", alias = "avatar", default)]
     pub profile_photo: Option<String>,
     
     /// Cover photo URL
-    #[serde(rename = "cover_photo", alias = "cover_url", default)]
+    #[serde(rename = "cover_photo", alias = "cover_url", alias = "cover_photo_url", default)]
     pub cover_photo: Option<String>,
     
+    /// Whether the profile has a photo; the URL may arrive later
+    #[serde(alias = "has_profile_picture", default)]
+    pub has_profile_photo: Option<bool>,
+    
+    /// Whether the profile has a cover photo; the URL may arrive later
+    #[serde(default)]
+    pub has_cover_photo: Option<bool>,
+    
     /// Bio
     #[serde(rename = "bio", alias = "description", default)]
     pub bio: Option<String>,
     
     /// Update timestamp
-    #[serde(rename = "updated_at", default = "default_timestamp", deserialize_with = "deserialize_number_from_string")]
+    #[serde(rename = "updated_at", alias = "timestamp", default = "default_timestamp", deserialize_with = "deserialize_number_from_string")]
     pub updated_at: u64,
     
     // All sensitive fields that are client-side encrypted
@@ -261,6 +325,95 @@ pub struct ProfileUpdatedEvent {
     pub github_username: Option<String>,
 }
 
+impl ProfileUpdatedEvent {
+    /// Changes to apply to an existing profile; fields the event leaves out are kept
+    pub fn into_update_model(&self) -> UpdateProfile {
+        UpdateProfile {
+            display_name: self.display_name.clone(),
+            bio: self.bio.clone(),
+            profile_photo: self.profile_photo.clone(),
+            website: None,
+            cover_photo: self.cover_photo.clone(),
+            sensitive_data_updated_at: Some(Utc::now().naive_utc()),
+            followers_count: None,
+            following_count: None,
+            birthdate: self.birthdate.clone(),
+            current_location: self.current_location.clone(),
+            raised_location: self.raised_location.clone(),
+            phone: self.phone.clone(),
+            email: self.email.clone(),
+            gender: self.gender.clone(),
+            political_view: self.political_view.clone(),
+            religion: self.religion.clone(),
+            education: self.education.clone(),
+            primary_language: self.primary_language.clone(),
+            relationship_status: self.relationship_status.clone(),
+            x_username: self.x_username.clone(),
+            mastodon_username: self.mastodon_username.clone(),
+            facebook_username: self.facebook_username.clone(),
+            reddit_username: self.reddit_username.clone(),
+            github_username: self.github_username.clone(),
+            block_list_address: None,
+            // Only touch the photo flags when the event says something about the photo
+            has_profile_photo: self.has_profile_photo.or(self.profile_photo.as_ref().map(|url| !url.is_empty())),
+            has_cover_photo: self.has_cover_photo.or(self.cover_photo.as_ref().map(|url| !url.is_empty())),
+            source_event: None,
+        }
+    }
+
+    /// Build a profile row from the update alone, for when the profile hasn't been indexed yet
+    ///
+    /// Counts start at 0; a later `ProfileCreatedEvent` for the same profile_id fills in the rest.
+    pub fn into_stub_model(&self) -> NewProfile {
+        let now = Utc::now().naive_utc();
+        
+        NewProfile {
+            owner_address: self.owner_address.clone(),
+            username: username_or_placeholder(&self.username, &self.owner_address),
+            display_name: self.display_name.clone(),
+            bio: self.bio.clone(),
+            profile_photo: self.profile_photo.clone(),
+            website: None,
+            created_at: now,
+            updated_at: now,
+            cover_photo: self.cover_photo.clone(),
+            profile_id: Some(self.profile_id.clone()),
+            sensitive_data_updated_at: Some(now),
+            followers_count: 0,
+            following_count: 0,
+            birthdate: self.birthdate.clone(),
+            current_location: self.current_location.clone(),
+            raised_location: self.raised_location.clone(),
+            phone: self.phone.clone(),
+            email: self.email.clone(),
+            gender: self.gender.clone(),
+            political_view: self.political_view.clone(),
+            religion: self.religion.clone(),
+            education: self.education.clone(),
+            primary_language: self.primary_language.clone(),
+            relationship_status: self.relationship_status.clone(),
+            x_username: self.x_username.clone(),
+            mastodon_username: self.mastodon_username.clone(),
+            facebook_username: self.facebook_username.clone(),
+            reddit_username: self.reddit_username.clone(),
+            github_username: self.github_username.clone(),
+            block_list_address: None,
+            source_event: None,
+            has_profile_photo: photo_flag(self.has_profile_photo, &self.profile_photo),
+            has_cover_photo: photo_flag(self.has_cover_photo, &self.cover_photo),
+            last_activity_at: Some(now),
+        }
+    }
+}
+
+/// A photo is present when the event flags it or carries a non-empty URL
+///
+/// The flag can be set while the URL is still missing, so it's stored separately rather than
+/// inferred from the URL column.
+pub fn photo_flag(flag: Option<bool>, url: &Option<String>) -> bool {
+    flag.unwrap_or(false) || url.as_deref().is_some_and(|url| !url.is_empty())
+}
+
 /// Event emitted when a username is updated
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsernameUpdatedEvent {
@@ -320,4 +473,24 @@ pub struct ProfileJoinedPlatformEvent {
     /// Timestamp of the join action
     #[serde(default, deserialize_with = "deserialize_optional_number_from_string")]
     pub joined_at: Option<u64>,
-}
\ No newline at end of file
+}
+
+/// Event emitted when a platform or authority verifies (or unverifies) a profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileVerifiedEvent {
+    /// ID of the profile
+    pub profile_id: String,
+    /// Whether the profile is verified - revocations emit `false`
+    #[serde(default = "default_verified")]
+    pub verified: bool,
+    /// Address of the verifying authority
+    #[serde(alias = "verifier", default)]
+    pub verified_by: Option<String>,
+    /// Timestamp of the verification
+    #[serde(alias = "verified_at", default, deserialize_with = "deserialize_optional_number_from_string")]
+    pub timestamp: Option<u64>,
+}
+
+fn default_verified() -> bool {
+    true
+}
@@ -0,0 +1,27 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use tracing::warn;
+
+use crate::metrics::SENTINEL_EVENTS;
+
+/// Placeholder the fallback parsers put in required fields they could not find
+pub const UNKNOWN_SENTINEL: &str = "unknown";
+
+/// Whether a required id field holds no real value (empty or the `"unknown"` placeholder)
+pub fn is_sentinel(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || value == UNKNOWN_SENTINEL
+}
+
+/// Skip an event if any of its required `(name, value)` fields is a sentinel, counting it for `handler`
+pub fn skip_sentinel(handler: &str, event_type: &str, fields: &[(&str, &str)]) -> bool {
+    match fields.iter().find(|(_, value)| is_sentinel(value)) {
+        Some((name, value)) => {
+            warn!("Skipping {} with placeholder {} {:?}", event_type, name, value);
+            SENTINEL_EVENTS.with_label_values(&[handler, name]).inc();
+            true
+        }
+        None => false,
+    }
+}
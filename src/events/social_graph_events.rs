@@ -17,6 +17,9 @@ pub struct FollowEvent {
     /// Optional timestamp - if not provided, current time will be used
     #[serde(default)]
     pub timestamp: Option<u64>,
+    /// Checkpoint timestamp of the event in milliseconds, filled in from the blockchain event
+    #[serde(default)]
+    pub timestamp_ms: Option<u64>,
 }
 
 /// Event emitted when a profile unfollows another profile
@@ -34,17 +37,22 @@ pub struct UnfollowEvent {
 impl FollowEvent {
     /// Convert the FollowEvent to a NewSocialGraphRelationship database model
     pub fn into_relationship(&self) -> Result<NewSocialGraphRelationship> {
-        // Use provided timestamp or current time
-        let timestamp = self.timestamp.unwrap_or_else(|| 
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-        );
-        
-        let created_at = DateTime::from_timestamp(timestamp as i64, 0)
-            .unwrap_or(Utc::now())
-            .naive_utc();
+        // Prefer the event's own time so backfilled follows keep their original created_at
+        let created_at = match self.timestamp_ms {
+            Some(timestamp_ms) => DateTime::from_timestamp_millis(timestamp_ms as i64),
+            None => {
+                // Use provided timestamp or current time
+                let timestamp = self.timestamp.unwrap_or_else(|| 
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs()
+                );
+                DateTime::from_timestamp(timestamp as i64, 0)
+            }
+        }
+        .unwrap_or(Utc::now())
+        .naive_utc();
             
         Ok(NewSocialGraphRelationship {
             follower_address: self.follower.clone(),
@@ -81,6 +89,9 @@ impl FollowEvent {
             reddit_username: None,
             github_username: None,
             block_list_address: None,
+            has_profile_photo: None,
+            has_cover_photo: None,
+            source_event: None,
         }
     }
     
@@ -112,6 +123,9 @@ impl FollowEvent {
             reddit_username: None,
             github_username: None,
             block_list_address: None,
+            has_profile_photo: None,
+            has_cover_photo: None,
+            source_event: None,
         }
     }
 }
@@ -145,6 +159,9 @@ impl UnfollowEvent {
             reddit_username: None,
             github_username: None,
             block_list_address: None,
+            has_profile_photo: None,
+            has_cover_photo: None,
+            source_event: None,
         }
     }
     
@@ -176,6 +193,39 @@ impl UnfollowEvent {
             reddit_username: None,
             github_username: None,
             block_list_address: None,
+            has_profile_photo: None,
+            has_cover_photo: None,
+            source_event: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follow(timestamp: Option<u64>, timestamp_ms: Option<u64>) -> FollowEvent {
+        FollowEvent {
+            follower: "0xa".to_string(),
+            following: "0xb".to_string(),
+            timestamp,
+            timestamp_ms,
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn relationship_is_created_at_the_event_time() {
+        let relationship = follow(Some(1), Some(1_700_000_000_123)).into_relationship().unwrap();
+        
+        assert_eq!(relationship.created_at.and_utc().timestamp_millis(), 1_700_000_000_123);
+        assert_eq!(relationship.follower_address, "0xa");
+        assert_eq!(relationship.following_address, "0xb");
+    }
+
+    #[test]
+    fn seconds_timestamp_is_used_without_an_event_time() {
+        let relationship = follow(Some(1_600_000_000), None).into_relationship().unwrap();
+        
+        assert_eq!(relationship.created_at.and_utc().timestamp(), 1_600_000_000);
+    }
+}
@@ -6,8 +6,10 @@ pub mod blockchain;
 pub mod config;
 pub mod db;
 pub mod events;
+pub mod metrics;
 pub mod models;
 pub mod schema;
+pub mod tasks;
 
 use once_cell::sync::OnceCell;
 
@@ -23,9 +25,49 @@ pub const PLATFORM_MODULE_NAME: &str = "platform";
 pub const SOCIAL_GRAPH_MODULE_NAME: &str = "social_graph";
 pub const BLOCK_LIST_MODULE_NAME: &str = "block_list";
 
+/// Modules whose events are indexed
+pub const MONITORED_MODULE_NAMES: &[&str] = &[
+    PROFILE_MODULE_NAME,
+    PLATFORM_MODULE_NAME,
+    SOCIAL_GRAPH_MODULE_NAME,
+    BLOCK_LIST_MODULE_NAME,
+];
+
+/// Event structs the indexer has a handler for; anything else in the monitored modules is ignored
+pub const INDEXED_EVENT_TYPES: &[&str] = &[
+    "ProfileCreatedEvent",
+    "ProfileUpdatedEvent",
+    "ProfileVerifiedEvent",
+    "PlatformCreatedEvent",
+    "PlatformUpdatedEvent",
+    "PlatformApprovalChangedEvent",
+    "ModeratorAddedEvent",
+    "ModeratorRemovedEvent",
+    "PlatformBlockedProfileEvent",
+    "PlatformUnblockedProfileEvent",
+    "PlatformJoinedEvent",
+    "PlatformLeftEvent",
+    "UserJoinedPlatformEvent",
+    "UserLeftPlatformEvent",
+    "FollowEvent",
+    "UnfollowEvent",
+    "BlockListCreatedEvent",
+    "BlockProfileEvent",
+    "UnblockProfileEvent",
+    "UserBlockEvent",
+    "UserUnblockEvent",
+    "ContentCreatedEvent",
+    "ContentInteractionEvent",
+    "ContentInteractionRemovedEvent",
+    "IPRegisteredEvent",
+];
+
 /// Common struct names
 pub const PROFILE_STRUCT_NAME: &str = "Profile";
 
+/// Version of the HTTP API, sent on every response as `X-API-Version`
+pub const API_VERSION: &str = "1";
+
 /// Set the MySocial package address
 pub fn set_mysocial_package_address(address: String) {
     MYSOCIAL_PACKAGE_ADDRESS.set(address).unwrap_or_else(|_| {
@@ -45,6 +87,20 @@ pub fn get_monitored_package_addresses() -> Vec<&'static str> {
     vec![get_mysocial_package_address()]
 }
 
+/// Whether `address` is one of the monitored packages
+///
+/// Addresses are compared as numbers, so `0x2` matches `0x0…02` and case doesn't matter.
+pub fn is_monitored_package(address: &str) -> bool {
+    let normalize = |address: &str| {
+        let hex = address.strip_prefix("0x").unwrap_or(address);
+        hex.trim_start_matches('0').to_ascii_lowercase()
+    };
+    let address = normalize(address);
+    get_monitored_package_addresses()
+        .into_iter()
+        .any(|monitored| normalize(monitored) == address)
+}
+
 /// Backward compatibility functions - these now just return the main package address
 
 /// Get the profile package address (same as the main package)
@@ -55,4 +111,22 @@ pub fn get_profile_package_address() -> &'static str {
 /// Get the platform package address (same as the main package)
 pub fn get_platform_package_address() -> &'static str {
     get_mysocial_package_address()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monitored_package_matches_regardless_of_padding_and_case() {
+        let short = DEFAULT_MYSOCIAL_PACKAGE_ADDRESS.to_uppercase().replacen("0X", "0x", 1);
+        assert!(is_monitored_package(DEFAULT_MYSOCIAL_PACKAGE_ADDRESS));
+        assert!(is_monitored_package(&short));
+        assert!(is_monitored_package(DEFAULT_MYSOCIAL_PACKAGE_ADDRESS.trim_start_matches("0x")));
+    }
+
+    #[test]
+    fn other_packages_are_not_monitored() {
+        assert!(!is_monitored_package("0x2"));
+        assert!(!is_monitored_package("0x0000000000000000000000000000000000000000000000000000000000000002"));
+    }
+}
@@ -3,18 +3,25 @@
 
 use anyhow::Result;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use mys_social_indexer::{
-    api,
-    blockchain::{BlockchainEventListener, ProfileEventListener, SocialGraphEventHandler, PlatformEventHandler, BlockListEventHandler},
+    api::{self, readiness::Readiness},
+    blockchain::{BlockchainEvent, BlockchainEventListener, CatchUpStatus, ProfileEventListener, SocialGraphEventHandler, PlatformEventHandler, BlockListEventHandler, ContentEventHandler, spawn_membership_reconciler, spawn_follower_snapshotter, set_ignored_addresses, publisher::publisher_from_config},
     config::Config,
     db,
+    events,
+    metrics,
     set_mysocial_package_address,
+    tasks::{install_panic_hook, spawn_named, ChannelDepth, ShutdownSummary, WorkerCheckpoint},
     get_mysocial_package_address,
 };
 
+/// How long shutdown waits to turn worker progress into checkpoint numbers
+const SHUTDOWN_CHECKPOINT_LOOKUP_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize tracing subscriber for logging
@@ -22,10 +29,19 @@ async fn main() -> Result<()> {
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
     
+    // Log panics with the name of the task that panicked
+    install_panic_hook();
+    
     info!("Starting MySocial indexer...");
     
     // Load config from environment
-    let config = Config::from_env();
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
     
     // Set MySocial package address from environment variable if provided
     let env_var_names = ["MYSOCIAL_PACKAGE_ADDRESS", "PROFILE_PACKAGE_ADDRESS", "PLATFORM_PACKAGE_ADDRESS"];
@@ -44,86 +60,193 @@ async fn main() -> Result<()> {
         info!("Using default MySocial package address: {}", get_mysocial_package_address());
     }
     
-    // Run database migrations
+    // Skip events from spam or test accounts
+    if !config.blockchain.ignored_addresses.is_empty() {
+        info!("Ignoring events from {} addresses", config.blockchain.ignored_addresses.len());
+    }
+    set_ignored_addresses(&config.blockchain.ignored_addresses);
+    
+    // Make sure event parsing still matches the expected event shapes
+    if !events::canary::run_schema_canary() {
+        error!("Schema canary detected event drift - see errors above");
+    }
+    
+    // Check the newest events on the node too, since drift only shows up in live events
+    spawn_named("schema-canary", {
+        let rpc_url = config.blockchain.rpc_url.clone();
+        async move {
+            if !events::canary::run_live_schema_canary(&rpc_url).await {
+                error!("Schema canary detected drift in live events - see errors above");
+            }
+        }
+    });
+    
+    // Set up database connection pool (checks that a connection can be made)
+    info!("Setting up database connection pool...");
+    let db_pool = db::setup_connection_pool(&config).await?;
+    
+    // Start the API server early so /ready can be probed; every other route answers 503 until
+    // migrations have run and the pool is verified
+    let readiness = Readiness::new();
+    let catch_up = CatchUpStatus::new();
+    let api_handle = spawn_named("api-server", {
+        let config = config.clone();
+        let api_db = db_pool.clone();
+        let readiness = readiness.clone();
+        let catch_up = catch_up.clone();
+        async move {
+            if let Err(e) = api::setup_api_server(&config, api_db, readiness, catch_up).await {
+                error!("API server error: {}", e);
+            }
+        }
+    });
+    
+    // Run database migrations off the async runtime, they use a blocking connection
     info!("Running database migrations...");
-    if let Err(e) = db::run_migrations(&config) {
+    let migration_config = config.clone();
+    let migrated = tokio::task::spawn_blocking(move || db::run_migrations(&migration_config))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|result| result);
+    if let Err(e) = migrated {
         error!("Failed to run migrations: {}", e);
         return Err(e);
     }
     
-    // Set up database connection pool
-    info!("Setting up database connection pool...");
-    let db_pool = db::setup_connection_pool(&config).await?;
+    // Make sure the pool still hands out working connections against the migrated schema
+    if let Err(e) = db_pool.get_connection().await {
+        error!("Database pool verification failed: {}", e);
+        return Err(e);
+    }
+    readiness.mark_ready();
+    info!("Migrations applied and database verified, API is ready");
+    
+    // Sample connection pool state for /metrics
+    metrics::spawn_pool_sampler(db_pool.clone());
+    
+    // Keep platform member counts in line with memberships
+    if config.database.membership_reconcile_interval_secs > 0 {
+        spawn_membership_reconciler(
+            db_pool.clone(),
+            std::time::Duration::from_secs(config.database.membership_reconcile_interval_secs),
+        );
+    }
+    
+    // Record daily follower counts for /profile/:profile_id/follower-trend
+    if config.database.follower_snapshot_interval_secs > 0 {
+        spawn_follower_snapshotter(
+            db_pool.clone(),
+            std::time::Duration::from_secs(config.database.follower_snapshot_interval_secs),
+        );
+    }
     
     // Create event channels
     let (profile_tx, profile_rx) = mpsc::channel(100);
     let (social_graph_tx, social_graph_rx) = mpsc::channel(100);
     let (platform_tx, platform_rx) = mpsc::channel(100);
     let (block_list_tx, block_list_rx) = mpsc::channel(100);
+    let (content_tx, content_rx) = mpsc::channel(100);
     
     // Create the blockchain event listener
-    let blockchain_listener = Arc::new(BlockchainEventListener::new(config.clone(), db_pool.clone()));
+    let blockchain_listener = Arc::new(
+        BlockchainEventListener::new(config.clone(), db_pool.clone()).with_catch_up(catch_up)
+    );
+    
+    // Keep weak handles on the channels so their depth can be reported on shutdown without
+    // holding them open; handlers still see their channel close and drain once the listener stops
+    let channel_monitors = vec![
+        ("profile", profile_tx.downgrade()),
+        ("social_graph", social_graph_tx.downgrade()),
+        ("platform", platform_tx.downgrade()),
+        ("block_list", block_list_tx.downgrade()),
+        ("content", content_tx.downgrade()),
+    ];
+    
+    // Handlers publish the events they apply to the event bus, if one is configured
+    let publisher = publisher_from_config(&config.event_bus);
     
     // Register event handlers
-    blockchain_listener.register_event_handler(profile_tx).await;
-    blockchain_listener.register_event_handler(social_graph_tx).await;
-    blockchain_listener.register_event_handler(platform_tx).await;
-    blockchain_listener.register_event_handler(block_list_tx).await;
+    blockchain_listener.register_event_handler("profile-worker", profile_tx).await;
+    blockchain_listener.register_event_handler("social-graph-worker", social_graph_tx).await;
+    blockchain_listener.register_event_handler("platform-worker", platform_tx).await;
+    blockchain_listener.register_event_handler("block-list-worker", block_list_tx).await;
+    blockchain_listener.register_event_handler("content-worker", content_tx).await;
     
     // Create and start profile event listener
     let mut profile_listener = ProfileEventListener::new(
         db_pool.clone(),
         profile_rx,
         "profile-worker".to_string(),
-    );
+    )
+    .with_source_events(config.blockchain.store_source_events)
+    .with_field_limits(config.blockchain.profile_field_limits)
+    .with_publisher(publisher.clone());
     
     // Create and start social graph event handler
     let mut social_graph_handler = SocialGraphEventHandler::new(
         db_pool.clone(),
         social_graph_rx,
         "social-graph-worker".to_string(),
-    );
+    )
+    .with_publisher(publisher.clone());
     
     // Create and start platform event handler
     let mut platform_handler = PlatformEventHandler::new(
         db_pool.clone(),
         platform_rx,
         "platform-worker".to_string(),
-    );
+    )
+    .with_concurrency(config.blockchain.platform_handler_concurrency)
+    .with_publisher(publisher.clone());
     
     // Create and start block list event handler
     let mut block_list_handler = BlockListEventHandler::new(
         db_pool.clone(),
         block_list_rx,
         "block-list-worker".to_string(),
-    );
+    )
+    .with_publisher(publisher.clone());
     
-    let profile_handle = tokio::spawn(async move {
+    // Create and start content event handler
+    let mut content_handler = ContentEventHandler::new(
+        db_pool.clone(),
+        content_rx,
+        "content-worker".to_string(),
+    )
+    .with_publisher(publisher);
+    
+    let profile_handle = spawn_named("profile-listener", async move {
         if let Err(e) = profile_listener.start().await {
             error!("Profile event listener error: {}", e);
         }
     });
     
-    let social_graph_handle = tokio::spawn(async move {
+    let social_graph_handle = spawn_named("social-graph-handler", async move {
         if let Err(e) = social_graph_handler.start().await {
             error!("Social graph handler error: {}", e);
         }
     });
     
-    let platform_handle = tokio::spawn(async move {
+    let platform_handle = spawn_named("platform-handler", async move {
         if let Err(e) = platform_handler.start().await {
             error!("Platform handler error: {}", e);
         }
     });
     
-    let block_list_handle = tokio::spawn(async move {
+    let block_list_handle = spawn_named("block-list-handler", async move {
         if let Err(e) = block_list_handler.start().await {
             error!("Block list handler error: {}", e);
         }
     });
     
+    let content_handle = spawn_named("content-handler", async move {
+        if let Err(e) = content_handler.start().await {
+            error!("Content handler error: {}", e);
+        }
+    });
+    
     // Start the blockchain event listener
-    let blockchain_handle = tokio::spawn({
+    let blockchain_handle = spawn_named("blockchain-listener", {
         let listener = blockchain_listener.clone();
         async move {
             if let Err(e) = listener.start().await {
@@ -132,36 +255,92 @@ async fn main() -> Result<()> {
         }
     });
     
-    // Start the API server
-    let api_handle = tokio::spawn(async move {
-        if let Err(e) = api::setup_api_server(&config, db_pool).await {
-            error!("API server error: {}", e);
-        }
-    });
-    
     // Wait for all tasks to complete (they should run indefinitely)
-    tokio::select! {
-        _ = profile_handle => {
-            error!("Profile event listener terminated unexpectedly");
-        }
-        _ = social_graph_handle => {
-            error!("Social graph handler terminated unexpectedly");
-        }
-        _ = platform_handle => {
-            error!("Platform handler terminated unexpectedly");
-        }
-        _ = block_list_handle => {
-            error!("Block list handler terminated unexpectedly");
-        }
-        _ = blockchain_handle => {
-            error!("Blockchain event listener terminated unexpectedly");
-        }
-        _ = api_handle => {
-            error!("API server terminated unexpectedly");
-        }
-    }
+    let terminated_task = tokio::select! {
+        _ = profile_handle => "Profile event listener",
+        _ = social_graph_handle => "Social graph handler",
+        _ = platform_handle => "Platform handler",
+        _ = block_list_handle => "Block list handler",
+        _ = content_handle => "Content handler",
+        _ = blockchain_handle => "Blockchain event listener",
+        _ = api_handle => "API server",
+    };
+    
+    error!("{} terminated unexpectedly", terminated_task);
+    log_shutdown_summary(terminated_task, &db_pool, &blockchain_listener, &channel_monitors).await;
     
     info!("Indexer terminated");
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Log the state the indexer was in when it stopped, to help diagnose restarts
+async fn log_shutdown_summary(
+    terminated_task: &'static str,
+    db_pool: &db::Database,
+    blockchain_listener: &BlockchainEventListener,
+    channel_monitors: &[(&'static str, mpsc::WeakSender<BlockchainEvent>)],
+) {
+    let workers = match db::load_indexer_progress(db_pool).await {
+        Ok(progress) => {
+            // Workers persist the timestamp of the last event they handled, 0 before the first
+            let timestamps: Vec<u64> = progress
+                .iter()
+                .filter(|worker| worker.last_checkpoint_processed > 0)
+                .map(|worker| worker.last_checkpoint_processed as u64)
+                .collect();
+            let mut checkpoints = match tokio::time::timeout(
+                SHUTDOWN_CHECKPOINT_LOOKUP_TIMEOUT,
+                blockchain_listener.checkpoints_at(&timestamps),
+            )
+            .await
+            {
+                Ok(Ok(checkpoints)) => Some(checkpoints.into_iter()),
+                Ok(Err(e)) => {
+                    warn!("Shutdown summary: failed to look up worker checkpoints: {}", e);
+                    None
+                }
+                Err(_) => {
+                    warn!("Shutdown summary: timed out looking up worker checkpoints");
+                    None
+                }
+            };
+            
+            Some(
+                progress
+                    .into_iter()
+                    .map(|worker| WorkerCheckpoint {
+                        last_checkpoint: match checkpoints.as_mut() {
+                            Some(checkpoints) if worker.last_checkpoint_processed > 0 => checkpoints.next(),
+                            _ => None,
+                        },
+                        worker: worker.id,
+                        last_event_timestamp_ms: worker.last_checkpoint_processed,
+                        last_processed_at: worker.last_processed_at,
+                    })
+                    .collect(),
+            )
+        }
+        Err(e) => {
+            warn!("Shutdown summary: failed to load worker progress: {}", e);
+            None
+        }
+    };
+    
+    // Every strong sender is gone once a channel has closed, so there is nothing to measure
+    let channels = channel_monitors
+        .iter()
+        .map(|(name, weak_sender)| ChannelDepth {
+            name: *name,
+            depth: weak_sender
+                .upgrade()
+                .filter(|sender| !sender.is_closed())
+                .map(|sender| (sender.max_capacity() - sender.capacity(), sender.max_capacity())),
+        })
+        .collect();
+    
+    let summary = ShutdownSummary { terminated_task, workers, channels };
+    for line in summary.lines() {
+        info!("Shutdown summary: {}", line);
+    }
+}
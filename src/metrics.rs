@@ -0,0 +1,272 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_gauge_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Encoder, Gauge,
+    Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry, TextEncoder,
+};
+use tracing::debug;
+
+use crate::db::Database;
+
+/// How often the connection pool state is sampled
+pub const POOL_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Registry for all indexer metrics, exported on `/metrics`
+pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+/// Maximum number of connections the pool may hold
+pub static DB_POOL_MAX_SIZE: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge_with_registry!(
+        "indexer_db_pool_max_size",
+        "Maximum number of database connections in the pool",
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Connections currently held by the pool
+pub static DB_POOL_SIZE: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge_with_registry!(
+        "indexer_db_pool_size",
+        "Number of database connections currently held by the pool",
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Idle connections ready to be checked out
+pub static DB_POOL_IDLE: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge_with_registry!(
+        "indexer_db_pool_idle",
+        "Number of idle database connections in the pool",
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Connections currently checked out
+pub static DB_POOL_IN_USE: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge_with_registry!(
+        "indexer_db_pool_in_use",
+        "Number of database connections currently checked out",
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Tasks waiting for a connection
+pub static DB_POOL_WAITING: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge_with_registry!(
+        "indexer_db_pool_waiting",
+        "Number of tasks waiting for a database connection",
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Time spent waiting to acquire a connection from the pool
+pub static DB_POOL_ACQUIRE_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram_with_registry!(
+        "indexer_db_pool_acquire_seconds",
+        "Time spent waiting to acquire a database connection",
+        vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0],
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Set to 1 for event types whose canary sample no longer parses cleanly
+pub static SCHEMA_DRIFT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec_with_registry!(
+        "indexer_schema_drift",
+        "Whether the schema canary detected missing fields for an event type",
+        &["event_type"],
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Applied events handed to the event bus publisher
+pub static EVENTS_PUBLISHED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter_with_registry!(
+        "indexer_events_published_total",
+        "Number of applied events handed to the event bus publisher",
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Events that failed to publish to the event bus
+pub static EVENT_PUBLISH_FAILURES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter_with_registry!(
+        "indexer_event_publish_failures_total",
+        "Number of events that failed to publish to the event bus",
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Events parsed by `parse_event`, by event type and the fallback layer that parsed them
+///
+/// `layer` is `direct`, `nested` (found under `fields`/`content`/...), `manual` (rebuilt
+/// field by field) or `failed`. A growing share of `manual` means the event schema has drifted.
+pub static PARSE_FALLBACK_DEPTH: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        "indexer_parse_fallback_depth_total",
+        "Number of events parsed at each fallback layer",
+        &["event_type", "layer"],
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Events skipped because they came from an ignored address, by handler
+pub static IGNORED_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        "indexer_ignored_events_total",
+        "Number of events skipped because they involve an ignored address",
+        &["handler"],
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Events skipped because a required id field held a placeholder, by handler and field
+pub static SENTINEL_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        "indexer_sentinel_events_total",
+        "Number of events skipped because a required id field was empty or a placeholder",
+        &["handler", "field"],
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Events dropped at the listener because their data couldn't be indexed, by reason
+pub static MALFORMED_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        "indexer_malformed_events_total",
+        "Number of events dead-lettered at the listener because their data was malformed",
+        &["reason"],
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Events that found a handler's channel full and had to wait, by handler
+pub static HANDLER_CHANNEL_FULL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        "indexer_handler_channel_full_total",
+        "Number of events that found a handler's channel full when fanned out",
+        &["handler"],
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Events dead-lettered because a handler didn't accept them, by handler and reason
+pub static HANDLER_UNDELIVERED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        "indexer_handler_undelivered_total",
+        "Number of events dead-lettered because a handler's channel stayed full or was closed",
+        &["handler", "reason"],
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// 1 while the listener has indexed everything up to the chain tip (less the confirmation lag)
+pub static CAUGHT_UP: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge_with_registry!(
+        "indexer_caught_up",
+        "Whether the indexer has caught up with the chain tip (1) or is behind (0)",
+        REGISTRY
+    )
+    .expect("metric can be registered")
+});
+
+/// Record the current pool state in the pool gauges
+pub fn record_pool_status(db: &Database) {
+    let status = db.pool.status();
+    let idle = status.available as f64;
+    let size = status.size as f64;
+    
+    DB_POOL_MAX_SIZE.set(status.max_size as f64);
+    DB_POOL_SIZE.set(size);
+    DB_POOL_IDLE.set(idle);
+    DB_POOL_IN_USE.set((size - idle).max(0.0));
+    DB_POOL_WAITING.set(status.waiting as f64);
+}
+
+/// Periodically sample the connection pool state
+pub fn spawn_pool_sampler(db: Arc<Database>) -> tokio::task::JoinHandle<()> {
+    crate::tasks::spawn_named("pool-sampler", async move {
+        let mut interval = tokio::time::interval(POOL_SAMPLE_INTERVAL);
+        loop {
+            interval.tick().await;
+            record_pool_status(&db);
+            debug!("Sampled database pool status: {:?}", db.pool.status());
+        }
+    })
+}
+
+/// Render all registered metrics in the Prometheus text format
+pub fn render() -> String {
+    let encoder = TextEncoder::new();
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&REGISTRY.gather(), &mut buffer) {
+        tracing::error!("Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_support::test_database;
+
+    #[test]
+    fn pool_metrics_are_registered() {
+        Lazy::force(&DB_POOL_MAX_SIZE);
+        Lazy::force(&DB_POOL_SIZE);
+        Lazy::force(&DB_POOL_IDLE);
+        Lazy::force(&DB_POOL_IN_USE);
+        Lazy::force(&DB_POOL_WAITING);
+        Lazy::force(&DB_POOL_ACQUIRE_SECONDS);
+        
+        let rendered = render();
+        for name in [
+            "indexer_db_pool_max_size",
+            "indexer_db_pool_size",
+            "indexer_db_pool_idle",
+            "indexer_db_pool_in_use",
+            "indexer_db_pool_waiting",
+            "indexer_db_pool_acquire_seconds_count",
+        ] {
+            assert!(rendered.contains(name), "{} missing from /metrics", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn pool_gauges_follow_the_pool() {
+        let Some(db) = test_database().await else { return };
+        let acquires = DB_POOL_ACQUIRE_SECONDS.get_sample_count();
+        
+        let conn = db.pool.get().await.unwrap();
+        record_pool_status(&db);
+        
+        assert!(DB_POOL_ACQUIRE_SECONDS.get_sample_count() > acquires);
+        assert_eq!(DB_POOL_MAX_SIZE.get(), db.pool.status().max_size as f64);
+        assert!(DB_POOL_SIZE.get() >= 1.0);
+        // The connection held above is checked out while the pool is sampled
+        assert!(DB_POOL_IN_USE.get() >= 1.0);
+        drop(conn);
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::schema::content;
+
+/// Content model
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = content)]
+pub struct Content {
+    pub id: String,
+    pub creator_id: String,
+    pub platform_id: String,
+    pub parent_id: Option<String>,
+    pub like_count: i32,
+    pub view_count: i32,
+    pub share_count: i32,
+    pub comment_count: i32,
+    pub has_ip_registered: bool,
+    pub created_at: NaiveDateTime,
+}
+
+/// DTO for inserting new content
+///
+/// Counters and the IP flag are written on insert only; they are maintained by later events,
+/// so re-indexing the creation must not reset them.
+#[derive(Debug, Insertable, AsChangeset, Serialize, Deserialize)]
+#[diesel(table_name = content)]
+pub struct NewContent {
+    pub id: String,
+    pub creator_id: String,
+    pub platform_id: String,
+    pub parent_id: Option<String>,
+    #[diesel(skip_update)]
+    pub like_count: i32,
+    #[diesel(skip_update)]
+    pub view_count: i32,
+    #[diesel(skip_update)]
+    pub share_count: i32,
+    #[diesel(skip_update)]
+    pub comment_count: i32,
+    #[diesel(skip_update)]
+    pub has_ip_registered: bool,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,35 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::schema::dead_letter_events;
+
+/// Event that a handler failed to process
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = dead_letter_events)]
+pub struct DeadLetterEvent {
+    pub id: i32,
+    pub event_id: Option<String>,
+    pub event_type: String,
+    pub worker_id: String,
+    pub event_data: serde_json::Value,
+    pub error_message: String,
+    pub retry_count: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// DTO for recording a failed event
+#[derive(Debug, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = dead_letter_events)]
+pub struct NewDeadLetterEvent {
+    pub event_id: Option<String>,
+    pub event_type: String,
+    pub worker_id: String,
+    pub event_data: serde_json::Value,
+    pub error_message: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -7,6 +7,8 @@ pub mod social_graph;
 pub mod platform;
 pub mod blocking;
 pub mod profile_events;
+pub mod dead_letter;
+pub mod content;
 
 pub use profile::*;
 pub use indexer::*;
@@ -21,7 +23,7 @@ pub use platform::{
     PlatformWithDetails, PlatformCreatedEvent, PlatformApprovalChangedEvent,
     PlatformUpdatedEvent, PlatformStatus, ModeratorAddedEvent, ModeratorRemovedEvent,
     UserJoinedPlatformEvent, UserLeftPlatformEvent,
-    NewPlatformMembership,
+    NewPlatformMembership, platform_accepts_members, parse_platform_date,
     PLATFORM_STATUS_DEVELOPMENT, PLATFORM_STATUS_ALPHA, PLATFORM_STATUS_BETA,
     PLATFORM_STATUS_LIVE, PLATFORM_STATUS_MAINTENANCE, PLATFORM_STATUS_SUNSET, PLATFORM_STATUS_SHUTDOWN
 };
@@ -30,4 +32,10 @@ pub use platform::{
 pub use blocking::*;
 
 // Export profile events models
-pub use profile_events::*;
\ No newline at end of file
+pub use profile_events::*;
+
+// Export dead letter models
+pub use dead_letter::*;
+
+// Export content models
+pub use content::*;
\ No newline at end of file
@@ -15,6 +15,36 @@ pub const PLATFORM_STATUS_MAINTENANCE: i16 = 4;
 pub const PLATFORM_STATUS_SUNSET: i16 = 5;
 pub const PLATFORM_STATUS_SHUTDOWN: i16 = 6;
 
+/// Whether a platform in this status still accepts new members
+pub fn platform_accepts_members(status: i16) -> bool {
+    status != PLATFORM_STATUS_SUNSET && status != PLATFORM_STATUS_SHUTDOWN
+}
+
+/// Parse a platform date string (epoch seconds/millis, RFC 3339 or `YYYY-MM-DD`)
+pub fn parse_platform_date(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    
+    if let Ok(timestamp) = value.parse::<i64>() {
+        // Treat large values as milliseconds
+        return if timestamp > 100_000_000_000 {
+            chrono::DateTime::from_timestamp_millis(timestamp).map(|dt| dt.naive_utc())
+        } else {
+            chrono::DateTime::from_timestamp(timestamp, 0).map(|dt| dt.naive_utc())
+        };
+    }
+    
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.naive_utc());
+    }
+    
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+}
+
 /// Platform model
 #[derive(Debug, Queryable, Selectable, Serialize, Deserialize)]
 #[diesel(table_name = platforms)]
@@ -38,6 +68,10 @@ pub struct Platform {
     pub is_approved: bool,
     pub approval_changed_at: Option<NaiveDateTime>,
     pub approved_by: Option<String>,
+    /// Members, recomputed by the membership reconciliation task
+    pub total_users_count: i64,
+    /// Profiles active in the last 30 days, recomputed by the membership reconciliation task
+    pub active_users_count: i64,
 }
 
 /// DTO for inserting a new platform
@@ -65,20 +99,22 @@ pub struct NewPlatform {
 }
 
 /// DTO for updating a platform
+///
+/// `None` leaves a column unchanged; for the nullable text columns `Some(None)` clears it.
 #[derive(Debug, AsChangeset, Serialize, Deserialize)]
 #[diesel(table_name = platforms)]
 pub struct UpdatePlatform {
     pub name: Option<String>,
     pub tagline: Option<String>,
-    pub description: Option<String>,
-    pub logo: Option<String>,
-    pub terms_of_service: Option<String>,
-    pub privacy_policy: Option<String>,
+    pub description: Option<Option<String>>,
+    pub logo: Option<Option<String>>,
+    pub terms_of_service: Option<Option<String>>,
+    pub privacy_policy: Option<Option<String>>,
     pub platform_names: Option<serde_json::Value>,
     pub links: Option<serde_json::Value>,
     pub status: Option<i16>,
-    pub release_date: Option<String>,
-    pub shutdown_date: Option<String>,
+    pub release_date: Option<Option<String>>,
+    pub shutdown_date: Option<Option<String>>,
     pub updated_at: Option<NaiveDateTime>,
     pub is_approved: Option<bool>,
     pub approval_changed_at: Option<NaiveDateTime>,
@@ -94,6 +130,9 @@ pub struct PlatformModerator {
     pub moderator_address: String,
     pub added_by: String,
     pub created_at: NaiveDateTime,
+    /// Numeric reference to `platforms.id`
+    #[serde(skip)]
+    pub platform_ref: Option<i32>,
 }
 
 /// DTO for inserting a new platform moderator
@@ -104,9 +143,12 @@ pub struct NewPlatformModerator {
     pub moderator_address: String,
     pub added_by: String,
     pub created_at: NaiveDateTime,
+    pub platform_ref: Option<i32>,
 }
 
 /// Platform blocked profile model
+///
+/// A row exists only while the block is in place; unblocking deletes it.
 #[derive(Debug, Queryable, Selectable, Serialize, Deserialize)]
 #[diesel(table_name = platform_blocked_profiles)]
 pub struct PlatformBlockedProfile {
@@ -115,6 +157,9 @@ pub struct PlatformBlockedProfile {
     pub profile_id: String,
     pub blocked_by: String,
     pub created_at: NaiveDateTime,
+    /// Numeric reference to `platforms.id`
+    #[serde(skip)]
+    pub platform_ref: Option<i32>,
 }
 
 /// DTO for inserting a new platform blocked profile
@@ -125,6 +170,7 @@ pub struct NewPlatformBlockedProfile {
     pub profile_id: String,
     pub blocked_by: String,
     pub created_at: NaiveDateTime,
+    pub platform_ref: Option<i32>,
 }
 
 /// Platform event model
@@ -174,6 +220,8 @@ pub struct PlatformWithDetails {
     pub is_approved: bool,
     pub approval_changed_at: Option<NaiveDateTime>,
     pub approved_by: Option<String>,
+    pub total_users_count: i64,
+    pub active_users_count: i64,
     // Related data
     pub moderator_count: i64,
     pub blocked_profiles_count: i64,
@@ -307,27 +355,132 @@ where
         })
 }
 
+/// A platform update; it may carry only the fields that changed
+///
+/// A field that is absent or `null` keeps the stored value. An empty string clears a
+/// nullable text field, but is ignored for `name` and `tagline`, which can't be empty.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PlatformUpdatedEvent {
     pub platform_id: String,
-    pub name: String,
-    pub tagline: String,
-    pub description: String,
-    pub terms_of_service: String,
-    pub privacy_policy: String,
-    pub platforms: Vec<String>,
-    pub links: Vec<String>,
-    pub status: PlatformStatus,
-    pub release_date: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub tagline: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub logo: Option<String>,
+    #[serde(default)]
+    pub terms_of_service: Option<String>,
+    #[serde(default)]
+    pub privacy_policy: Option<String>,
+    #[serde(default)]
+    pub platforms: Option<Vec<String>>,
+    #[serde(default)]
+    pub links: Option<Vec<String>>,
+    #[serde(default)]
+    pub status: Option<PlatformStatus>,
+    #[serde(default)]
+    pub release_date: Option<String>,
+    #[serde(default)]
     pub shutdown_date: Option<String>,
+    #[serde(default)]
     pub updated_at: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Change for a nullable text column: absent keeps it, empty clears it, anything else sets it
+fn text_change(value: &Option<String>) -> Option<Option<String>> {
+    value.as_ref().map(|text| Some(text.clone()).filter(|text| !text.is_empty()))
+}
+
+impl PlatformUpdatedEvent {
+    /// Changes to apply to a stored platform, leaving out what the event didn't provide
+    pub fn into_update(&self) -> UpdatePlatform {
+        UpdatePlatform {
+            name: self.name.clone().filter(|name| !name.is_empty()),
+            tagline: self.tagline.clone().filter(|tagline| !tagline.is_empty()),
+            description: text_change(&self.description),
+            logo: text_change(&self.logo),
+            terms_of_service: text_change(&self.terms_of_service),
+            privacy_policy: text_change(&self.privacy_policy),
+            platform_names: self.platforms.as_ref().map(|platforms| serde_json::to_value(platforms).unwrap_or_default()),
+            links: self.links.as_ref().map(|links| serde_json::to_value(links).unwrap_or_default()),
+            status: self.status.as_ref().map(|status| status.status as i16),
+            release_date: text_change(&self.release_date),
+            shutdown_date: text_change(&self.shutdown_date),
+            updated_at: Some(
+                chrono::DateTime::from_timestamp(self.updated_at as i64, 0)
+                    .filter(|_| self.updated_at > 0)
+                    .unwrap_or_else(chrono::Utc::now)
+                    .naive_utc(),
+            ),
+            is_approved: None, // Don't change approval status on regular update
+            approval_changed_at: None, // Don't change approval timestamp
+            approved_by: None, // Don't change approver
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct PlatformStatus {
     pub status: u8,
 }
 
+// On chain the status arrives either as the Move struct `{"status": n}` or as a bare
+// number; accept both, with the number as an integer or a numeric string
+impl<'de> Deserialize<'de> for PlatformStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        
+        fn status_code(value: &serde_json::Value) -> Option<u8> {
+            match value {
+                serde_json::Value::Number(n) => n.as_u64().and_then(|n| u8::try_from(n).ok()),
+                serde_json::Value::String(s) => s.trim().parse::<u8>().ok(),
+                serde_json::Value::Object(obj) => obj.get("status").and_then(status_code),
+                _ => None,
+            }
+        }
+        
+        let value = serde_json::Value::deserialize(deserializer)?;
+        status_code(&value)
+            .map(|status| PlatformStatus { status })
+            .ok_or_else(|| D::Error::custom(format!("invalid platform status: {}", value)))
+    }
+}
+
+impl PlatformStatus {
+    /// Status code as stored in the `platforms.status` column
+    pub fn code(&self) -> i16 {
+        self.status as i16
+    }
+}
+
+impl std::str::FromStr for PlatformStatus {
+    type Err = String;
+    
+    /// Parse a status name (e.g. `live`, case-insensitive) or its numeric code
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = match s.trim().to_lowercase().as_str() {
+            "development" => PLATFORM_STATUS_DEVELOPMENT,
+            "alpha" => PLATFORM_STATUS_ALPHA,
+            "beta" => PLATFORM_STATUS_BETA,
+            "live" => PLATFORM_STATUS_LIVE,
+            "maintenance" => PLATFORM_STATUS_MAINTENANCE,
+            "sunset" => PLATFORM_STATUS_SUNSET,
+            "shutdown" => PLATFORM_STATUS_SHUTDOWN,
+            other => match other.parse::<i16>() {
+                Ok(code) if (PLATFORM_STATUS_DEVELOPMENT..=PLATFORM_STATUS_SHUTDOWN).contains(&code) => code,
+                _ => return Err(format!("Unknown platform status: {}", s)),
+            },
+        };
+        
+        Ok(PlatformStatus { status: code as u8 })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModeratorAddedEvent {
     pub platform_id: String,
@@ -380,7 +533,83 @@ pub struct NewPlatformMembership {
     pub platform_id: String,
     pub profile_id: String,
     pub joined_at: NaiveDateTime,
+    pub platform_ref: Option<i32>,
 }
 
 // Note: PlatformRelationship, NewPlatformRelationship, and UpdatePlatformRelationship 
-// have been removed in favor of using platform_memberships table
\ No newline at end of file
+// have been removed in favor of using platform_memberships table
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sunset_and_shut_down_platforms_refuse_members() {
+        assert!(platform_accepts_members(PLATFORM_STATUS_LIVE));
+        assert!(platform_accepts_members(PLATFORM_STATUS_MAINTENANCE));
+        assert!(!platform_accepts_members(PLATFORM_STATUS_SUNSET));
+        assert!(!platform_accepts_members(PLATFORM_STATUS_SHUTDOWN));
+    }
+
+    #[test]
+    fn shutdown_dates_parse_in_every_supported_form() {
+        let midnight = chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        
+        assert_eq!(parse_platform_date("2024-05-01"), Some(midnight));
+        assert_eq!(parse_platform_date("2024-05-01T00:00:00Z"), Some(midnight));
+        assert_eq!(parse_platform_date("1714521600"), Some(midnight));
+        assert_eq!(parse_platform_date("1714521600000"), Some(midnight));
+        assert_eq!(parse_platform_date("  "), None);
+        assert_eq!(parse_platform_date("soon"), None);
+    }
+
+    fn status(json: serde_json::Value) -> Option<u8> {
+        serde_json::from_value::<PlatformStatus>(json).ok().map(|status| status.status)
+    }
+
+    #[test]
+    fn status_accepts_the_nested_and_bare_shapes() {
+        assert_eq!(status(serde_json::json!({ "status": 3 })), Some(3));
+        assert_eq!(status(serde_json::json!(3)), Some(3));
+        assert_eq!(status(serde_json::json!("3")), Some(3));
+        assert_eq!(status(serde_json::json!({ "status": "2" })), Some(2));
+    }
+
+    #[test]
+    fn status_rejects_values_that_are_not_a_code() {
+        assert_eq!(status(serde_json::json!(300)), None);
+        assert_eq!(status(serde_json::json!(-1)), None);
+        assert_eq!(status(serde_json::json!({ "state": 3 })), None);
+        assert_eq!(status(serde_json::json!(null)), None);
+    }
+
+    #[test]
+    fn update_event_reads_a_bare_status() {
+        let event: PlatformUpdatedEvent = serde_json::from_value(serde_json::json!({
+            "platform_id": "0xplatform",
+            "status": 4,
+        }))
+        .unwrap();
+        assert_eq!(event.into_update().status, Some(PLATFORM_STATUS_MAINTENANCE));
+    }
+
+    #[test]
+    fn update_event_only_changes_the_fields_it_carries() {
+        let event: PlatformUpdatedEvent = serde_json::from_value(serde_json::json!({
+            "platform_id": "0xplatform",
+            "name": "",
+            "description": "",
+            "terms_of_service": "https://example.com/tos",
+            "privacy_policy": null,
+        }))
+        .unwrap();
+        let update = event.into_update();
+        
+        assert_eq!(update.logo, None);
+        assert_eq!(update.privacy_policy, None);
+        assert_eq!(update.name, None);
+        assert_eq!(update.description, Some(None));
+        assert_eq!(update.terms_of_service, Some(Some("https://example.com/tos".to_string())));
+    }
+}
+
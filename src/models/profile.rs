@@ -43,9 +43,24 @@ pub struct Profile {
     pub github_username: Option<String>,
     // BlockList object address
     pub block_list_address: Option<String>,
+    // Verification status
+    pub is_verified: bool,
+    pub verified_at: Option<NaiveDateTime>,
+    pub verified_by: Option<String>,
+    /// Raw event that last wrote the row, when STORE_SOURCE_EVENTS is on
+    ///
+    /// Never serialized: it carries the sensitive fields the API gates.
+    #[serde(skip_serializing)]
+    pub source_event: Option<serde_json::Value>,
+    /// The profile has a photo, even if `profile_photo` isn't known yet
+    pub has_profile_photo: bool,
+    /// The profile has a cover photo, even if `cover_photo` isn't known yet
+    pub has_cover_photo: bool,
+    /// Last on-chain activity: a profile change, follow/unfollow or platform join
+    pub last_activity_at: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, Insertable, Serialize, Deserialize)]
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize)]
 #[diesel(table_name = profiles)]
 pub struct NewProfile {
     pub owner_address: String,
@@ -83,6 +98,15 @@ pub struct NewProfile {
     pub github_username: Option<String>,
     // BlockList object address
     pub block_list_address: Option<String>,
+    /// Raw event this row was built from, when STORE_SOURCE_EVENTS is on
+    #[serde(default, skip_serializing)]
+    pub source_event: Option<serde_json::Value>,
+    #[serde(default)]
+    pub has_profile_photo: bool,
+    #[serde(default)]
+    pub has_cover_photo: bool,
+    #[serde(default)]
+    pub last_activity_at: Option<NaiveDateTime>,
 }
 
 #[derive(Debug, AsChangeset, Serialize, Deserialize)]
@@ -116,4 +140,120 @@ pub struct UpdateProfile {
     pub github_username: Option<String>,
     // BlockList object address
     pub block_list_address: Option<String>,
-}
\ No newline at end of file
+    // Photo flags - only set when a photo is added or removed
+    pub has_profile_photo: Option<bool>,
+    pub has_cover_photo: Option<bool>,
+    /// Raw event making this update, when STORE_SOURCE_EVENTS is on
+    #[serde(default, skip_serializing)]
+    pub source_event: Option<serde_json::Value>,
+}
+/// Maximum lengths, in characters, of the free-text profile fields written from events
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ProfileFieldLimits {
+    pub display_name: usize,
+    pub bio: usize,
+    pub website: usize,
+    /// Applies to `profile_photo` and `cover_photo`
+    pub media_url: usize,
+}
+
+impl Default for ProfileFieldLimits {
+    fn default() -> Self {
+        Self {
+            display_name: 100,
+            bio: 2000,
+            website: 500,
+            media_url: 2048,
+        }
+    }
+}
+
+/// Strip control characters from `value` and cut it to `max_chars`, logging any truncation
+///
+/// Line breaks and tabs are kept when `multiline` is set, as they are meaningful in a bio.
+pub fn sanitize_field(field: &str, value: &mut Option<String>, max_chars: usize, multiline: bool) {
+    let Some(text) = value.as_mut() else {
+        return;
+    };
+    
+    if text.chars().any(char::is_control) {
+        text.retain(|c| !c.is_control() || (multiline && matches!(c, '\n' | '\t')));
+        tracing::debug!("Removed control characters from profile {}", field);
+    }
+    
+    if let Some((cut, _)) = text.char_indices().nth(max_chars) {
+        tracing::warn!(
+            "Truncating profile {} from {} to {} characters",
+            field,
+            text.chars().count(),
+            max_chars
+        );
+        text.truncate(cut);
+    }
+}
+
+impl NewProfile {
+    /// Sanitize and cap the free-text fields before the row is written
+    pub fn sanitize(&mut self, limits: &ProfileFieldLimits) {
+        sanitize_field("display_name", &mut self.display_name, limits.display_name, false);
+        sanitize_field("bio", &mut self.bio, limits.bio, true);
+        sanitize_field("website", &mut self.website, limits.website, false);
+        sanitize_field("profile_photo", &mut self.profile_photo, limits.media_url, false);
+        sanitize_field("cover_photo", &mut self.cover_photo, limits.media_url, false);
+    }
+}
+
+impl UpdateProfile {
+    /// Sanitize and cap the free-text fields before the update is applied
+    pub fn sanitize(&mut self, limits: &ProfileFieldLimits) {
+        sanitize_field("display_name", &mut self.display_name, limits.display_name, false);
+        sanitize_field("bio", &mut self.bio, limits.bio, true);
+        sanitize_field("website", &mut self.website, limits.website, false);
+        sanitize_field("profile_photo", &mut self.profile_photo, limits.media_url, false);
+        sanitize_field("cover_photo", &mut self.cover_photo, limits.media_url, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(fields: serde_json::Value) -> UpdateProfile {
+        serde_json::from_value(fields).unwrap()
+    }
+
+    #[test]
+    fn over_length_bio_is_truncated() {
+        let limits = ProfileFieldLimits { bio: 10, ..ProfileFieldLimits::default() };
+        let mut changes = update(serde_json::json!({ "bio": "ééééééééééééééé" }));
+        
+        changes.sanitize(&limits);
+        
+        assert_eq!(changes.bio.as_deref(), Some("éééééééééé"));
+    }
+
+    #[test]
+    fn control_characters_are_removed_from_display_name() {
+        let mut changes = update(serde_json::json!({
+            "display_name": "Al\u{0}ice\u{1b}[31m\n",
+            "bio": "line one\nline two\u{7}"
+        }));
+        
+        changes.sanitize(&ProfileFieldLimits::default());
+        
+        assert_eq!(changes.display_name.as_deref(), Some("Alice[31m"));
+        // A bio keeps its line breaks
+        assert_eq!(changes.bio.as_deref(), Some("line one\nline two"));
+    }
+
+    #[test]
+    fn fields_within_limits_are_left_alone() {
+        let mut changes = update(serde_json::json!({ "display_name": "Alice", "website": "https://example.com" }));
+        
+        changes.sanitize(&ProfileFieldLimits::default());
+        
+        assert_eq!(changes.display_name.as_deref(), Some("Alice"));
+        assert_eq!(changes.website.as_deref(), Some("https://example.com"));
+        assert!(changes.bio.is_none());
+    }
+}
@@ -1,10 +1,10 @@
 // Copyright (c) MySocial Team
 // SPDX-License-Identifier: Apache-2.0
 
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
-use crate::schema::{social_graph_relationships, social_graph_events};
+use crate::schema::{profile_follower_snapshots, social_graph_relationships, social_graph_events};
 
 /// Model for a social graph relationship (follow)
 #[derive(Debug, Queryable, Selectable, Serialize, Deserialize)]
@@ -73,10 +73,46 @@ pub struct FollowDetail {
     pub followed_at: NaiveDateTime,
 }
 
-/// Query parameters for paginating followers/following lists
+/// Query parameters for follow growth over a time window
 #[derive(Debug, Deserialize)]
-pub struct FollowsQuery {
-    pub limit: Option<i64>,
-    pub offset: Option<i64>,
-    pub page: Option<i64>,
-}
\ No newline at end of file
+pub struct GrowthQuery {
+    /// Window length such as `24h`, `7d` or `4w` (defaults to `7d`)
+    pub window: Option<String>,
+}
+
+/// Query parameters for a profile's daily follower trend
+#[derive(Debug, Deserialize)]
+pub struct FollowerTrendQuery {
+    /// First day, inclusive (`YYYY-MM-DD`); defaults to 29 days before `to`
+    pub from: Option<NaiveDate>,
+    /// Last day, inclusive (`YYYY-MM-DD`); defaults to today (UTC)
+    pub to: Option<NaiveDate>,
+}
+
+/// A profile's follower and following counts at the end of a day
+#[derive(Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = profile_follower_snapshots)]
+pub struct FollowerSnapshot {
+    pub date: NaiveDate,
+    pub followers_count: i32,
+    pub following_count: i32,
+}
+
+/// Parse a window such as `24h`, `7d` or `4w` into a duration
+pub fn parse_window(window: &str) -> Option<chrono::Duration> {
+    let window = window.trim();
+    let unit = window.chars().last()?;
+    let amount: i64 = window[..window.len() - unit.len_utf8()].parse().ok()?;
+    // Anything longer than a few years is certainly a typo
+    if amount <= 0 || amount > 100_000 {
+        return None;
+    }
+    
+    let hours = match unit {
+        'h' => amount,
+        'd' => amount * 24,
+        'w' => amount * 24 * 7,
+        _ => return None,
+    };
+    Some(chrono::Duration::hours(hours))
+}
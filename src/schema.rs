@@ -43,6 +43,16 @@ table! {
         github_username -> Nullable<Text>,
         // Block list address
         block_list_address -> Nullable<Varchar>,
+        // Verification status
+        is_verified -> Bool,
+        verified_at -> Nullable<Timestamp>,
+        verified_by -> Nullable<Varchar>,
+        // Raw event that last wrote the row, when STORE_SOURCE_EVENTS is on
+        source_event -> Nullable<Jsonb>,
+        // Photo flags from the event; true while the URL may still be missing
+        has_profile_photo -> Bool,
+        has_cover_photo -> Bool,
+        last_activity_at -> Nullable<Timestamp>,
     }
 }
 
@@ -106,6 +116,8 @@ table! {
         is_approved -> Bool,
         approval_changed_at -> Nullable<Timestamp>,
         approved_by -> Nullable<Varchar>,
+        total_users_count -> BigInt,
+        active_users_count -> BigInt,
     }
 }
 
@@ -117,6 +129,7 @@ table! {
         moderator_address -> Varchar,
         added_by -> Varchar,
         created_at -> Timestamp,
+        platform_ref -> Nullable<Integer>,  // platforms.id
     }
 }
 
@@ -128,6 +141,7 @@ table! {
         profile_id -> Varchar,
         blocked_by -> Varchar,
         created_at -> Timestamp,
+        platform_ref -> Nullable<Integer>,  // platforms.id
     }
 }
 
@@ -150,6 +164,7 @@ table! {
         platform_id -> Varchar,
         profile_id -> Varchar,
         joined_at -> Timestamp,
+        platform_ref -> Nullable<Integer>,  // platforms.id
     }
 }
 
@@ -178,6 +193,101 @@ table! {
     }
 }
 
+// Events that failed processing
+table! {
+    dead_letter_events (id) {
+        id -> Integer,
+        event_id -> Nullable<Varchar>,
+        event_type -> Varchar,
+        worker_id -> Varchar,
+        event_data -> Jsonb,
+        error_message -> Text,
+        retry_count -> Integer,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+// Profiles active on a platform per day, for distinct daily active counts
+table! {
+    platform_daily_active_profiles (platform_id, date, profile_id) {
+        platform_id -> Varchar,
+        date -> Date,
+        profile_id -> Varchar,
+    }
+}
+
+// Follower and following counts per profile per day
+table! {
+    profile_follower_snapshots (profile_id, date) {
+        profile_id -> Varchar,
+        date -> Date,
+        followers_count -> Integer,
+        following_count -> Integer,
+    }
+}
+
+// Content posted on platforms
+table! {
+    content (id) {
+        id -> Varchar,
+        creator_id -> Varchar,
+        platform_id -> Varchar,
+        parent_id -> Nullable<Varchar>,
+        like_count -> Integer,
+        view_count -> Integer,
+        share_count -> Integer,
+        comment_count -> Integer,
+        has_ip_registered -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+// Interactions (likes, views, shares) with content
+table! {
+    content_interactions (content_id, profile_id, interaction_type) {
+        content_id -> Varchar,
+        profile_id -> Varchar,
+        interaction_type -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
+// Registered IP, keyed by the content it covers
+table! {
+    ip_registrations (ip_id) {
+        ip_id -> Varchar,
+        registered_at -> Timestamp,
+    }
+}
+
+// Network-wide aggregates per day, rebuilt from the source tables
+table! {
+    daily_statistics (date) {
+        date -> Date,
+        new_profiles_count -> BigInt,
+        active_profiles_count -> BigInt,
+        new_content_count -> BigInt,
+        total_interactions_count -> BigInt,
+        new_follows_count -> BigInt,
+        new_ip_registrations_count -> BigInt,
+        updated_at -> Timestamp,
+    }
+}
+
+// Per-platform aggregates per day, rebuilt from the source tables
+table! {
+    platform_daily_statistics (platform_id, date) {
+        platform_id -> Varchar,
+        date -> Date,
+        new_users_count -> BigInt,
+        active_users_count -> BigInt,
+        content_created_count -> BigInt,
+        total_interactions_count -> BigInt,
+        updated_at -> Timestamp,
+    }
+}
+
 // Allow joining the tables if needed
 allow_tables_to_appear_in_same_query!(
     profiles,
@@ -191,4 +301,12 @@ allow_tables_to_appear_in_same_query!(
     platform_memberships,
     profiles_blocked,
     profile_events,
+    dead_letter_events,
+    platform_daily_active_profiles,
+    profile_follower_snapshots,
+    content,
+    content_interactions,
+    ip_registrations,
+    daily_statistics,
+    platform_daily_statistics,
 );
\ No newline at end of file
@@ -0,0 +1,194 @@
+// Copyright (c) MySocial Team
+// SPDX-License-Identifier: Apache-2.0
+
+use std::future::Future;
+
+use chrono::NaiveDateTime;
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+tokio::task_local! {
+    static TASK_NAME: &'static str;
+}
+
+/// Spawn a task that carries `name` in its tracing span and in panic logs
+pub fn spawn_named<F>(name: &'static str, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let span = tracing::info_span!("task", name);
+    tokio::spawn(TASK_NAME.scope(name, future.instrument(span)))
+}
+
+/// Name of the task running the caller, if it was started with `spawn_named`
+pub fn current_task_name() -> Option<&'static str> {
+    TASK_NAME.try_with(|name| *name).ok()
+}
+
+/// Log every panic with the name of the task it happened in, then run the default hook
+///
+/// A panic in a task started with `spawn_named("profile-listener", ...)` logs
+/// `task=profile-listener` with the panic message and location.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<String>()
+            .map(String::as_str)
+            .or_else(|| info.payload().downcast_ref::<&str>().copied())
+            .unwrap_or("unknown panic payload");
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        
+        tracing::error!(
+            task = current_task_name().unwrap_or("unnamed"),
+            %location,
+            "Panic: {}",
+            message
+        );
+        default_hook(info);
+    }));
+}
+
+/// Where a worker had got to when the indexer stopped
+#[derive(Debug, Clone)]
+pub struct WorkerCheckpoint {
+    pub worker: String,
+    /// Checkpoint holding the last event the worker handled, if it could be looked up
+    pub last_checkpoint: Option<u64>,
+    pub last_event_timestamp_ms: i64,
+    pub last_processed_at: NaiveDateTime,
+}
+
+/// Events still queued for a handler; `depth` is `(queued, capacity)`, or `None` once closed
+#[derive(Debug, Clone)]
+pub struct ChannelDepth {
+    pub name: &'static str,
+    pub depth: Option<(usize, usize)>,
+}
+
+/// State of the indexer when it stopped, to help diagnose restarts
+#[derive(Debug, Clone)]
+pub struct ShutdownSummary {
+    /// The `select!` branch in main that fired
+    pub terminated_task: &'static str,
+    /// `None` when the persisted progress couldn't be read
+    pub workers: Option<Vec<WorkerCheckpoint>>,
+    pub channels: Vec<ChannelDepth>,
+}
+
+impl ShutdownSummary {
+    /// One log line per fact, without the `Shutdown summary:` prefix
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("triggered by {}", self.terminated_task)];
+        
+        match &self.workers {
+            None => lines.push("worker progress could not be loaded".to_string()),
+            Some(workers) if workers.is_empty() => {
+                lines.push("no worker progress has been persisted".to_string())
+            }
+            Some(workers) => {
+                for worker in workers {
+                    let last_checkpoint = worker
+                        .last_checkpoint
+                        .map_or_else(|| "unknown".to_string(), |checkpoint| checkpoint.to_string());
+                    lines.push(format!(
+                        "worker={} last_checkpoint={} last_event_timestamp_ms={} last_processed_at={}",
+                        worker.worker, last_checkpoint, worker.last_event_timestamp_ms, worker.last_processed_at
+                    ));
+                }
+            }
+        }
+        
+        for channel in &self.channels {
+            lines.push(match channel.depth {
+                Some((queued, capacity)) => {
+                    format!("channel={} queued={} capacity={}", channel.name, queued, capacity)
+                }
+                None => format!("channel={} closed=true", channel.name),
+            });
+        }
+        
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn named_task_sees_its_name() {
+        let name = spawn_named("name-probe", async { current_task_name() }).await.unwrap();
+        
+        assert_eq!(name, Some("name-probe"));
+        assert_eq!(current_task_name(), None);
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn panic_in_named_task_logs_its_name() {
+        install_panic_hook();
+        
+        let result = spawn_named("panicking-probe", async { panic!("probe failure") }).await;
+        
+        assert!(result.unwrap_err().is_panic());
+        assert!(logs_contain("task=\"panicking-probe\""));
+        assert!(logs_contain("Panic: probe failure"));
+    }
+
+    fn worker(last_checkpoint: Option<u64>) -> WorkerCheckpoint {
+        WorkerCheckpoint {
+            worker: "profile-worker".to_string(),
+            last_checkpoint,
+            last_event_timestamp_ms: 1_700_000_000_000,
+            last_processed_at: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap().naive_utc(),
+        }
+    }
+
+    #[test]
+    fn summary_includes_the_last_checkpoint() {
+        let summary = ShutdownSummary {
+            terminated_task: "Blockchain event listener",
+            workers: Some(vec![worker(Some(48_213))]),
+            channels: vec![
+                ChannelDepth { name: "profile", depth: Some((3, 1000)) },
+                ChannelDepth { name: "content", depth: None },
+            ],
+        };
+        
+        assert_eq!(
+            summary.lines(),
+            vec![
+                "triggered by Blockchain event listener".to_string(),
+                "worker=profile-worker last_checkpoint=48213 last_event_timestamp_ms=1700000000000 last_processed_at=2023-11-14 22:13:20".to_string(),
+                "channel=profile queued=3 capacity=1000".to_string(),
+                "channel=content closed=true".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_says_when_the_checkpoint_is_unknown() {
+        let summary = ShutdownSummary {
+            terminated_task: "API server",
+            workers: Some(vec![worker(None)]),
+            channels: Vec::new(),
+        };
+        
+        assert!(summary.lines()[1].contains("last_checkpoint=unknown"));
+    }
+
+    #[test]
+    fn summary_without_progress_says_so() {
+        let empty = ShutdownSummary { terminated_task: "API server", workers: Some(Vec::new()), channels: Vec::new() };
+        let failed = ShutdownSummary { terminated_task: "API server", workers: None, channels: Vec::new() };
+        
+        assert_eq!(empty.lines()[1], "no worker progress has been persisted");
+        assert_eq!(failed.lines()[1], "worker progress could not be loaded");
+    }
+}